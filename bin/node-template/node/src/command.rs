@@ -56,8 +56,8 @@ impl SubstrateCli for Cli {
 		})
 	}
 
-	fn native_runtime_version(_: &Box<dyn ChainSpec>) -> &'static RuntimeVersion {
-		&node_template_runtime::VERSION
+	fn native_runtime_version(_: &dyn ChainSpec) -> Option<&'static RuntimeVersion> {
+		Some(&node_template_runtime::VERSION)
 	}
 }
 
@@ -84,7 +84,8 @@ pub fn run() -> sc_cli::Result<()> {
 			runner.async_run(|config| {
 				let PartialComponents { client, task_manager, ..}
 					= service::new_partial(&config)?;
-				Ok((cmd.run(client, config.database), task_manager))
+				let output = cmd.output()?;
+				Ok((cmd.run(client, config.database, output), task_manager))
 			})
 		},
 		Some(Subcommand::ExportState(cmd)) => {
@@ -92,7 +93,8 @@ pub fn run() -> sc_cli::Result<()> {
 			runner.async_run(|config| {
 				let PartialComponents { client, task_manager, ..}
 					= service::new_partial(&config)?;
-				Ok((cmd.run(client, config.chain_spec), task_manager))
+				let output = cmd.output()?;
+				Ok((cmd.run(client, config.chain_spec, output), task_manager))
 			})
 		},
 		Some(Subcommand::ImportBlocks(cmd)) => {
@@ -127,12 +129,17 @@ pub fn run() -> sc_cli::Result<()> {
 		},
 		None => {
 			let runner = cli.create_runner(&cli.run)?;
-			runner.run_node_until_exit(|config| async move {
+			// Stopping for a restart exits with the code its supervisor expects.
+			let exit_code = runner.run_node_until_exit_code(|config| async move {
 				match config.role {
 					Role::Light => service::new_light(config),
 					_ => service::new_full(config),
 				}
-			})
+			}, sc_cli::default_exit_code);
+			if exit_code != sc_cli::ExitCode::SUCCESS {
+				exit_code.exit();
+			}
+			Ok(())
 		}
 	}
 }
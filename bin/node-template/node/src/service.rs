@@ -139,6 +139,7 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		on_demand: None,
 		remote_blockchain: None,
 		backend, network_status_sinks, system_rpc_tx, config,
+		authoring_switch: None,
 	})?;
 
 	if role.is_authority() {
@@ -288,6 +289,7 @@ pub fn new_light(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		network,
 		network_status_sinks,
 		system_rpc_tx,
+		authoring_switch: None,
 	 })?;
 
 	 network_starter.start_network();
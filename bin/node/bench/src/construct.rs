@@ -271,6 +271,10 @@ impl sp_transaction_pool::TransactionPool for Transactions {
 		unimplemented!()
 	}
 
+	fn futures(&self) -> Vec<Arc<Self::InPoolTransaction>> {
+		unimplemented!()
+	}
+
 	fn remove_invalid(&self, _hashes: &[TxHash<Self>]) -> Vec<Arc<Self::InPoolTransaction>> {
 		Default::default()
 	}
@@ -56,24 +56,126 @@ pub enum Subcommand {
 	/// Sign a message, with a given (secret) key.
 	Sign(SignCmd),
 
+	/// Apply a single extrinsic on top of the state of a block and print its events and the
+	/// storage keys it changes, without writing anything to the database.
+	ApplyExtrinsic(sc_cli::ApplyExtrinsicCmd),
+
+	/// Report the storage values larger than a given size.
+	AuditStorage(sc_cli::AuditStorageCmd),
+
+	/// Print the BABE and GRANDPA authorities at a given block.
+	AuthoritySet(sc_cli::AuthoritySetCmd),
+
+	/// Measure how many signatures this machine can verify per second.
+	BenchCrypto(sc_cli::BenchCryptoCmd),
+
 	/// Build a chain specification.
 	BuildSpec(sc_cli::BuildSpecCmd),
 
+	/// Build the genesis state of the chain spec once and cache it into a file.
+	CacheGenesis(sc_cli::CacheGenesisCmd),
+
+	/// Print the genesis, best and finalized blocks of the database and its pruning mode.
+	ChainInfo(sc_cli::ChainInfoCmd),
+
 	/// Validate blocks.
 	CheckBlock(sc_cli::CheckBlockCmd),
 
+	/// Look for blocks or finality votes signed twice by the same authority in the recent blocks.
+	CheckEquivocations(sc_cli::CheckEquivocationsCmd),
+
+	/// Check that the node key and keystore aren't readable by other users.
+	CheckKeyPermissions(sc_cli::CheckKeyPermissionsCmd),
+
+	/// Check the consistency of the persistent storage of the offchain workers.
+	CheckOffchainDb(sc_cli::CheckOffchainDbCmd),
+
+	/// Print the version of the runtime stored in the database at the best block.
+	DbRuntimeVersion(sc_cli::DbRuntimeVersionCmd),
+
+	/// Decode a SCALE-encoded value of one of the types of the runtime.
+	Decode(sc_cli::DecodeCmd),
+
+	/// Compare the state of this node's database with another one at a given block.
+	DiffState(sc_cli::DiffStateCmd),
+
+	/// List the transactions in the pool of a running node.
+	DumpPool(sc_cli::DumpPoolCmd),
+
+	/// Export the balances of all accounts at a given block into a CSV file.
+	ExportBalances(sc_cli::ExportBalancesCmd),
+
 	/// Export blocks.
 	ExportBlocks(sc_cli::ExportBlocksCmd),
 
+	/// Export the finalized headers and justifications needed to follow the authority set
+	/// changes of the chain.
+	ExportFinalityChain(sc_cli::ExportFinalityChainCmd),
+
+	/// Export the header chain, optionally with justifications, into a file.
+	ExportHeaders(sc_cli::ExportHeadersCmd),
+
+	/// Export the number, hash and parent hash of the blocks of the chain into a CSV file.
+	ExportIndex(sc_cli::ExportIndexCmd),
+
 	/// Export the state of a given block into a chain spec.
 	ExportState(sc_cli::ExportStateCmd),
 
+	/// Run the node for a while and measure how far finality trails the best block.
+	FinalityLag(sc_cli::FinalityLagCmd),
+
+	/// Write a raw chain spec whose genesis is the state of the chain at a given block.
+	ForkSpec(sc_cli::ForkSpecCmd),
+
+	/// Print a systemd service file running this node.
+	GenSystemdUnit(sc_cli::GenSystemdUnitCmd),
+
 	/// Import blocks.
 	ImportBlocks(sc_cli::ImportBlocksCmd),
 
+	/// Run the node while sampling the memory used by its subsystems.
+	MemProfile(sc_cli::MemProfileCmd),
+
+	/// Compare the metadata of the on-chain runtime with the one of a wasm runtime blob.
+	MetadataDiff(sc_cli::MetadataDiffCmd),
+
+	/// Run the node and exit with an error when it falls behind the network for too long.
+	Monitor(sc_cli::MonitorCmd),
+
+	/// Print the peers a running node is connected to.
+	PeersSnapshot(sc_cli::PeersSnapshotCmd),
+
 	/// Remove the whole chain.
 	PurgeChain(sc_cli::PurgeChainCmd),
 
+	/// Import a sequence of possibly competing blocks and report the reorgs they cause.
+	ReplayReorg(sc_cli::ReplayReorgCmd),
+
 	/// Revert the chain to a previous state.
 	Revert(sc_cli::RevertCmd),
+
+	/// Check that exporting the blocks and importing them into a fresh database results in the
+	/// same chain.
+	RoundTripCheck(sc_cli::RoundTripCheckCmd),
+
+	/// List the WS RPC sessions of a running node, or close one of them.
+	RpcSessions(sc_cli::RpcSessionsCmd),
+
+	/// Print the upcoming slots of the chain and the ones a hypothetical validator would author.
+	SimulateAuthoring(sc_cli::SimulateAuthoringCmd),
+
+	/// Check that the on-chain wasm runtime can be loaded and executed.
+	SmokeRuntime(sc_cli::SmokeRuntimeCmd),
+
+	/// Generate the merkle proof of a storage key at a given block.
+	StorageProof(sc_cli::StorageProofCmd),
+
+	/// Check that the blocks of an export are in the database, unchanged.
+	VerifyAgainstExport(sc_cli::VerifyAgainstExportCmd),
+
+	/// Re-execute blocks and compare the events they produce against the stored ones.
+	VerifyEvents(sc_cli::VerifyEventsCmd),
+
+	/// Check that the genesis of the chain spec matches the one in the database.
+	VerifyGenesis(sc_cli::VerifyGenesisCmd),
 }
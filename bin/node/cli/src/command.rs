@@ -19,7 +19,7 @@
 use crate::{chain_spec, service, Cli, Subcommand};
 use node_executor::Executor;
 use node_runtime::{Block, RuntimeApi};
-use sc_cli::{Result, SubstrateCli, RuntimeVersion, Role, ChainSpec};
+use sc_cli::{Result, SubstrateCli, RuntimeVersion, Role, ChainSpec, CliConfiguration, Runner};
 use sc_service::PartialComponents;
 use crate::service::new_partial;
 
@@ -60,8 +60,12 @@ impl SubstrateCli for Cli {
 		})
 	}
 
-	fn native_runtime_version(_: &Box<dyn ChainSpec>) -> &'static RuntimeVersion {
-		&node_runtime::VERSION
+	fn builtin_chain_specs() -> Vec<&'static str> {
+		vec!["dev", "local", "", "fir", "flaming-fir", "staging"]
+	}
+
+	fn native_runtime_version(_: &dyn ChainSpec) -> Option<&'static RuntimeVersion> {
+		Some(&node_runtime::VERSION)
 	}
 }
 
@@ -71,13 +75,21 @@ pub fn run() -> Result<()> {
 
 	match &cli.subcommand {
 		None => {
-			let runner = cli.create_runner(&cli.run)?;
-			runner.run_node_until_exit(|config| async move {
+			let mut runner = cli.create_runner(&cli.run)?;
+			if let Some(path) = &cli.run.genesis_cache {
+				runner.use_genesis_cache::<Block>(path)?;
+			}
+			// Stopping for a restart exits with the code its supervisor expects.
+			let exit_code = runner.run_node_until_exit_code(|config| async move {
 				match config.role {
 					Role::Light => service::new_light(config),
 					_ => service::new_full(config),
 				}
-			})
+			}, sc_cli::default_exit_code);
+			if exit_code != sc_cli::ExitCode::SUCCESS {
+				exit_code.exit();
+			}
+			Ok(())
 		}
 		Some(Subcommand::Inspect(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
@@ -88,7 +100,21 @@ pub fn run() -> Result<()> {
 			if cfg!(feature = "runtime-benchmarks") {
 				let runner = cli.create_runner(cmd)?;
 
-				runner.sync_run(|config| cmd.run::<Block, Executor>(config))
+				// The benchmark runs on a thread of its own, so that a signal stops the command
+				// without waiting for the benchmark to return.
+				runner.async_run(|config| {
+					let task_manager = sc_service::TaskManager::new(config.task_executor.clone(), None)
+						.map_err(sc_service::Error::from)?;
+					let cmd = cmd.clone();
+					let (tx, rx) = futures::channel::oneshot::channel();
+					std::thread::spawn(move || {
+						let _ = tx.send(cmd.run::<Block, Executor>(config));
+					});
+					let future = async move {
+						rx.await.unwrap_or_else(|_| Err("The benchmark panicked".into()))
+					};
+					Ok((future, task_manager))
+				})
 			} else {
 				Err("Benchmarking wasn't enabled when building the node. \
 				You can enable it with `--features runtime-benchmarks`.".into())
@@ -98,46 +124,253 @@ pub fn run() -> Result<()> {
 		Some(Subcommand::Sign(cmd)) => cmd.run(),
 		Some(Subcommand::Verify(cmd)) => cmd.run(),
 		Some(Subcommand::Vanity(cmd)) => cmd.run(),
+		Some(Subcommand::ApplyExtrinsic(cmd)) => {
+			let runner = create_inspection_runner(&cli, cmd)?;
+			runner.async_run(|config| {
+				let PartialComponents { client, backend, task_manager, ..}
+					= new_partial(&config)?;
+				Ok((cmd.run(client, backend, describe_events), task_manager))
+			})
+		},
+		Some(Subcommand::AuditStorage(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.async_run(|config| {
+				let PartialComponents { client, task_manager, ..}
+					= new_partial(&config)?;
+				Ok((cmd.run(client, storage_prefixes()), task_manager))
+			})
+		},
+		Some(Subcommand::AuthoritySet(cmd)) => {
+			let runner = create_inspection_runner(&cli, cmd)?;
+			runner.async_run(|config| {
+				let PartialComponents { client, task_manager, ..}
+					= new_partial(&config)?;
+				Ok((cmd.run(client, authority_sets), task_manager))
+			})
+		},
+		Some(Subcommand::BenchCrypto(cmd)) => cmd.run(),
 		Some(Subcommand::BuildSpec(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
-			runner.sync_run(|config| cmd.run(config.chain_spec, config.network))
+			if cmd.needs_runtime() {
+				runner.async_run(|config| {
+					let task_manager = sc_service::TaskManager::new(config.task_executor.clone(), None)
+						.map_err(sc_service::Error::from)?;
+					let future = cmd.run_async(config.chain_spec, config.network, extend_chain_spec);
+					Ok((future, task_manager))
+				})
+			} else {
+				runner.sync_run(|config| cmd.run(config.chain_spec, config.network))
+			}
 		},
-		Some(Subcommand::CheckBlock(cmd)) => {
+		Some(Subcommand::CacheGenesis(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| cmd.run::<Block>(&*config.chain_spec))
+		},
+		Some(Subcommand::ChainInfo(cmd)) => {
+			let runner = create_inspection_runner(&cli, cmd)?;
 			runner.async_run(|config| {
+				let PartialComponents { client, task_manager, ..}
+					= new_partial(&config)?;
+				Ok((cmd.run(client, config.pruning.clone()), task_manager))
+			})
+		},
+		Some(Subcommand::CheckBlock(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.run_cmd::<Block, _, _, _>(cmd, |config| {
 				let PartialComponents { client, task_manager, import_queue, ..}
+					= new_partial(config)?;
+				Ok(((client, import_queue), task_manager))
+			})
+		},
+		Some(Subcommand::CheckEquivocations(cmd)) => {
+			let runner = create_inspection_runner(&cli, cmd)?;
+			runner.async_run(|config| {
+				let PartialComponents { client, backend, task_manager, keystore_container, ..}
 					= new_partial(&config)?;
-				Ok((cmd.run(client, import_queue), task_manager))
+				let keystore = keystore_container.sync_keystore();
+				Ok((cmd.run(
+					backend,
+					move |header| babe_author(&client, header),
+					grandpa_votes,
+					move |public| is_local_authority_key(&*keystore, public),
+				), task_manager))
 			})
 		},
-		Some(Subcommand::ExportBlocks(cmd)) => {
+		Some(Subcommand::CheckKeyPermissions(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| cmd.run(&config.network.node_key, &config.keystore))
+		},
+		Some(Subcommand::CheckOffchainDb(cmd)) => {
+			let runner = create_inspection_runner(&cli, cmd)?;
+			runner.async_run(|config| {
+				let PartialComponents { client, backend, task_manager, ..}
+					= new_partial(&config)?;
+				let known_keys = im_online_heartbeat_keys(&client)?;
+				Ok((cmd.run(backend, known_keys, describe_offchain_value), task_manager))
+			})
+		},
+		Some(Subcommand::DbRuntimeVersion(cmd)) => {
+			let runner = create_inspection_runner(&cli, cmd)?;
+			runner.async_run(|config| {
+				let native = Cli::native_runtime_version(&*config.chain_spec);
+				let PartialComponents { client, task_manager, ..}
+					= new_partial(&config)?;
+				Ok((cmd.run(client, native), task_manager))
+			})
+		},
+		Some(Subcommand::Decode(cmd)) => cmd.run(&decodable_types()),
+		Some(Subcommand::DiffState(cmd)) => {
+			let runner = create_inspection_runner(&cli, cmd)?;
+			runner.async_run(|mut config| {
+				let PartialComponents { client, mut task_manager, ..}
+					= new_partial(&config)?;
+				config.database = cmd.other_database(&config.database)?;
+				let PartialComponents { client: other, task_manager: other_task_manager, ..}
+					= new_partial(&config)?;
+				task_manager.add_child(other_task_manager);
+				Ok((cmd.run(client, other), task_manager))
+			})
+		},
+		Some(Subcommand::DumpPool(cmd)) => {
+			use node_primitives::{AccountId, Hash, Index};
+			use codec::Decode;
+
+			// The nonce check of `frame_system` tags its transactions with their sender and nonce.
+			cmd.run::<Hash>(|mut tag| <(AccountId, Index)>::decode(&mut tag).ok()
+				.map(|(sender, nonce)| format!("sender {} nonce {}", sender, nonce)))
+		},
+		Some(Subcommand::ExportBalances(cmd)) => {
+			use node_primitives::{AccountId, Balance, Index};
+
 			let runner = cli.create_runner(cmd)?;
+			runner.async_run(|config| {
+				let PartialComponents { client, task_manager, ..}
+					= new_partial(&config)?;
+				let balances = |_: &AccountId, info: frame_system::AccountInfo<
+					Index,
+					pallet_balances::AccountData<Balance>,
+				>| (info.data.free, info.data.reserved);
+				Ok((cmd.run(client, balances), task_manager))
+			})
+		},
+		Some(Subcommand::ExportBlocks(cmd)) => {
+			let runner = create_inspection_runner(&cli, cmd)?;
+			runner.run_cmd::<Block, _, _, _>(cmd, |config| {
+				let PartialComponents { client, task_manager, ..}
+					= new_partial(config)?;
+				Ok((client, task_manager))
+			})
+		},
+		Some(Subcommand::ExportFinalityChain(cmd)) => {
+			let runner = create_inspection_runner(&cli, cmd)?;
+			runner.async_run(|config| {
+				let PartialComponents { client, task_manager, ..}
+					= new_partial(&config)?;
+				Ok((cmd.run(client, grandpa_signals_change), task_manager))
+			})
+		},
+		Some(Subcommand::ExportHeaders(cmd)) => {
+			let runner = create_inspection_runner(&cli, cmd)?;
 			runner.async_run(|config| {
 				let PartialComponents { client, task_manager, ..}
 					= new_partial(&config)?;
 				Ok((cmd.run(client, config.database), task_manager))
 			})
 		},
+		Some(Subcommand::ExportIndex(cmd)) => {
+			let runner = create_inspection_runner(&cli, cmd)?;
+			runner.async_run(|config| {
+				let PartialComponents { client, task_manager, ..}
+					= new_partial(&config)?;
+				Ok((cmd.run(client), task_manager))
+			})
+		},
 		Some(Subcommand::ExportState(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
+			runner.async_run(|config| {
+				let PartialComponents { client, task_manager, ..}
+					= new_partial(&config)?;
+				let output = cmd.output()?;
+				Ok((cmd.run(client, config.chain_spec, output), task_manager))
+			})
+		},
+		Some(Subcommand::FinalityLag(cmd)) => {
+			let runner = cli.create_runner(&cmd.run)?;
+			runner.async_run(|config| {
+				let service::NewFullBase { client, task_manager, .. }
+					= service::new_full_base(config, |_, _| ())?;
+				Ok((cmd.run(client), task_manager))
+			})
+		},
+		Some(Subcommand::ForkSpec(cmd)) => {
+			let runner = create_inspection_runner(&cli, cmd)?;
 			runner.async_run(|config| {
 				let PartialComponents { client, task_manager, ..}
 					= new_partial(&config)?;
 				Ok((cmd.run(client, config.chain_spec), task_manager))
 			})
 		},
+		Some(Subcommand::GenSystemdUnit(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| cmd.run(&config))
+		},
 		Some(Subcommand::ImportBlocks(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
-			runner.async_run(|config| {
+			runner.run_cmd::<Block, _, _, _>(cmd, |config| {
 				let PartialComponents { client, task_manager, import_queue, ..}
+					= new_partial(config)?;
+				Ok(((client, import_queue), task_manager))
+			})
+		},
+		Some(Subcommand::MemProfile(cmd)) => {
+			use sp_transaction_pool::TransactionPool;
+
+			let runner = cli.create_runner(&cmd.run)?;
+			runner.async_run(|config| {
+				let service::NewFullBase { client, transaction_pool, task_manager, .. }
+					= service::new_full_base(config, |_, _| ())?;
+				let transaction_pool_size = move || {
+					let status = transaction_pool.status();
+					status.ready_bytes + status.future_bytes
+				};
+				Ok((cmd.run(client, transaction_pool_size), task_manager))
+			})
+		},
+		Some(Subcommand::MetadataDiff(cmd)) => {
+			let runner = create_inspection_runner(&cli, cmd)?;
+			runner.async_run(|config| {
+				let executor = node_executor::NativeExecutor::<Executor>::new(
+					config.wasm_method,
+					config.default_heap_pages,
+					config.max_runtime_instances,
+				);
+				let PartialComponents { client, task_manager, ..}
 					= new_partial(&config)?;
-				Ok((cmd.run(client, import_queue), task_manager))
+				Ok((cmd.run(client, executor, metadata_items), task_manager))
+			})
+		},
+		Some(Subcommand::Monitor(cmd)) => {
+			let runner = cli.create_runner(&cmd.run)?;
+			runner.async_run(|config| {
+				let service::NewFullBase { client, network_status_sinks, task_manager, .. }
+					= service::new_full_base(config, |_, _| ())?;
+				let network_status = network_status_sinks.status_stream(std::time::Duration::from_secs(1));
+				Ok((cmd.run(client, network_status), task_manager))
 			})
 		},
+		Some(Subcommand::PeersSnapshot(cmd)) => cmd.run(),
 		Some(Subcommand::PurgeChain(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.sync_run(|config| cmd.run(config.database))
 		},
+		Some(Subcommand::ReplayReorg(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.async_run(|config| {
+				let PartialComponents { client, task_manager, import_queue, ..}
+					= new_partial(&config)?;
+				Ok((cmd.run(client, import_queue), task_manager))
+			})
+		},
 		Some(Subcommand::Revert(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
 			runner.async_run(|config| {
@@ -146,5 +379,400 @@ pub fn run() -> Result<()> {
 				Ok((cmd.run(client, backend), task_manager))
 			})
 		},
+		Some(Subcommand::RoundTripCheck(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.async_run(|mut config| {
+				let PartialComponents { client, mut task_manager, ..}
+					= new_partial(&config)?;
+				config.database = sc_service::DatabaseConfig::in_memory();
+				let PartialComponents {
+					client: fresh,
+					task_manager: fresh_task_manager,
+					import_queue,
+					..
+				} = new_partial(&config)?;
+				task_manager.add_child(fresh_task_manager);
+				Ok((cmd.run(client, fresh, import_queue), task_manager))
+			})
+		},
+		Some(Subcommand::RpcSessions(cmd)) => cmd.run(),
+		Some(Subcommand::SimulateAuthoring(cmd)) => {
+			let runner = create_inspection_runner(&cli, cmd)?;
+			runner.sync_run(|config| {
+				let PartialComponents { client, .. } = new_partial(&config)?;
+				let babe = sc_consensus_babe::Config::get_or_compute(&*client)?;
+				let (epoch_start, authorities) = babe_schedule(&client, babe.epoch_length)?;
+				cmd.run(babe.slot_duration, babe.epoch_length, epoch_start, authorities)
+			})
+		},
+		Some(Subcommand::SmokeRuntime(cmd)) => {
+			let runner = create_inspection_runner(&cli, cmd)?;
+			runner.async_run(|config| {
+				let PartialComponents { client, task_manager, ..}
+					= new_partial(&config)?;
+				Ok((cmd.run(client), task_manager))
+			})
+		},
+		Some(Subcommand::StorageProof(cmd)) => {
+			let runner = create_inspection_runner(&cli, cmd)?;
+			runner.async_run(|config| {
+				let PartialComponents { client, task_manager, ..}
+					= new_partial(&config)?;
+				Ok((cmd.run(client), task_manager))
+			})
+		},
+		Some(Subcommand::VerifyAgainstExport(cmd)) => {
+			let runner = create_inspection_runner(&cli, cmd)?;
+			runner.async_run(|config| {
+				let PartialComponents { client, task_manager, ..}
+					= new_partial(&config)?;
+				Ok((cmd.run(client), task_manager))
+			})
+		},
+		Some(Subcommand::VerifyEvents(cmd)) => {
+			let runner = create_inspection_runner(&cli, cmd)?;
+			runner.async_run(|config| {
+				let PartialComponents { client, backend, task_manager, ..}
+					= new_partial(&config)?;
+				Ok((cmd.run(client, backend, describe_events), task_manager))
+			})
+		},
+		Some(Subcommand::VerifyGenesis(cmd)) => {
+			let runner = create_inspection_runner(&cli, cmd)?;
+			runner.async_run(|config| {
+				cmd.ensure_database_exists(&config.database)?;
+				let PartialComponents { client, task_manager, ..}
+					= new_partial(&config)?;
+				Ok((cmd.run(client, config.chain_spec), task_manager))
+			})
+		},
+	}
+}
+
+/// Create the runner of an inspection subcommand, reading from the database of the replica given
+/// with `--read-replica`, if any.
+fn create_inspection_runner<T: CliConfiguration>(cli: &Cli, command: &T) -> Result<Runner<Cli>> {
+	let mut runner = cli.create_runner(command)?;
+	if let Some(path) = command.read_replica()? {
+		runner.use_read_replica::<Block>(&path)?;
+	}
+	Ok(runner)
+}
+
+/// Extend a chain specification before `build-spec --extend` writes it.
+///
+/// The node doesn't add anything to its specifications yet.
+async fn extend_chain_spec(spec: Box<dyn ChainSpec>) -> Result<Box<dyn ChainSpec>> {
+	Ok(spec)
+}
+
+/// Prefix of the keys of the offchain storage under which `pallet_im_online` records the
+/// heartbeats sent by the local authorities.
+const IM_ONLINE_HEARTBEAT_PREFIX: &[u8] = b"parity/im-online-heartbeat/";
+
+/// The keys of the offchain storage that `pallet_im_online` may use to record the heartbeats of
+/// the current authorities, one for each authority index.
+fn im_online_heartbeat_keys(
+	client: &sc_service::TFullClient<Block, RuntimeApi, Executor>,
+) -> Result<Vec<Vec<u8>>> {
+	use codec::{Decode, Encode};
+	use sc_client_api::{StorageProvider, UsageProvider};
+	use sp_core::{hashing::twox_128, storage::StorageKey};
+	use sp_runtime::generic::BlockId;
+
+	let block = BlockId::Hash(client.usage_info().chain.best_hash);
+	let key = StorageKey([twox_128(b"ImOnline"), twox_128(b"Keys")].concat());
+	let authorities = match client.storage(&block, &key)? {
+		Some(keys) => Vec::<pallet_im_online::sr25519::AuthorityId>::decode(&mut &keys.0[..])?.len(),
+		None => 0,
+	};
+
+	Ok((0..authorities as u32)
+		.map(|index| [IM_ONLINE_HEARTBEAT_PREFIX, &index.encode()].concat())
+		.collect())
+}
+
+/// The slot that started the current BABE epoch and the number of authorities of the epoch,
+/// read from the storage of `pallet_babe` at the best block.
+fn babe_schedule(
+	client: &sc_service::TFullClient<Block, RuntimeApi, Executor>,
+	epoch_length: u64,
+) -> Result<(u64, u32)> {
+	use codec::Decode;
+	use sc_client_api::{StorageProvider, UsageProvider};
+	use sp_core::{hashing::twox_128, storage::StorageKey};
+	use sp_runtime::generic::BlockId;
+
+	let block = BlockId::Hash(client.usage_info().chain.best_hash);
+	let value = |item: &[u8]| -> Result<Option<Vec<u8>>> {
+		let key = StorageKey([twox_128(b"Babe"), twox_128(item)].concat());
+		Ok(client.storage(&block, &key)?.map(|data| data.0))
+	};
+
+	let genesis_slot = match value(b"GenesisSlot")? {
+		Some(slot) => u64::decode(&mut &slot[..])?,
+		None => 0,
+	};
+	let epoch_index = match value(b"EpochIndex")? {
+		Some(index) => u64::decode(&mut &index[..])?,
+		None => 0,
+	};
+	let authorities = match value(b"Authorities")? {
+		Some(authorities) =>
+			Vec::<(sp_consensus_babe::AuthorityId, u64)>::decode(&mut &authorities[..])?.len(),
+		None => 0,
+	};
+
+	Ok((genesis_slot + epoch_index * epoch_length, authorities as u32))
+}
+
+/// The BABE and GRANDPA authorities at `at`, read from the storage of the native runtime.
+fn authority_sets(
+	client: &sc_service::TFullClient<Block, RuntimeApi, Executor>,
+	at: &sp_runtime::generic::BlockId<Block>,
+) -> std::result::Result<Vec<sc_cli::AuthoritySet>, sc_service::Error> {
+	use codec::{Decode, Encode};
+	use grandpa_primitives::{AuthorityList, VersionedAuthorityList, GRANDPA_AUTHORITIES_KEY};
+	use sc_client_api::StorageProvider;
+	use sp_core::{hashing::twox_128, storage::StorageKey};
+
+	let value = |key: Vec<u8>| client.storage(at, &StorageKey(key))
+		.map(|value| value.map(|value| value.0));
+
+	let babe = match value([twox_128(b"Babe"), twox_128(b"Authorities")].concat())? {
+		Some(authorities) => Vec::<(sp_consensus_babe::AuthorityId, u64)>::decode(&mut &authorities[..])
+			.map_err(|e| format!("Invalid BABE authorities: {}", e))?,
+		None => Vec::new(),
+	};
+	let grandpa: AuthorityList = match value(GRANDPA_AUTHORITIES_KEY.to_vec())? {
+		Some(authorities) => VersionedAuthorityList::decode(&mut &authorities[..])
+			.map_err(|e| format!("Invalid GRANDPA authorities: {}", e))?
+			.into(),
+		None => Vec::new(),
+	};
+	let set_id = match value([twox_128(b"GrandpaFinality"), twox_128(b"CurrentSetId")].concat())? {
+		Some(set_id) => u64::decode(&mut &set_id[..])
+			.map_err(|e| format!("Invalid GRANDPA set id: {}", e))?,
+		None => 0,
+	};
+
+	Ok(vec![
+		sc_cli::AuthoritySet {
+			engine: "BABE",
+			set_id: None,
+			authorities: babe.iter().map(|(id, weight)| (id.encode(), *weight)).collect(),
+		},
+		sc_cli::AuthoritySet {
+			engine: "GRANDPA",
+			set_id: Some(set_id),
+			authorities: grandpa.iter().map(|(id, weight)| (id.encode(), *weight)).collect(),
+		},
+	])
+}
+
+/// The slot of `header` and the BABE key of its author, according to the authorities stored at
+/// its parent.
+fn babe_author(
+	client: &sc_service::TFullClient<Block, RuntimeApi, Executor>,
+	header: &node_primitives::Header,
+) -> std::result::Result<Option<(u64, Vec<u8>)>, sc_service::Error> {
+	use codec::{Decode, Encode};
+	use sc_client_api::StorageProvider;
+	use sp_core::{hashing::twox_128, storage::StorageKey};
+	use sp_runtime::generic::BlockId;
+
+	let pre_digest = match sc_consensus_babe::find_pre_digest::<Block>(header) {
+		Ok(pre_digest) => pre_digest,
+		Err(_) => return Ok(None),
+	};
+	let key = StorageKey([twox_128(b"Babe"), twox_128(b"Authorities")].concat());
+	let authorities = match client.storage(&BlockId::Hash(header.parent_hash), &key)? {
+		Some(authorities) => Vec::<(sp_consensus_babe::AuthorityId, u64)>::decode(&mut &authorities.0[..])
+			.map_err(|e| format!("Invalid BABE authorities: {}", e))?,
+		None => return Ok(None),
+	};
+
+	Ok(authorities.get(pre_digest.authority_index() as usize)
+		.map(|(authority, _)| (pre_digest.slot_number(), authority.encode())))
+}
+
+/// The `(round, voter, target)` triples of the precommits of a GRANDPA justification.
+fn grandpa_votes(
+	justification: &sp_runtime::Justification,
+) -> std::result::Result<Vec<(u64, Vec<u8>, node_primitives::Hash)>, sc_service::Error> {
+	use codec::{Decode, Encode};
+
+	let justification = grandpa::GrandpaJustification::<Block>::decode(&mut &justification[..])
+		.map_err(|e| format!("Invalid GRANDPA justification: {}", e))?;
+	Ok(justification.precommits()
+		.map(|(voter, target)| (justification.round(), voter.encode(), *target))
+		.collect())
+}
+
+/// Whether `header` schedules or forces a change of the GRANDPA authority set.
+fn grandpa_signals_change(header: &node_primitives::Header) -> bool {
+	use grandpa_primitives::{ConsensusLog, GRANDPA_ENGINE_ID};
+	use sp_runtime::generic::OpaqueDigestItemId;
+
+	header.digest.logs().iter().any(|log| {
+		match log.try_to(OpaqueDigestItemId::Consensus(&GRANDPA_ENGINE_ID)) {
+			Some(ConsensusLog::<node_primitives::BlockNumber>::ScheduledChange(_)) |
+			Some(ConsensusLog::<node_primitives::BlockNumber>::ForcedChange(_, _)) => true,
+			_ => false,
+		}
+	})
+}
+
+/// Whether `public` is a BABE or GRANDPA key of the local keystore.
+fn is_local_authority_key(keystore: &dyn sp_keystore::SyncCryptoStore, public: &[u8]) -> bool {
+	use sp_core::crypto::key_types::{BABE, GRANDPA};
+
+	keystore.has_keys(&[(public.to_vec(), BABE)]) || keystore.has_keys(&[(public.to_vec(), GRANDPA)])
+}
+
+/// Decode the values written to the offchain storage by the offchain workers of the runtime.
+fn describe_offchain_value(key: &[u8], mut value: &[u8]) -> Option<std::result::Result<String, String>> {
+	use codec::Decode;
+	use node_primitives::BlockNumber;
+
+	if !key.starts_with(IM_ONLINE_HEARTBEAT_PREFIX) {
+		return None;
+	}
+	let mut index = &key[IM_ONLINE_HEARTBEAT_PREFIX.len()..];
+	let index = u32::decode(&mut index).ok().filter(|_| index.is_empty())?;
+
+	// The `HeartbeatStatus` of `pallet_im_online`: the session index and the block at which
+	// the heartbeat was sent.
+	Some(<(u32, BlockNumber)>::decode(&mut value)
+		.map_err(|e| format!("invalid heartbeat status: {}", e))
+		.and_then(|status| match value.is_empty() {
+			true => Ok(status),
+			false => Err(format!("{} trailing byte(s) after the heartbeat status", value.len())),
+		})
+		.map(|(session, sent_at)| format!(
+			"heartbeat of authority {} for session {} sent at #{}",
+			index,
+			session,
+			sent_at,
+		)))
+}
+
+/// The types of the native runtime that the `decode` subcommand can decode.
+fn decodable_types() -> Vec<(&'static str, sc_cli::DecodeFn)> {
+	use node_primitives::{AccountId, Balance, BlockNumber, Hash, Index};
+	use node_runtime::{Call, Event, Header, UncheckedExtrinsic};
+	use sc_cli::{decode_to_debug, decode_to_json};
+
+	vec![
+		("AccountId", decode_to_json::<AccountId>),
+		("AccountInfo", decode_to_debug::<
+			frame_system::AccountInfo<Index, pallet_balances::AccountData<Balance>>,
+		>),
+		("Balance", decode_to_debug::<Balance>),
+		("Block", decode_to_json::<Block>),
+		("BlockNumber", decode_to_json::<BlockNumber>),
+		("Call", decode_to_debug::<Call>),
+		("Event", decode_to_debug::<Event>),
+		("EventRecords", decode_to_debug::<Vec<frame_system::EventRecord<Event, Hash>>>),
+		("Extrinsic", decode_to_debug::<UncheckedExtrinsic>),
+		("Hash", decode_to_json::<Hash>),
+		("Header", decode_to_json::<Header>),
+		("Index", decode_to_json::<Index>),
+	]
+}
+
+/// Decode the events of a block of the native runtime, one line per event.
+fn describe_events(mut encoded: &[u8]) -> Option<Vec<String>> {
+	use codec::Decode;
+	use node_primitives::Hash;
+	use node_runtime::Event;
+
+	Vec::<frame_system::EventRecord<Event, Hash>>::decode(&mut encoded)
+		.ok()
+		.map(|records| records.iter().map(|record| format!("{:?}", record)).collect())
+}
+
+/// Decode SCALE encoded runtime metadata into the items compared by `metadata-diff`.
+fn metadata_items(mut encoded: &[u8]) -> std::result::Result<sc_cli::MetadataItems, String> {
+	use codec::Decode;
+	use frame_support::metadata::{
+		DecodeDifferent, RuntimeMetadata, RuntimeMetadataPrefixed, StorageEntryType,
+	};
+
+	fn decoded<E, D>(value: &DecodeDifferent<E, D>) -> std::result::Result<&D, String> {
+		match value {
+			DecodeDifferent::Decoded(value) => Ok(value),
+			DecodeDifferent::Encode(_) => Err("Metadata is not decoded".into()),
+		}
+	}
+
+	let modules = match RuntimeMetadataPrefixed::decode(&mut encoded).map_err(|e| e.to_string())?.1 {
+		RuntimeMetadata::V12(metadata) => metadata.modules,
+		_ => return Err("Unsupported metadata version".into()),
+	};
+
+	let mut pallets = sc_cli::MetadataItems::new();
+	for module in decoded(&modules)? {
+		let mut items = std::collections::BTreeMap::new();
+
+		for call in module.calls.as_ref().map(decoded).transpose()?.into_iter().flatten() {
+			let arguments = decoded(&call.arguments)?.iter()
+				.map(|argument| Ok(format!("{}: {}", decoded(&argument.name)?, decoded(&argument.ty)?)))
+				.collect::<std::result::Result<Vec<_>, String>>()?;
+			items.insert(format!("call {}", decoded(&call.name)?), format!("({})", arguments.join(", ")));
+		}
+		for event in module.event.as_ref().map(decoded).transpose()?.into_iter().flatten() {
+			let arguments = decoded(&event.arguments)?.join(", ");
+			items.insert(format!("event {}", decoded(&event.name)?), format!("({})", arguments));
+		}
+		for error in decoded(&module.errors)? {
+			items.insert(format!("error {}", decoded(&error.name)?), String::new());
+		}
+		for constant in decoded(&module.constants)? {
+			items.insert(format!("constant {}", decoded(&constant.name)?), decoded(&constant.ty)?.clone());
+		}
+		if let Some(storage) = module.storage.as_ref().map(decoded).transpose()? {
+			for entry in decoded(&storage.entries)? {
+				let ty = match &entry.ty {
+					StorageEntryType::Plain(value) => decoded(value)?.clone(),
+					StorageEntryType::Map { hasher, key, value, .. } =>
+						format!("map {:?}({}) => {}", hasher, decoded(key)?, decoded(value)?),
+					StorageEntryType::DoubleMap { hasher, key1, key2_hasher, key2, value } => format!(
+						"double map {:?}({}), {:?}({}) => {}",
+						hasher, decoded(key1)?, key2_hasher, decoded(key2)?, decoded(value)?,
+					),
+				};
+				items.insert(
+					format!("storage {}", decoded(&entry.name)?),
+					format!("{:?} {}", entry.modifier, ty),
+				);
+			}
+		}
+
+		pallets.insert(decoded(&module.name)?.clone(), items);
+	}
+
+	Ok(pallets)
+}
+
+/// The storage prefixes of the modules of the native runtime.
+fn storage_prefixes() -> Vec<String> {
+	use frame_support::metadata::{DecodeDifferent, ModuleMetadata, RuntimeMetadata, StorageMetadata};
+
+	let prefix = |storage: &StorageMetadata| match &storage.prefix {
+		DecodeDifferent::Encode(prefix) => prefix.to_string(),
+		DecodeDifferent::Decoded(prefix) => prefix.clone(),
+	};
+	let storage_prefix = |module: &ModuleMetadata| match module.storage.as_ref()? {
+		DecodeDifferent::Encode(storage) => Some(prefix(&(storage.0)())),
+		DecodeDifferent::Decoded(storage) => Some(prefix(storage)),
+	};
+
+	match node_runtime::Runtime::metadata().1 {
+		RuntimeMetadata::V12(metadata) => match metadata.modules {
+			DecodeDifferent::Encode(modules) => modules.iter().filter_map(storage_prefix).collect(),
+			DecodeDifferent::Decoded(modules) => modules.iter().filter_map(storage_prefix).collect(),
+		},
+		_ => Vec::new(),
 	}
 }
@@ -85,7 +85,7 @@ pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponen
 
 	let inherent_data_providers = sp_inherents::InherentDataProviders::new();
 
-	let import_queue = sc_consensus_babe::import_queue(
+	let mut import_queue = sc_consensus_babe::import_queue(
 		babe_link.clone(),
 		block_import.clone(),
 		Some(Box::new(justification_import)),
@@ -95,7 +95,9 @@ pub fn new_partial(config: &Configuration) -> Result<sc_service::PartialComponen
 		&task_manager.spawn_handle(),
 		config.prometheus_registry(),
 		sp_consensus::CanAuthorWithNativeVersion::new(client.executor().clone()),
+		config.future_block_policy,
 	)?;
+	import_queue.set_max_pending_justifications(config.max_pending_justifications);
 
 	let import_setup = (block_import, grandpa_link, babe_link);
 
@@ -180,6 +182,7 @@ pub fn new_full_base(
 
 	config.network.notifications_protocols.push(grandpa::GRANDPA_PROTOCOL_NAME.into());
 
+	let import_throttle = import_queue.import_throttle();
 	let (network, network_status_sinks, system_rpc_tx, network_starter) =
 		sc_service::build_network(sc_service::BuildNetworkParams {
 			config: &config,
@@ -191,6 +194,17 @@ pub fn new_full_base(
 			block_announce_validator_builder: None,
 		})?;
 
+	if let Some(max_blocks_per_sec) = config.max_catch_up_import_rate {
+		task_manager.spawn_handle().spawn(
+			"import-throttle",
+			sc_service::throttle_import_until_synced(
+				network_status_sinks.status_stream(std::time::Duration::from_secs(1)),
+				import_throttle,
+				max_blocks_per_sec,
+			),
+		);
+	}
+
 	if config.offchain_worker.enabled {
 		sc_service::build_offchain_workers(
 			&config, backend.clone(), task_manager.spawn_handle(), client.clone(), network.clone(),
@@ -199,12 +213,14 @@ pub fn new_full_base(
 
 	let role = config.role.clone();
 	let force_authoring = config.force_authoring;
+	let min_peers_before_authoring = config.min_peers_before_authoring;
 	let backoff_authoring_blocks =
 		Some(sc_consensus_slots::BackoffAuthoringOnFinalizedHeadLagging::default());
 	let name = config.network.node_name.clone();
 	let enable_grandpa = !config.disable_grandpa;
 	let prometheus_registry = config.prometheus_registry().cloned();
 	let telemetry_connection_sinks = sc_service::TelemetryConnectionSinks::default();
+	let authoring_switch = sp_consensus::AuthoringSwitch::default();
 
 	sc_service::spawn_tasks(sc_service::SpawnTasksParams {
 		config,
@@ -220,6 +236,7 @@ pub fn new_full_base(
 		telemetry_connection_sinks: telemetry_connection_sinks.clone(),
 		network_status_sinks: network_status_sinks.clone(),
 		system_rpc_tx,
+		authoring_switch: Some(authoring_switch.clone()),
 	})?;
 
 	let (block_import, grandpa_link, babe_link) = import_setup;
@@ -234,8 +251,10 @@ pub fn new_full_base(
 			prometheus_registry.as_ref(),
 		);
 
-		let can_author_with =
-			sp_consensus::CanAuthorWithNativeVersion::new(client.executor().clone());
+		let can_author_with = sp_consensus::PausableAuthoring::new(
+			sp_consensus::CanAuthorWithNativeVersion::new(client.executor().clone()),
+			authoring_switch,
+		);
 
 		let babe_config = sc_consensus_babe::BabeParams {
 			keystore: keystore_container.sync_keystore(),
@@ -252,7 +271,13 @@ pub fn new_full_base(
 		};
 
 		let babe = sc_consensus_babe::start_babe(babe_config)?;
-		task_manager.spawn_essential_handle().spawn_blocking("babe-proposer", babe);
+		let babe_network = network.clone();
+		task_manager.spawn_essential_handle().spawn_blocking("babe-proposer", async move {
+			if let Some(barrier) = min_peers_before_authoring {
+				sc_service::wait_for_authoring_peers(&*babe_network, barrier).await;
+			}
+			babe.await
+		});
 	}
 
 	// Spawn authority discovery module.
@@ -373,7 +398,7 @@ pub fn new_light_base(mut config: Configuration) -> Result<(
 
 	let inherent_data_providers = sp_inherents::InherentDataProviders::new();
 
-	let import_queue = sc_consensus_babe::import_queue(
+	let mut import_queue = sc_consensus_babe::import_queue(
 		babe_link,
 		babe_block_import,
 		Some(Box::new(justification_import)),
@@ -383,7 +408,9 @@ pub fn new_light_base(mut config: Configuration) -> Result<(
 		&task_manager.spawn_handle(),
 		config.prometheus_registry(),
 		sp_consensus::NeverCanAuthor,
+		config.future_block_policy,
 	)?;
+	import_queue.set_max_pending_justifications(config.max_pending_justifications);
 
 	let (network, network_status_sinks, system_rpc_tx, network_starter) =
 		sc_service::build_network(sc_service::BuildNetworkParams {
@@ -424,6 +451,7 @@ pub fn new_light_base(mut config: Configuration) -> Result<(
 			network: network.clone(),
 			telemetry_connection_sinks: sc_service::TelemetryConnectionSinks::default(),
 			task_manager: &mut task_manager,
+			authoring_switch: None,
 		})?;
 
 	Ok((task_manager, rpc_handlers, client, network, transaction_pool))
@@ -174,6 +174,8 @@ arg_enum! {
 		RocksDb,
 		// ParityDb. https://github.com/paritytech/parity-db/
 		ParityDb,
+		// In-memory database, lost when the node shuts down.
+		Memory,
 	}
 }
 
@@ -189,6 +191,16 @@ arg_enum! {
 	}
 }
 
+arg_enum! {
+	/// What the import queue does with the blocks too far in the future.
+	#[allow(missing_docs)]
+	#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+	pub enum FutureBlockAction {
+		Reject,
+		Queue,
+	}
+}
+
 /// Default value for the `--execution-syncing` parameter.
 pub const DEFAULT_EXECUTION_SYNCING: ExecutionStrategy = ExecutionStrategy::NativeElseWasm;
 /// Default value for the `--execution-import-block` parameter.
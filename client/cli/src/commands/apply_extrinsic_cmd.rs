@@ -0,0 +1,124 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	CliConfiguration, commands::utils::decode_hex, error,
+	params::{BlockNumberOrHash, DatabaseParams, PruningParams, SharedParams},
+};
+use log::info;
+use parity_scale_codec::Decode;
+use sc_client_api::backend::Backend;
+use sc_service::chain_ops::apply_extrinsic;
+use sp_api::{ApiExt, Core, ProvideRuntimeApi};
+use sp_block_builder::BlockBuilder;
+use sp_blockchain::HeaderBackend;
+use sp_core::{hashing::twox_128, hexdisplay::HexDisplay};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use std::{fmt::Debug, str::FromStr, sync::Arc};
+use structopt::StructOpt;
+
+/// The `apply-extrinsic` command used to apply a single extrinsic on top of the state of a block
+/// and print its effects.
+#[derive(Debug, StructOpt)]
+pub struct ApplyExtrinsicCmd {
+	/// Block hash or number on top of which the extrinsic is applied.
+	#[structopt(long, value_name = "HASH or NUMBER")]
+	pub at: BlockNumberOrHash,
+
+	/// The encoded extrinsic, in hex.
+	#[structopt(long, value_name = "HEX")]
+	pub extrinsic: String,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl ApplyExtrinsicCmd {
+	/// Run the `apply-extrinsic` command
+	///
+	/// The extrinsic is applied in a new block that is never imported. The events it deposits
+	/// in `System::Events` are decoded by `describe`, one line per event; when it returns `None`,
+	/// only the encoded events are printed along with the other changed keys.
+	pub async fn run<B, BA, C>(
+		&self,
+		client: Arc<C>,
+		backend: Arc<BA>,
+		describe: impl Fn(&[u8]) -> Option<Vec<String>>,
+	) -> error::Result<()>
+	where
+		B: BlockT,
+		BA: Backend<B>,
+		C: HeaderBackend<B> + ProvideRuntimeApi<B>,
+		C::Api: Core<B, Error = sp_blockchain::Error>
+			+ BlockBuilder<B, Error = sp_blockchain::Error>
+			+ ApiExt<B, StateBackend = BA::State>,
+		B::Hash: FromStr,
+		<B::Hash as FromStr>::Err: Debug,
+		<<B::Header as HeaderT>::Number as FromStr>::Err: Debug,
+	{
+		let block_id = self.at.parse::<B>()?;
+		let hash = client.header(block_id)?
+			.ok_or_else(|| format!("Block {} not found", block_id))?
+			.hash();
+		let extrinsic = B::Extrinsic::decode(&mut &decode_hex(&self.extrinsic)?[..])?;
+
+		info!("Applying the extrinsic on top of block {}", hash);
+		let applied = apply_extrinsic(&*client, &*backend, hash, extrinsic)?;
+		println!("result: {:?}", applied.result);
+
+		let events_key = [twox_128(b"System"), twox_128(b"Events")].concat();
+		for (key, value) in &applied.changes {
+			let events = match value {
+				Some(value) if *key == events_key => describe(value),
+				_ => None,
+			};
+			match (events, value) {
+				(Some(events), _) => events.iter().for_each(|event| println!("event: {}", event)),
+				(None, Some(value)) =>
+					println!("changed: 0x{} = 0x{}", HexDisplay::from(key), HexDisplay::from(value)),
+				(None, None) => println!("deleted: 0x{}", HexDisplay::from(key)),
+			}
+		}
+
+		info!("The extrinsic changed {} storage key(s)", applied.changes.len());
+		Ok(())
+	}
+}
+
+impl CliConfiguration for ApplyExtrinsicCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
@@ -0,0 +1,119 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	CliConfiguration, error, params::{BlockNumberOrHash, PruningParams, SharedParams},
+};
+use log::info;
+use sc_client_api::{StorageProvider, UsageProvider};
+use sp_core::{hashing::twox_128, hexdisplay::HexDisplay};
+use sp_runtime::{generic::BlockId, traits::{Block as BlockT, Header as HeaderT}};
+use std::{collections::HashMap, fmt::Debug, str::FromStr, sync::Arc};
+use structopt::StructOpt;
+
+/// The `audit-storage` command used to find the storage values exceeding a given size.
+#[derive(Debug, StructOpt)]
+pub struct AuditStorageCmd {
+	/// Report the values larger than this number of bytes.
+	#[structopt(long, value_name = "BYTES")]
+	pub threshold: usize,
+
+	/// Block hash or number.
+	///
+	/// Default is the best block.
+	#[structopt(long, value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+}
+
+impl AuditStorageCmd {
+	/// Run the `audit-storage` command
+	///
+	/// The keys are walked one by one and only the oversized ones are kept, which are printed
+	/// from the largest to the smallest value. `modules` are the storage prefixes of the runtime
+	/// modules, used to tell which module owns every reported key.
+	pub async fn run<B, BA, C>(
+		&self,
+		client: Arc<C>,
+		modules: impl IntoIterator<Item = String>,
+	) -> error::Result<()>
+	where
+		B: BlockT,
+		C: UsageProvider<B> + StorageProvider<B, BA>,
+		BA: sc_client_api::backend::Backend<B>,
+		B::Hash: FromStr,
+		<B::Hash as FromStr>::Err: Debug,
+		<<B::Header as HeaderT>::Number as FromStr>::Err: Debug,
+	{
+		let block = match self.at {
+			Some(ref at) => at.parse::<B>()?,
+			None => BlockId::Hash(client.usage_info().chain.best_hash),
+		};
+		info!("Auditing storage at {} for values larger than {} bytes", block, self.threshold);
+
+		let modules = modules.into_iter()
+			.map(|module| (twox_128(module.as_bytes()).to_vec(), module))
+			.collect::<HashMap<_, _>>();
+
+		let mut keys = 0u64;
+		let mut oversized = Vec::new();
+		for key in client.storage_keys_iter(&block, None, None)? {
+			keys += 1;
+			let size = match client.storage(&block, &key)? {
+				Some(value) => value.0.len(),
+				None => continue,
+			};
+			if size > self.threshold {
+				oversized.push((size, key));
+			}
+		}
+
+		oversized.sort_by(|(a, _), (b, _)| b.cmp(a));
+		for (size, key) in &oversized {
+			let owner = if key.0.starts_with(b":") {
+				"well-known"
+			} else {
+				key.0.get(..16)
+					.and_then(|prefix| modules.get(prefix))
+					.map(String::as_str)
+					.unwrap_or("unknown")
+			};
+			println!("{}\t{}\t0x{}", size, owner, HexDisplay::from(&key.0));
+		}
+
+		info!("Found {} oversized value(s) out of {} key(s)", oversized.len(), keys);
+		Ok(())
+	}
+}
+
+impl CliConfiguration for AuditStorageCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+}
@@ -0,0 +1,111 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+
+use crate::{
+	CliConfiguration, error,
+	params::{BlockNumberOrHash, DatabaseParams, PruningParams, SharedParams},
+};
+use sc_client_api::UsageProvider;
+use sp_core::hexdisplay::HexDisplay;
+use sp_runtime::{generic::BlockId, traits::{Block as BlockT, Header as HeaderT}};
+use std::{fmt::Debug, str::FromStr, sync::Arc};
+use structopt::StructOpt;
+
+/// The authorities of a consensus engine at a block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthoritySet {
+	/// Name of the consensus engine, e.g. `GRANDPA`.
+	pub engine: &'static str,
+	/// Id of the authority set, for the engines that number their sets.
+	pub set_id: Option<u64>,
+	/// The public keys of the authorities, with their weight.
+	pub authorities: Vec<(Vec<u8>, u64)>,
+}
+
+/// The `authority-set` command used to print the consensus authorities at a block.
+#[derive(Debug, StructOpt)]
+pub struct AuthoritySetCmd {
+	/// Block hash or number.
+	///
+	/// Default is the best block.
+	#[structopt(long, value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl AuthoritySetCmd {
+	/// Run the `authority-set` command
+	///
+	/// The authority sets of the consensus engines of the chain are read by `authority_sets`,
+	/// which depends on the runtime.
+	pub async fn run<B, C>(
+		&self,
+		client: Arc<C>,
+		authority_sets: impl Fn(&C, &BlockId<B>) -> Result<Vec<AuthoritySet>, sc_service::Error>,
+	) -> error::Result<()>
+	where
+		B: BlockT,
+		C: UsageProvider<B>,
+		B::Hash: FromStr,
+		<B::Hash as FromStr>::Err: Debug,
+		<<B::Header as HeaderT>::Number as FromStr>::Err: Debug,
+	{
+		let block = match self.at {
+			Some(ref at) => at.parse::<B>()?,
+			None => BlockId::Hash(client.usage_info().chain.best_hash),
+		};
+
+		for set in authority_sets(&*client, &block)? {
+			match set.set_id {
+				Some(set_id) => println!("{} authorities at {} (set id {}):", set.engine, block, set_id),
+				None => println!("{} authorities at {}:", set.engine, block),
+			}
+			for (public, weight) in &set.authorities {
+				println!("  0x{} (weight {})", HexDisplay::from(public), weight);
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl CliConfiguration for AuthoritySetCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
@@ -0,0 +1,99 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::error;
+use sp_core::{ecdsa, ed25519, sr25519, Pair};
+use std::{sync::Arc, thread, time::Instant};
+use structopt::StructOpt;
+
+/// Length of the messages that are signed, about the size of a signed transfer.
+const MESSAGE_LEN: usize = 128;
+
+/// The `bench-crypto` command used to measure how many signatures this machine can verify per
+/// second, with each of the signature schemes supported by the node.
+#[derive(Debug, StructOpt)]
+pub struct BenchCryptoCmd {
+	/// Number of signatures verified by each thread, for each signature scheme.
+	#[structopt(long, value_name = "COUNT", default_value = "10000")]
+	pub iterations: u32,
+
+	/// Number of threads verifying signatures in the multi-threaded run.
+	///
+	/// Default is the number of logical CPUs.
+	#[structopt(long, value_name = "COUNT")]
+	pub threads: Option<usize>,
+}
+
+impl BenchCryptoCmd {
+	/// Run the `bench-crypto` command
+	pub fn run(&self) -> error::Result<()> {
+		if self.iterations == 0 {
+			return Err(error::Error::Input("The number of iterations must not be 0".into()));
+		}
+		let threads = self.threads.unwrap_or_else(num_cpus::get).max(1);
+
+		println!("scheme\tthreads\tverifications/s");
+		self.bench::<sr25519::Pair>("sr25519", threads)?;
+		self.bench::<ed25519::Pair>("ed25519", threads)?;
+		self.bench::<ecdsa::Pair>("ecdsa", threads)?;
+
+		Ok(())
+	}
+
+	/// Print the verification throughput of the signature scheme of `P`, first on a single
+	/// thread and then on `threads` threads.
+	fn bench<P>(&self, name: &str, threads: usize) -> error::Result<()>
+	where
+		P: Pair,
+		P::Public: Send + Sync + 'static,
+		P::Signature: Send + Sync + 'static,
+	{
+		let pair = P::from_string("//Alice", None)
+			.map_err(|_| error::Error::Input(format!("Failed to generate a {} key", name)))?;
+		let message = [0x42u8; MESSAGE_LEN];
+		let signed = Arc::new((pair.sign(&message[..]), pair.public()));
+
+		let runs = if threads == 1 { vec![1] } else { vec![1, threads] };
+		for threads in runs {
+			let iterations = self.iterations;
+			let started = Instant::now();
+			let workers = (0..threads)
+				.map(|_| {
+					let signed = signed.clone();
+					thread::spawn(move || {
+						(0..iterations).all(|_| P::verify(&signed.0, &message[..], &signed.1))
+					})
+				})
+				.collect::<Vec<_>>();
+
+			for worker in workers {
+				let verified = worker.join()
+					.map_err(|_| error::Error::Input(format!("A {} verification thread panicked", name)))?;
+				if !verified {
+					return Err(error::Error::Input(format!("Invalid {} signature", name)));
+				}
+			}
+
+			let elapsed = started.elapsed().as_secs_f64();
+			let total = threads as f64 * iterations as f64;
+			println!("{}\t{}\t{:.0}", name, threads, total / elapsed);
+		}
+
+		Ok(())
+	}
+}
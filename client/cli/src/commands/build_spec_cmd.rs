@@ -20,6 +20,7 @@ use crate::error;
 use crate::params::NodeKeyParams;
 use crate::params::SharedParams;
 use crate::CliConfiguration;
+use futures::Future;
 use log::info;
 use sc_network::config::build_multiaddr;
 use sc_service::{config::{MultiaddrWithPeerId, NetworkConfiguration}, ChainSpec};
@@ -40,6 +41,14 @@ pub struct BuildSpecCmd {
 	#[structopt(long = "disable-default-bootnode")]
 	pub disable_default_bootnode: bool,
 
+	/// Let the node extend the specification before it is written, e.g. with boot nodes it
+	/// fetches.
+	///
+	/// What gets added depends on the node. The extension may be asynchronous, so the tokio
+	/// runtime is only driven when this flag is given.
+	#[structopt(long)]
+	pub extend: bool,
+
 	#[allow(missing_docs)]
 	#[structopt(flatten)]
 	pub shared_params: SharedParams,
@@ -50,6 +59,12 @@ pub struct BuildSpecCmd {
 }
 
 impl BuildSpecCmd {
+	/// Whether the command needs the tokio runtime, i.e. whether it must be run with
+	/// [`BuildSpecCmd::run_async`] rather than [`BuildSpecCmd::run`].
+	pub fn needs_runtime(&self) -> bool {
+		self.extend
+	}
+
 	/// Run the build-spec command
 	pub fn run(
 		&self,
@@ -57,8 +72,37 @@ impl BuildSpecCmd {
 		network_config: NetworkConfiguration,
 	) -> error::Result<()> {
 		info!("Building chain spec");
-		let raw_output = self.raw;
+		self.add_default_boot_node(&mut *spec, network_config)?;
+		self.write_spec(&*spec)
+	}
+
+	/// Run the build-spec command, letting `extend` change the specification before it is
+	/// written.
+	///
+	/// `extend` is only called when [`BuildSpecCmd::needs_runtime`] is `true`.
+	pub async fn run_async<F, Fut>(
+		&self,
+		mut spec: Box<dyn ChainSpec>,
+		network_config: NetworkConfiguration,
+		extend: F,
+	) -> error::Result<()>
+	where
+		F: FnOnce(Box<dyn ChainSpec>) -> Fut,
+		Fut: Future<Output = error::Result<Box<dyn ChainSpec>>>,
+	{
+		info!("Building chain spec");
+		if self.needs_runtime() {
+			spec = extend(spec).await?;
+		}
+		self.add_default_boot_node(&mut *spec, network_config)?;
+		self.write_spec(&*spec)
+	}
 
+	fn add_default_boot_node(
+		&self,
+		spec: &mut dyn ChainSpec,
+		network_config: NetworkConfiguration,
+	) -> error::Result<()> {
 		if spec.boot_nodes().is_empty() && !self.disable_default_bootnode {
 			let keys = network_config.node_key.into_keypair()?;
 			let peer_id = keys.public().into_peer_id();
@@ -68,8 +112,11 @@ impl BuildSpecCmd {
 			};
 			spec.add_boot_node(addr)
 		}
+		Ok(())
+	}
 
-		let json = sc_service::chain_ops::build_spec(&*spec, raw_output)?;
+	fn write_spec(&self, spec: &dyn ChainSpec) -> error::Result<()> {
+		let json = sc_service::chain_ops::build_spec(spec, self.raw)?;
 		if std::io::stdout().write_all(json.as_bytes()).is_err() {
 			let _ = std::io::stderr().write_all(b"Error writing to stdout\n");
 		}
@@ -0,0 +1,59 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{CliConfiguration, error, params::SharedParams};
+use log::info;
+use sc_service::{ChainSpec, chain_ops::write_genesis_cache};
+use sp_runtime::traits::Block as BlockT;
+use std::{fs, io, path::PathBuf};
+use structopt::StructOpt;
+
+/// The `cache-genesis` command used to build the genesis state of a chain spec once and write
+/// it to a file.
+///
+/// The file can then be loaded with [`Runner::use_genesis_cache`](crate::Runner::use_genesis_cache)
+/// instead of building the genesis state again.
+#[derive(Debug, StructOpt)]
+pub struct CacheGenesisCmd {
+	/// Output file.
+	#[structopt(long, parse(from_os_str), value_name = "PATH")]
+	pub out: PathBuf,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl CacheGenesisCmd {
+	/// Run the `cache-genesis` command
+	pub fn run<B: BlockT>(&self, spec: &dyn ChainSpec) -> error::Result<()> {
+		info!("Building the genesis state of {}", spec.name());
+
+		let output = io::BufWriter::new(fs::File::create(&self.out)?);
+		let genesis = write_genesis_cache::<B>(spec, output)?;
+
+		info!("Genesis {} cached in {}", genesis, self.out.display());
+		Ok(())
+	}
+}
+
+impl CliConfiguration for CacheGenesisCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+}
@@ -0,0 +1,115 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	CliConfiguration, OutputType, error, params::{DatabaseParams, PruningParams, SharedParams},
+};
+use sc_client_api::UsageProvider;
+use sc_service::PruningMode;
+use serde_json::json;
+use sp_runtime::traits::Block as BlockT;
+use std::sync::Arc;
+use structopt::StructOpt;
+
+/// The `chain-info` command used to print the genesis, best and finalized blocks of the database
+/// along with its pruning mode.
+#[derive(Debug, StructOpt)]
+pub struct ChainInfoCmd {
+	/// Output format.
+	#[structopt(
+		long,
+		value_name = "FORMAT",
+		possible_values = &OutputType::variants(),
+		case_insensitive = true,
+		default_value = "Text"
+	)]
+	pub output: OutputType,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl ChainInfoCmd {
+	/// Run the `chain-info` command
+	///
+	/// `pruning` is the pruning mode the database was opened with.
+	pub async fn run<B, C>(&self, client: Arc<C>, pruning: PruningMode) -> error::Result<()>
+	where
+		B: BlockT,
+		C: UsageProvider<B>,
+	{
+		let info = client.usage_info().chain;
+		let pruning = describe_pruning(&pruning);
+
+		match self.output {
+			OutputType::Json => {
+				let summary = json!({
+					"genesisHash": format!("{:?}", info.genesis_hash),
+					"bestNumber": info.best_number.to_string(),
+					"bestHash": format!("{:?}", info.best_hash),
+					"finalizedNumber": info.finalized_number.to_string(),
+					"finalizedHash": format!("{:?}", info.finalized_hash),
+					"pruning": pruning,
+				});
+				println!("{}", serde_json::to_string_pretty(&summary).expect("Json pretty print failed"));
+			},
+			OutputType::Text => {
+				println!("Genesis:   {:?}", info.genesis_hash);
+				println!("Best:      #{} ({:?})", info.best_number, info.best_hash);
+				println!("Finalized: #{} ({:?})", info.finalized_number, info.finalized_hash);
+				println!("Pruning:   {}", pruning);
+			},
+		}
+
+		Ok(())
+	}
+}
+
+fn describe_pruning(pruning: &PruningMode) -> String {
+	match pruning {
+		PruningMode::ArchiveAll => "archive".into(),
+		PruningMode::ArchiveCanonical => "archive-canonical".into(),
+		PruningMode::Constrained(constraints) => match constraints.max_blocks {
+			Some(blocks) => format!("{} blocks", blocks),
+			None => "non-canonical states only".into(),
+		},
+	}
+}
+
+impl CliConfiguration for ChainInfoCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
@@ -17,11 +17,12 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{
-	CliConfiguration, error, params::{ImportParams, SharedParams, BlockNumberOrHash},
+	CliConfiguration, CliSubcommand, error, params::{ImportParams, SharedParams, BlockNumberOrHash},
 };
 use sc_client_api::{BlockBackend, UsageProvider};
+use sc_service::Configuration;
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
-use std::{fmt::Debug, str::FromStr, sync::Arc};
+use std::{fmt::Debug, future::Future, pin::Pin, str::FromStr, sync::Arc};
 use structopt::StructOpt;
 
 /// The `check-block` command used to validate blocks.
@@ -69,6 +70,24 @@ impl CheckBlockCmd {
 	}
 }
 
+impl<B, C, IQ> CliSubcommand<B, (Arc<C>, IQ)> for CheckBlockCmd
+where
+	B: BlockT + for<'de> serde::Deserialize<'de>,
+	C: BlockBackend<B> + UsageProvider<B> + Send + Sync + 'static,
+	IQ: sc_service::ImportQueue<B> + 'static,
+	B::Hash: FromStr,
+	<B::Hash as FromStr>::Err: Debug,
+	<<B::Header as HeaderT>::Number as FromStr>::Err: Debug,
+{
+	fn run<'a>(
+		&'a self,
+		(client, import_queue): (Arc<C>, IQ),
+		_config: Configuration,
+	) -> Pin<Box<dyn Future<Output = error::Result<()>> + 'a>> {
+		Box::pin(CheckBlockCmd::run(self, client, import_queue))
+	}
+}
+
 impl CliConfiguration for CheckBlockCmd {
 	fn shared_params(&self) -> &SharedParams {
 		&self.shared_params
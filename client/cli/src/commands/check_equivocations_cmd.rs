@@ -0,0 +1,119 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{CliConfiguration, error, params::{DatabaseParams, PruningParams, SharedParams}};
+use log::{info, warn};
+use sc_client_api::backend::Backend;
+use sc_service::chain_ops::{check_equivocations, EquivocationKind};
+use sp_core::hexdisplay::HexDisplay;
+use sp_runtime::{Justification, traits::Block as BlockT};
+use std::sync::Arc;
+use structopt::StructOpt;
+
+/// The `check-equivocations` command used to look for conflicting blocks or finality votes
+/// signed by the same authority.
+#[derive(Debug, StructOpt)]
+pub struct CheckEquivocationsCmd {
+	/// Number of blocks below the best one to scan.
+	#[structopt(long, value_name = "COUNT", default_value = "256")]
+	pub window: u32,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl CheckEquivocationsCmd {
+	/// Run the `check-equivocations` command
+	///
+	/// `author` gives the slot and the encoded public key of the author of a header, and `votes`
+	/// splits a justification into `(round, voter, target)` triples. Every equivocation is
+	/// printed, and the command fails if one of them was signed by a key for which `is_local`
+	/// returns `true`.
+	pub async fn run<B, BA>(
+		&self,
+		backend: Arc<BA>,
+		author: impl Fn(&B::Header) -> Result<Option<(u64, Vec<u8>)>, sc_service::Error>,
+		votes: impl Fn(&Justification) -> Result<Vec<(u64, Vec<u8>, B::Hash)>, sc_service::Error>,
+		is_local: impl Fn(&[u8]) -> bool,
+	) -> error::Result<()>
+	where
+		B: BlockT,
+		BA: Backend<B>,
+	{
+		info!("Looking for equivocations in the last {} blocks", self.window);
+		let equivocations = check_equivocations(&*backend, self.window.into(), author, votes)?;
+
+		let mut local = 0;
+		for equivocation in &equivocations {
+			let is_local = is_local(&equivocation.offender);
+			if is_local {
+				local += 1;
+			}
+
+			let kind = match equivocation.kind {
+				EquivocationKind::Authorship => "slot",
+				EquivocationKind::Finality => "round",
+			};
+			let hashes = equivocation.hashes.iter().map(|h| format!("{}", h)).collect::<Vec<_>>();
+			println!(
+				"{} {}\t0x{}{}\t{}",
+				kind,
+				equivocation.slot_or_round,
+				HexDisplay::from(&equivocation.offender),
+				if is_local { " (local key)" } else { "" },
+				hashes.join(","),
+			);
+		}
+
+		if local > 0 {
+			return Err(error::Error::Input(
+				format!("Found {} equivocation(s) signed by local keys", local),
+			));
+		}
+
+		if equivocations.is_empty() {
+			info!("No equivocation found");
+		} else {
+			warn!("Found {} equivocation(s), none signed by local keys", equivocations.len());
+		}
+		Ok(())
+	}
+}
+
+impl CliConfiguration for CheckEquivocationsCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
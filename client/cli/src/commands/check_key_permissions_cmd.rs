@@ -0,0 +1,165 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::error;
+use crate::params::{KeystoreParams, NodeKeyParams, SharedParams};
+use crate::CliConfiguration;
+use sc_network::config::NodeKeyConfig;
+use sc_service::config::KeystoreConfig;
+use structopt::StructOpt;
+
+/// The `check-key-permissions` command used to verify that key material isn't readable by
+/// other users.
+///
+/// The node key file must not be more permissive than `0600`, the keystore directory not more
+/// permissive than `0700` and the keys it contains not more permissive than `0600`.
+///
+/// This is a no-op on non-unix platforms.
+#[derive(Debug, StructOpt)]
+pub struct CheckKeyPermissionsCmd {
+	/// Restrict the permissions of the offending files instead of failing.
+	#[structopt(long)]
+	pub fix: bool,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub node_key_params: NodeKeyParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub keystore_params: KeystoreParams,
+}
+
+impl CheckKeyPermissionsCmd {
+	/// Run the check-key-permissions command
+	pub fn run(&self, node_key: &NodeKeyConfig, keystore: &KeystoreConfig) -> error::Result<()> {
+		#[cfg(unix)]
+		{
+			let mut offending = 0;
+
+			match node_key {
+				NodeKeyConfig::Ed25519(sc_network::config::Secret::File(path)) =>
+					offending += unix::check(path, unix::FILE_MODE, self.fix)?,
+				_ => println!("Node key is not stored in a file, skipping."),
+			}
+
+			match keystore.path() {
+				Some(path) => offending += unix::check_keystore(path, self.fix)?,
+				None => println!("Keystore is in memory, skipping."),
+			}
+
+			if offending > 0 && !self.fix {
+				return Err(error::Error::Input(format!(
+					"{} file(s) holding key material are too permissive, use `--fix` to restrict them",
+					offending,
+				)));
+			}
+		}
+
+		#[cfg(not(unix))]
+		{
+			let _ = (node_key, keystore);
+			println!("Permission checks are only supported on unix, skipping.");
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(unix)]
+mod unix {
+	use crate::error;
+	use std::fs;
+	use std::io;
+	use std::os::unix::fs::PermissionsExt;
+	use std::path::Path;
+
+	/// Most permissive mode allowed for files holding key material.
+	pub const FILE_MODE: u32 = 0o600;
+	/// Most permissive mode allowed for the keystore directory.
+	pub const DIR_MODE: u32 = 0o700;
+
+	/// Check that the keystore directory and every file in it are restricted enough.
+	///
+	/// Returns the number of offending entries.
+	pub fn check_keystore(path: &Path, fix: bool) -> error::Result<usize> {
+		let mut offending = check(path, DIR_MODE, fix)?;
+
+		let entries = match fs::read_dir(path) {
+			Ok(entries) => entries,
+			Err(ref err) if err.kind() == io::ErrorKind::NotFound => return Ok(offending),
+			Err(err) => return Err(err.into()),
+		};
+
+		for entry in entries {
+			let entry = entry?;
+			if entry.file_type()?.is_file() {
+				offending += check(&entry.path(), FILE_MODE, fix)?;
+			}
+		}
+
+		Ok(offending)
+	}
+
+	/// Check that the mode of `path` doesn't grant more than `allowed`.
+	///
+	/// Returns `1` if it does (and restricts it if `fix` is set), `0` otherwise.
+	pub fn check(path: &Path, allowed: u32, fix: bool) -> error::Result<usize> {
+		let metadata = match fs::metadata(path) {
+			Ok(metadata) => metadata,
+			Err(ref err) if err.kind() == io::ErrorKind::NotFound => {
+				println!("{:?} does not exist, skipping.", path);
+				return Ok(0);
+			},
+			Err(err) => return Err(err.into()),
+		};
+
+		let mode = metadata.permissions().mode() & 0o777;
+		if mode & !allowed == 0 {
+			println!("{:?}: {:o} ok.", path, mode);
+			return Ok(0);
+		}
+
+		if fix {
+			fs::set_permissions(path, fs::Permissions::from_mode(mode & allowed))?;
+			println!("{:?}: {:o} restricted to {:o}.", path, mode, mode & allowed);
+		} else {
+			eprintln!("{:?}: {:o} is more permissive than {:o}.", path, mode, allowed);
+		}
+
+		Ok(1)
+	}
+}
+
+impl CliConfiguration for CheckKeyPermissionsCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn node_key_params(&self) -> Option<&NodeKeyParams> {
+		Some(&self.node_key_params)
+	}
+
+	fn keystore_params(&self) -> Option<&KeystoreParams> {
+		Some(&self.keystore_params)
+	}
+}
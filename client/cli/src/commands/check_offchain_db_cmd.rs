@@ -0,0 +1,150 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	CliConfiguration, commands::utils::decode_hex, error,
+	params::{DatabaseParams, PruningParams, SharedParams},
+};
+use log::{info, warn};
+use sc_client_api::Backend;
+use sp_core::{hexdisplay::HexDisplay, offchain::{OffchainStorage, STORAGE_PREFIX}};
+use sp_runtime::traits::Block as BlockT;
+use std::sync::Arc;
+use structopt::StructOpt;
+
+/// The `check-offchain-db` command used to check the consistency of the persistent storage of
+/// the offchain workers.
+///
+/// The offchain storage can't be iterated, so only the keys known by the runtime, and the ones
+/// given on the command line, are checked.
+#[derive(Debug, StructOpt)]
+pub struct CheckOffchainDbCmd {
+	/// Additional key of the persistent offchain storage to check, in hex.
+	///
+	/// Can be given multiple times.
+	#[structopt(long = "key", value_name = "HEX")]
+	pub keys: Vec<String>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl CheckOffchainDbCmd {
+	/// Run the `check-offchain-db` command
+	///
+	/// `known_keys` are the keys the offchain workers of the runtime are expected to write.
+	/// `describe` decodes the value of a key, returning `None` if it doesn't recognize the format
+	/// of the key, or an error if the value doesn't decode. Keys that can't be read or whose value
+	/// doesn't match its key are reported as anomalies, and the command fails if there is any.
+	///
+	/// Nothing is written to the offchain storage.
+	pub async fn run<B, BA>(
+		&self,
+		backend: Arc<BA>,
+		known_keys: Vec<Vec<u8>>,
+		describe: impl Fn(&[u8], &[u8]) -> Option<Result<String, String>>,
+	) -> error::Result<()>
+	where
+		B: BlockT,
+		BA: Backend<B>,
+	{
+		let storage = backend.offchain_storage().ok_or_else(|| error::Error::Input(
+			"The database of this node has no offchain storage".into(),
+		))?;
+
+		let mut keys = known_keys;
+		for key in &self.keys {
+			keys.push(decode_hex(key)?);
+		}
+		info!("Checking {} key(s) of the offchain storage", keys.len());
+
+		let mut anomalies = 0;
+		for key in keys {
+			let value = match storage.get(STORAGE_PREFIX, &key) {
+				Some(value) => value,
+				None => {
+					println!("absent    {}", display_key(&key));
+					continue;
+				},
+			};
+
+			match describe(&key, &value) {
+				Some(Ok(description)) => println!("ok        {}: {}", display_key(&key), description),
+				Some(Err(e)) => {
+					anomalies += 1;
+					println!("invalid   {}: {}", display_key(&key), e);
+				},
+				None => {
+					anomalies += 1;
+					println!(
+						"unknown   {}: unexpected key format, value 0x{}",
+						display_key(&key),
+						HexDisplay::from(&value),
+					);
+				},
+			}
+		}
+
+		if anomalies > 0 {
+			warn!("Found {} anomalies in the offchain storage", anomalies);
+			return Err(error::Error::Input(
+				format!("The offchain storage contains {} anomalies", anomalies),
+			));
+		}
+
+		info!("No anomaly found in the offchain storage");
+		Ok(())
+	}
+}
+
+/// Display the printable prefix of `key`, the way offchain workers usually name their keys,
+/// followed by the rest of it in hex.
+fn display_key(key: &[u8]) -> String {
+	let printable = key.iter()
+		.position(|b| !(b.is_ascii_graphic() || *b == b' '))
+		.unwrap_or(key.len());
+	let (name, rest) = key.split_at(printable);
+
+	match rest.is_empty() {
+		true => String::from_utf8_lossy(name).into_owned(),
+		false => format!("{}0x{}", String::from_utf8_lossy(name), HexDisplay::from(&rest)),
+	}
+}
+
+impl CliConfiguration for CheckOffchainDbCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
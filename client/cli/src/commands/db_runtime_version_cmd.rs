@@ -0,0 +1,101 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::error;
+use crate::params::{DatabaseParams, PruningParams, SharedParams};
+use crate::CliConfiguration;
+use sc_client_api::UsageProvider;
+use sp_api::CallApiAt;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use sp_version::RuntimeVersion;
+use std::sync::Arc;
+use structopt::StructOpt;
+
+/// The `db-runtime-version` command used to print the version of the runtime stored in the
+/// database at the best block.
+#[derive(Debug, StructOpt)]
+pub struct DbRuntimeVersionCmd {
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl DbRuntimeVersionCmd {
+	/// Run the db-runtime-version command
+	///
+	/// The version is read from the `:code` of the best block and compared with the `native`
+	/// version of this binary, if it has one.
+	pub async fn run<B, C>(
+		&self,
+		client: Arc<C>,
+		native: Option<&RuntimeVersion>,
+	) -> error::Result<()>
+	where
+		B: BlockT,
+		C: CallApiAt<B, Error = sp_blockchain::Error> + UsageProvider<B>,
+	{
+		let info = client.usage_info().chain;
+		let version = client.runtime_version_at(&BlockId::Hash(info.best_hash))?;
+
+		println!("Best block: #{} ({})", info.best_number, info.best_hash);
+		println!("spec_name: {}", version.spec_name);
+		println!("spec_version: {}", version.spec_version);
+		println!("impl_name: {}", version.impl_name);
+		println!("impl_version: {}", version.impl_version);
+		println!("transaction_version: {}", version.transaction_version);
+		println!("authoring_version: {}", version.authoring_version);
+		let native = match native {
+			Some(native) => native,
+			None => {
+				println!("Native runtime: <none, wasm-only>");
+				return Ok(());
+			},
+		};
+		println!("Native runtime: {}", native);
+
+		if native.can_call_with(&version) {
+			println!("The native runtime is compatible with the database.");
+		} else {
+			println!("The native runtime is NOT compatible with the database.");
+		}
+
+		Ok(())
+	}
+}
+
+impl CliConfiguration for DbRuntimeVersionCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
@@ -0,0 +1,88 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{commands::utils::decode_hex, error};
+use parity_scale_codec::Decode;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::Debug;
+use structopt::StructOpt;
+
+/// A function decoding a SCALE-encoded value of a given type into JSON.
+///
+/// See [`decode_to_json`] and [`decode_to_debug`].
+pub type DecodeFn = fn(&[u8]) -> error::Result<Value>;
+
+/// The `decode` command used to decode a SCALE-encoded value of one of the types of the runtime,
+/// and print it as JSON.
+#[derive(Debug, StructOpt)]
+pub struct DecodeCmd {
+	/// Name of the type of the value, e.g. `Header`.
+	#[structopt(long = "type", value_name = "TYPE")]
+	pub type_name: String,
+
+	/// The SCALE-encoded value, in hex.
+	#[structopt(long, value_name = "HEX")]
+	pub hex: String,
+}
+
+impl DecodeCmd {
+	/// Run the `decode` command
+	///
+	/// `types` are the names of the types that can be decoded, along with the function decoding
+	/// them.
+	pub fn run(&self, types: &[(&str, DecodeFn)]) -> error::Result<()> {
+		let decode = types.iter()
+			.find(|(name, _)| *name == self.type_name)
+			.map(|(_, decode)| decode)
+			.ok_or_else(|| error::Error::Input(format!(
+				"Unknown type `{}`, expected one of: {}",
+				self.type_name,
+				types.iter().map(|(name, _)| *name).collect::<Vec<_>>().join(", "),
+			)))?;
+
+		let value = decode(&decode_hex(&self.hex)?)?;
+		let output = serde_json::to_string_pretty(&value)
+			.map_err(|e| format!("Failed to encode the value: {}", e))?;
+		println!("{}", output);
+		Ok(())
+	}
+}
+
+/// Decode a `T` from the whole of `input`, into the JSON it serializes to.
+pub fn decode_to_json<T: Decode + Serialize>(input: &[u8]) -> error::Result<Value> {
+	let value = decode_all::<T>(input)?;
+	serde_json::to_value(&value).map_err(|e| format!("Failed to encode the value: {}", e).into())
+}
+
+/// Decode a `T` from the whole of `input`, into a JSON string of its debug representation, for
+/// the types that can't be serialized.
+pub fn decode_to_debug<T: Decode + Debug>(input: &[u8]) -> error::Result<Value> {
+	let value = decode_all::<T>(input)?;
+	Ok(Value::String(format!("{:?}", value)))
+}
+
+fn decode_all<T: Decode>(mut input: &[u8]) -> error::Result<T> {
+	let value = T::decode(&mut input)?;
+	if !input.is_empty() {
+		return Err(error::Error::Input(
+			format!("{} trailing byte(s) after the decoded value", input.len()),
+		));
+	}
+	Ok(value)
+}
@@ -0,0 +1,126 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	CliConfiguration, error, params::{BlockNumberOrHash, DatabaseParams, PruningParams, SharedParams},
+};
+use log::info;
+use sc_client_api::StorageProvider;
+use sc_service::{config::DatabaseConfig, chain_ops::{diff_state, StateDiff}};
+use sp_core::hexdisplay::HexDisplay;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use std::{fmt::Debug, path::PathBuf, str::FromStr, sync::Arc};
+use structopt::StructOpt;
+
+/// The `diff-state` command used to compare the state of two databases at a given block.
+#[derive(Debug, StructOpt)]
+pub struct DiffStateCmd {
+	/// Path to the database to compare against.
+	///
+	/// It must be an existing database of the same type as the one of this node.
+	#[structopt(long, parse(from_os_str), value_name = "PATH")]
+	pub other: PathBuf,
+
+	/// Block hash or number at which the states are compared.
+	#[structopt(long, value_name = "HASH or NUMBER")]
+	pub at: BlockNumberOrHash,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl DiffStateCmd {
+	/// Build the configuration of the database to compare against, based on the one of this
+	/// node.
+	///
+	/// Fails if the other database doesn't exist, so that it never gets created by opening it.
+	pub fn other_database(&self, database: &DatabaseConfig) -> error::Result<DatabaseConfig> {
+		if !self.other.exists() {
+			return Err(error::Error::Input(
+				format!("Database {} does not exist", self.other.display()),
+			));
+		}
+
+		match database {
+			DatabaseConfig::RocksDb { cache_size, .. } |
+			DatabaseConfig::RocksDbSecondary { cache_size, .. } => Ok(DatabaseConfig::RocksDb {
+				path: self.other.clone(),
+				cache_size: *cache_size,
+			}),
+			DatabaseConfig::ParityDb { .. } => Ok(DatabaseConfig::ParityDb {
+				path: self.other.clone(),
+			}),
+			DatabaseConfig::Custom(_) => Err(error::Error::Input(
+				"Cannot compare against a custom database implementation".into(),
+			)),
+		}
+	}
+
+	/// Run the `diff-state` command
+	pub async fn run<B, BA, C>(
+		&self,
+		client: Arc<C>,
+		other: Arc<C>,
+	) -> error::Result<()>
+	where
+		B: BlockT,
+		C: StorageProvider<B, BA>,
+		BA: sc_client_api::backend::Backend<B>,
+		B::Hash: FromStr,
+		<B::Hash as FromStr>::Err: Debug,
+		<<B::Header as HeaderT>::Number as FromStr>::Err: Debug,
+	{
+		let block_id = self.at.parse::<B>()?;
+		info!("Comparing state at {} with {}", block_id, self.other.display());
+
+		let diffs = diff_state(client, other, block_id, |diff| {
+			match diff {
+				StateDiff::OnlyLeft(key) => println!("only local: 0x{}", HexDisplay::from(&key.0)),
+				StateDiff::OnlyRight(key) => println!("only other: 0x{}", HexDisplay::from(&key.0)),
+				StateDiff::Value(key) => println!("differs:    0x{}", HexDisplay::from(&key.0)),
+			}
+			Ok(())
+		})?;
+
+		info!("Found {} difference(s)", diffs);
+		Ok(())
+	}
+}
+
+impl CliConfiguration for DiffStateCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
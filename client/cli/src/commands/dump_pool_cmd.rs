@@ -0,0 +1,80 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{error, runner::build_runtime};
+use futures::compat::Future01CompatExt;
+use jsonrpc_core::Params;
+use jsonrpc_core_client::{transports::http, RawClient};
+use sc_rpc_api::author::pool::{PoolContents, PoolTransaction};
+use serde::de::DeserializeOwned;
+use sp_core::hexdisplay::HexDisplay;
+use std::fmt::Debug;
+use structopt::StructOpt;
+
+/// The `dump-pool` command used to list the transactions in the pool of a running node.
+///
+/// The pool is queried through the unsafe `author_poolContents` RPC method, so the node must
+/// expose its unsafe RPC methods on the given endpoint.
+#[derive(Debug, StructOpt)]
+pub struct DumpPoolCmd {
+	/// HTTP RPC endpoint of the node.
+	#[structopt(long, value_name = "URL", default_value = "http://localhost:9933")]
+	pub rpc_url: String,
+}
+
+impl DumpPoolCmd {
+	/// Run the `dump-pool` command
+	///
+	/// `describe_tag` turns a tag provided by a transaction into a description of the
+	/// transaction, e.g. its sender and nonce, or `None` if it doesn't recognize the tag.
+	pub fn run<Hash>(
+		&self,
+		describe_tag: impl Fn(&[u8]) -> Option<String>,
+	) -> error::Result<()>
+	where
+		Hash: DeserializeOwned + Debug,
+	{
+		let contents: PoolContents<Hash> = build_runtime()?.block_on(self.pool_contents())?;
+
+		let print = |status: &str, tx: &PoolTransaction<Hash>| {
+			let description = tx.provides.iter()
+				.find_map(|tag| describe_tag(&tag[..]))
+				.unwrap_or_else(|| tx.provides.iter()
+					.map(|tag| format!("0x{}", HexDisplay::from(&tag.0)))
+					.collect::<Vec<_>>()
+					.join(","));
+			println!("{}\t{:?}\tpriority {}\t{}", status, tx.hash, tx.priority, description);
+		};
+
+		contents.ready.iter().for_each(|tx| print("ready", tx));
+		contents.future.iter().for_each(|tx| print("future", tx));
+
+		println!("{} ready, {} future", contents.ready.len(), contents.future.len());
+		Ok(())
+	}
+
+	async fn pool_contents<Hash: DeserializeOwned>(&self) -> error::Result<PoolContents<Hash>> {
+		let client: RawClient = http::connect(&self.rpc_url).compat().await
+			.map_err(|e| format!("Failed to connect to {}: {:?}", self.rpc_url, e))?;
+		let value = client.call_method("author_poolContents", Params::None).compat().await
+			.map_err(|e| format!("Failed to query the pool: {:?}", e))?;
+
+		serde_json::from_value(value)
+			.map_err(|e| format!("Failed to decode the pool contents: {}", e).into())
+	}
+}
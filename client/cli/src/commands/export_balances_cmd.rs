@@ -0,0 +1,151 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	CliConfiguration, error, params::{BlockNumberOrHash, PruningParams, SharedParams},
+};
+use log::{info, warn};
+use parity_scale_codec::Decode;
+use sc_client_api::{StorageProvider, UsageProvider};
+use sp_core::{hashing::twox_128, storage::StorageKey};
+use sp_runtime::{generic::BlockId, traits::{Block as BlockT, Header as HeaderT, Saturating, Zero}};
+use std::{fmt::{Debug, Display}, fs, io::{self, Write}, path::PathBuf, str::FromStr, sync::Arc};
+use structopt::StructOpt;
+
+/// Length of the hashed prefix of a `System::Account` key, i.e. the pallet and storage item
+/// prefixes followed by the `Blake2_128Concat` hash of the account id.
+const ACCOUNT_KEY_HASH_LEN: usize = 16 + 16 + 16;
+
+/// The `export-balances` command used to export the balances of all accounts at a given block
+/// into a CSV file.
+#[derive(Debug, StructOpt)]
+pub struct ExportBalancesCmd {
+	/// Block hash or number.
+	///
+	/// Default is the best block.
+	#[structopt(long, value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	/// Output CSV file.
+	#[structopt(long, parse(from_os_str), value_name = "PATH")]
+	pub out: PathBuf,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+}
+
+impl ExportBalancesCmd {
+	/// Run the `export-balances` command
+	///
+	/// Every entry of the `System::Account` map is decoded as an `AccountInfo` and `balances`
+	/// extracts its free and reserved balances. The sum of all of the balances is then compared
+	/// with the `Balances::TotalIssuance`.
+	pub async fn run<B, BA, C, AccountId, AccountInfo, Balance>(
+		&self,
+		client: Arc<C>,
+		balances: impl Fn(&AccountId, AccountInfo) -> (Balance, Balance),
+	) -> error::Result<()>
+	where
+		B: BlockT,
+		C: UsageProvider<B> + StorageProvider<B, BA>,
+		BA: sc_client_api::backend::Backend<B>,
+		B::Hash: FromStr,
+		<B::Hash as FromStr>::Err: Debug,
+		<<B::Header as HeaderT>::Number as FromStr>::Err: Debug,
+		AccountId: Decode + Display,
+		AccountInfo: Decode,
+		Balance: Decode + Display + Saturating + Zero + Copy + PartialEq,
+	{
+		let block = match self.at {
+			Some(ref at) => at.parse::<B>()?,
+			None => BlockId::Hash(client.usage_info().chain.best_hash),
+		};
+		info!("Exporting balances at {} to {}", block, self.out.display());
+
+		let mut output = io::BufWriter::new(fs::File::create(&self.out)?);
+		writeln!(output, "account,free,reserved")?;
+
+		let prefix = storage_prefix(b"System", b"Account");
+		let mut accounts = 0u64;
+		let mut total = Balance::zero();
+
+		for key in client.storage_keys_iter(&block, Some(&prefix), None)? {
+			let value = match client.storage(&block, &key)? {
+				Some(value) => value,
+				None => continue,
+			};
+
+			let account = key.0.get(ACCOUNT_KEY_HASH_LEN..)
+				.and_then(|mut raw| AccountId::decode(&mut raw).ok());
+			let info = AccountInfo::decode(&mut &value.0[..]).ok();
+			let (account, info) = match (account, info) {
+				(Some(account), Some(info)) => (account, info),
+				_ => {
+					warn!("Skipping undecodable account entry 0x{}", hex::encode(&key.0));
+					continue;
+				},
+			};
+
+			let (free, reserved) = balances(&account, info);
+			writeln!(output, "{},{},{}", account, free, reserved)?;
+
+			accounts += 1;
+			total = total.saturating_add(free).saturating_add(reserved);
+		}
+		output.flush()?;
+
+		if accounts == 0 {
+			warn!("No account found, the chain may not use the `System` pallet");
+			return Ok(());
+		}
+		info!("Exported {} accounts holding {} in total", accounts, total);
+
+		let issuance_key = storage_prefix(b"Balances", b"TotalIssuance");
+		match client.storage(&block, &issuance_key)?
+			.and_then(|value| Balance::decode(&mut &value.0[..]).ok())
+		{
+			Some(issuance) if issuance == total => info!("Total issuance matches: {}", issuance),
+			Some(issuance) => warn!("Total issuance is {}, which differs from the sum of the balances", issuance),
+			None => warn!("No total issuance found, the chain may not use the `Balances` pallet"),
+		}
+
+		Ok(())
+	}
+}
+
+/// Build the storage key prefix of a storage item of a pallet.
+fn storage_prefix(pallet: &[u8], item: &[u8]) -> StorageKey {
+	let mut key = twox_128(pallet).to_vec();
+	key.extend_from_slice(&twox_128(item));
+	StorageKey(key)
+}
+
+impl CliConfiguration for ExportBalancesCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+}
@@ -17,18 +17,20 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::error;
+use crate::commands::utils::output_writer;
 use crate::params::{GenericNumber, DatabaseParams, PruningParams, SharedParams};
-use crate::CliConfiguration;
+use crate::{CliConfiguration, CliSubcommand};
 use log::info;
 use sc_service::{
-	config::DatabaseConfig, chain_ops::export_blocks,
+	config::DatabaseConfig, chain_ops::export_blocks, Configuration,
 };
 use sc_client_api::{BlockBackend, UsageProvider};
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
 use std::fmt::Debug;
-use std::fs;
+use std::future::Future;
 use std::io;
 use std::path::PathBuf;
+use std::pin::Pin;
 use std::str::FromStr;
 use std::sync::Arc;
 use structopt::StructOpt;
@@ -36,7 +38,7 @@ use structopt::StructOpt;
 /// The `export-blocks` command used to export blocks.
 #[derive(Debug, StructOpt)]
 pub struct ExportBlocksCmd {
-	/// Output file name or stdout if unspecified.
+	/// Output file name, or stdout if `-` or unspecified.
 	#[structopt(parse(from_os_str))]
 	pub output: Option<PathBuf>,
 
@@ -70,11 +72,18 @@ pub struct ExportBlocksCmd {
 }
 
 impl ExportBlocksCmd {
-	/// Run the export-blocks command
+	/// Open the output of the blocks, see [`output_writer`].
+	pub fn output(&self) -> error::Result<Box<dyn io::Write>> {
+		Ok(output_writer(self.output.as_deref())?)
+	}
+
+	/// Run the export-blocks command, writing the blocks to `output`, see
+	/// [`ExportBlocksCmd::output`].
 	pub async fn run<B, C>(
 		&self,
 		client: Arc<C>,
 		database_config: DatabaseConfig,
+		output: Box<dyn io::Write>,
 	) -> error::Result<()>
 	where
 		B: BlockT,
@@ -90,17 +99,26 @@ impl ExportBlocksCmd {
 
 		let binary = self.binary;
 
-		let file: Box<dyn io::Write> = match &self.output {
-			Some(filename) => Box::new(fs::File::create(filename)?),
-			None => Box::new(io::stdout()),
-		};
-
-		export_blocks(client, file, from.into(), to, binary)
+		export_blocks(client, output, from.into(), to, binary)
 			.await
 			.map_err(Into::into)
 	}
 }
 
+impl<B, C> CliSubcommand<B, Arc<C>> for ExportBlocksCmd
+where
+	B: BlockT,
+	C: BlockBackend<B> + UsageProvider<B> + 'static,
+	<<B::Header as HeaderT>::Number as FromStr>::Err: Debug,
+{
+	fn run<'a>(&'a self, client: Arc<C>, config: Configuration) -> Pin<Box<dyn Future<Output = error::Result<()>> + 'a>> {
+		Box::pin(async move {
+			let output = self.output()?;
+			ExportBlocksCmd::run(self, client, config.database, output).await
+		})
+	}
+}
+
 impl CliConfiguration for ExportBlocksCmd {
 	fn shared_params(&self) -> &SharedParams {
 		&self.shared_params
@@ -0,0 +1,120 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	CliConfiguration, error, params::{DatabaseParams, GenericNumber, PruningParams, SharedParams},
+};
+use log::info;
+use parity_scale_codec::Encode;
+use sc_client_api::BlockBackend;
+use sc_service::chain_ops::export_finality_chain;
+use sp_blockchain::HeaderBackend;
+use sp_core::hexdisplay::HexDisplay;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use std::{fmt::Debug, fs, path::PathBuf, str::FromStr, sync::Arc};
+use structopt::StructOpt;
+
+/// The `export-finality-chain` command used to export the finalized headers and justifications
+/// a light client needs to follow the authority set changes of the chain.
+#[derive(Debug, StructOpt)]
+pub struct ExportFinalityChainCmd {
+	/// Number of the last block to export. It must be finalized.
+	#[structopt(long, value_name = "BLOCK")]
+	pub to: GenericNumber,
+
+	/// File the chain is written to.
+	#[structopt(long, parse(from_os_str), value_name = "PATH")]
+	pub out: PathBuf,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl ExportFinalityChainCmd {
+	/// Run the `export-finality-chain` command
+	///
+	/// `signals_change` tells whether a header signals a change of the authority set. The chain
+	/// is written as JSON, with the encoded headers and justifications of each entry and a
+	/// checksum chained with the one of the previous entry, so that the final checksum covers
+	/// the whole export.
+	pub async fn run<B, C>(
+		&self,
+		client: Arc<C>,
+		signals_change: impl Fn(&B::Header) -> bool,
+	) -> error::Result<()>
+	where
+		B: BlockT,
+		C: BlockBackend<B> + HeaderBackend<B>,
+		<<B::Header as HeaderT>::Number as FromStr>::Err: Debug,
+	{
+		let to = self.to.parse()?;
+		info!("Exporting the finality chain up to block #{}", to);
+
+		let mut checksum = [0u8; 32];
+		let mut entries = Vec::new();
+		export_finality_chain(&*client, to, signals_change, |entry| {
+			checksum = entry.checksum(&checksum);
+			entries.push(serde_json::json!({
+				"number": format!("{}", entry.header.number()),
+				"hash": format!("{:?}", entry.header.hash()),
+				"signalsChange": entry.signals_change,
+				"header": format!("0x{}", HexDisplay::from(&entry.header.encode())),
+				"justification": entry.justification.as_ref()
+					.map(|justification| format!("0x{}", HexDisplay::from(justification))),
+				"checksum": format!("0x{}", HexDisplay::from(&checksum)),
+			}));
+			Ok(())
+		})?;
+		let entry_count = entries.len();
+
+		let json = serde_json::json!({
+			"genesisHash": format!("{:?}", client.info().genesis_hash),
+			"entries": entries,
+			"checksum": format!("0x{}", HexDisplay::from(&checksum)),
+		});
+		let json = serde_json::to_string_pretty(&json)
+			.map_err(|e| format!("Failed to serialize the finality chain: {}", e))?;
+		fs::write(&self.out, json)?;
+
+		info!("Wrote {} entries to {}", entry_count, self.out.display());
+		Ok(())
+	}
+}
+
+impl CliConfiguration for ExportFinalityChainCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
@@ -0,0 +1,136 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::error;
+use crate::params::{GenericNumber, DatabaseParams, PruningParams, SharedParams};
+use crate::CliConfiguration;
+use log::info;
+use sc_service::{
+	config::DatabaseConfig, chain_ops::{export_headers, last_exported_header},
+};
+use sc_client_api::{BlockBackend, UsageProvider};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, One};
+use std::fmt::Debug;
+use std::fs;
+use std::io::{self, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+use structopt::StructOpt;
+
+/// The `export-headers` command used to export the header chain.
+#[derive(Debug, StructOpt)]
+pub struct ExportHeadersCmd {
+	/// Output file name.
+	#[structopt(parse(from_os_str))]
+	pub output: PathBuf,
+
+	/// Specify starting block number.
+	///
+	/// Default is 1.
+	#[structopt(long = "from", value_name = "BLOCK")]
+	pub from: Option<GenericNumber>,
+
+	/// Specify last block number.
+	///
+	/// Default is best block.
+	#[structopt(long = "to", value_name = "BLOCK")]
+	pub to: Option<GenericNumber>,
+
+	/// Include the justifications of finalized blocks.
+	#[structopt(long)]
+	pub justifications: bool,
+
+	/// Resume a previous export into the same output file.
+	///
+	/// Any truncated or corrupted trailing entry is discarded and the export continues after the
+	/// last valid header. `--from` is ignored if the file already contains valid headers.
+	#[structopt(long)]
+	pub resume: bool,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl ExportHeadersCmd {
+	/// Run the export-headers command
+	pub async fn run<B, C>(
+		&self,
+		client: Arc<C>,
+		database_config: DatabaseConfig,
+	) -> error::Result<()>
+	where
+		B: BlockT,
+		C: HeaderBackend<B> + BlockBackend<B> + UsageProvider<B> + 'static,
+		<<B::Header as HeaderT>::Number as FromStr>::Err: Debug,
+	{
+		if let DatabaseConfig::RocksDb { ref path, .. } = database_config {
+			info!("DB path: {}", path.display());
+		}
+
+		let mut from = self.from.as_ref()
+			.and_then(|f| f.parse().ok())
+			.unwrap_or_else(|| 1u32.into());
+		let to = self.to.as_ref().and_then(|t| t.parse().ok());
+
+		let file = if self.resume && self.output.exists() {
+			let mut file = fs::OpenOptions::new().read(true).write(true).open(&self.output)?;
+			let valid_len = match last_exported_header::<B>(io::BufReader::new(&mut file))? {
+				Some((number, valid_len)) => {
+					info!("Resuming headers export after #{}", number);
+					from = number + One::one();
+					valid_len
+				},
+				None => 0,
+			};
+			file.set_len(valid_len)?;
+			file.seek(SeekFrom::End(0))?;
+			file
+		} else {
+			fs::File::create(&self.output)?
+		};
+
+		export_headers(client, io::BufWriter::new(file), from, to, self.justifications)
+			.await
+			.map_err(Into::into)
+	}
+}
+
+impl CliConfiguration for ExportHeadersCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
@@ -0,0 +1,185 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	CliConfiguration, error, params::{DatabaseParams, GenericNumber, PruningParams, SharedParams},
+};
+use log::info;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::{Block as BlockT, Header as HeaderT, One, Zero}};
+use std::{
+	fmt::Debug, fs, io::{self, Read, Seek, SeekFrom, Write}, path::PathBuf, str::FromStr,
+	sync::Arc,
+};
+use structopt::StructOpt;
+
+/// First line of the exported CSV file.
+const CSV_HEADER: &str = "number,hash,parent_hash\n";
+
+/// How many bytes at the end of a previous export are scanned for its last complete row.
+const RESUME_SCAN_LEN: u64 = 4096;
+
+/// The `export-index` command used to export the number, hash and parent hash of the blocks of
+/// the canonical chain into a CSV file.
+#[derive(Debug, StructOpt)]
+pub struct ExportIndexCmd {
+	/// Number of the first block to export. Defaults to the genesis block.
+	#[structopt(long, value_name = "BLOCK")]
+	pub from: Option<GenericNumber>,
+
+	/// Number of the last block to export. Defaults to the best block.
+	#[structopt(long, value_name = "BLOCK")]
+	pub to: Option<GenericNumber>,
+
+	/// CSV file the index is written to.
+	#[structopt(long, parse(from_os_str), value_name = "PATH")]
+	pub out: PathBuf,
+
+	/// Resume a previous export into the same file.
+	///
+	/// Any truncated trailing row is discarded and the export continues after the last complete
+	/// one. `--from` is ignored if the file already contains rows.
+	#[structopt(long)]
+	pub resume: bool,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl ExportIndexCmd {
+	/// Run the `export-index` command
+	///
+	/// The rows are written as the headers are read, so that the whole index is never kept in
+	/// memory.
+	pub async fn run<B, C>(&self, client: Arc<C>) -> error::Result<()>
+	where
+		B: BlockT,
+		C: HeaderBackend<B>,
+		<<B::Header as HeaderT>::Number as FromStr>::Err: Debug,
+	{
+		let mut from = self.from.as_ref()
+			.map(|f| f.parse())
+			.transpose()?
+			.unwrap_or_else(Zero::zero);
+		let to = match &self.to {
+			Some(to) => to.parse()?,
+			None => client.info().best_number,
+		};
+
+		let mut file = if self.resume && self.out.exists() {
+			let mut file = fs::OpenOptions::new().read(true).write(true).open(&self.out)?;
+			match last_exported_row(&mut file)? {
+				Some((number, valid_len)) => {
+					let number: <B::Header as HeaderT>::Number = number.parse().map_err(|e| {
+						format!("Invalid block number {} in {}: {:?}", number, self.out.display(), e)
+					})?;
+					info!("Resuming index export after #{}", number);
+					from = number + One::one();
+					file.set_len(valid_len)?;
+					file.seek(SeekFrom::End(0))?;
+				},
+				None => {
+					file.set_len(0)?;
+					file.write_all(CSV_HEADER.as_bytes())?;
+				},
+			}
+			file
+		} else {
+			let mut file = fs::File::create(&self.out)?;
+			file.write_all(CSV_HEADER.as_bytes())?;
+			file
+		};
+
+		if from > to {
+			info!("Nothing to export, the index already goes up to #{}", to);
+			return Ok(());
+		}
+
+		info!("Exporting the index of blocks #{} to #{}", from, to);
+		let mut output = io::BufWriter::new(&mut file);
+		let mut number = from;
+		loop {
+			let header = client.header(BlockId::Number(number))?
+				.ok_or_else(|| format!("Block #{} not found", number))?;
+			writeln!(output, "{},{:?},{:?}", number, header.hash(), header.parent_hash())?;
+
+			if (number % 10000u32.into()).is_zero() {
+				info!("#{}", number);
+			}
+			if number == to {
+				break;
+			}
+			number += One::one();
+		}
+		output.flush()?;
+
+		info!("Exported the index up to #{} into {}", to, self.out.display());
+		Ok(())
+	}
+}
+
+/// Find the last complete row of a previous export.
+///
+/// Returns its block number along with the length of the file up to the end of that row, or
+/// `None` if the file contains no complete row.
+fn last_exported_row(file: &mut fs::File) -> io::Result<Option<(String, u64)>> {
+	let len = file.metadata()?.len();
+	let start = len.saturating_sub(RESUME_SCAN_LEN);
+	file.seek(SeekFrom::Start(start))?;
+	let mut tail = Vec::new();
+	file.read_to_end(&mut tail)?;
+
+	// Only the rows terminated by a newline are complete.
+	let end = match tail.iter().rposition(|b| *b == b'\n') {
+		Some(end) => end,
+		None => return Ok(None),
+	};
+	let row_start = tail[..end].iter().rposition(|b| *b == b'\n').map_or(0, |p| p + 1);
+	let row = String::from_utf8_lossy(&tail[row_start..end]);
+	let number = row.split(',').next().unwrap_or_default();
+
+	if number.is_empty() || !number.bytes().all(|b| b.is_ascii_digit()) {
+		// Either the CSV header, or garbage.
+		return Ok(None);
+	}
+
+	Ok(Some((number.to_string(), start + end as u64 + 1)))
+}
+
+impl CliConfiguration for ExportIndexCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
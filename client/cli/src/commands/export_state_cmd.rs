@@ -17,11 +17,12 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{
-	CliConfiguration, error, params::{PruningParams, SharedParams, BlockNumberOrHash},
+	CliConfiguration, error, commands::utils::output_writer,
+	params::{PruningParams, SharedParams, BlockNumberOrHash},
 };
 use log::info;
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
-use std::{fmt::Debug, str::FromStr, io::Write, sync::Arc};
+use std::{fmt::Debug, path::PathBuf, str::FromStr, io::Write, sync::Arc};
 use structopt::StructOpt;
 use sc_client_api::{StorageProvider, UsageProvider};
 
@@ -33,6 +34,10 @@ pub struct ExportStateCmd {
 	#[structopt(value_name = "HASH or NUMBER")]
 	pub input: Option<BlockNumberOrHash>,
 
+	/// Output file name, or stdout if `-` or unspecified.
+	#[structopt(long, short = "o", parse(from_os_str), value_name = "PATH")]
+	pub output: Option<PathBuf>,
+
 	#[allow(missing_docs)]
 	#[structopt(flatten)]
 	pub shared_params: SharedParams,
@@ -43,11 +48,18 @@ pub struct ExportStateCmd {
 }
 
 impl ExportStateCmd {
-	/// Run the `export-state` command
+	/// Open the output of the chain spec, see [`output_writer`].
+	pub fn output(&self) -> error::Result<Box<dyn Write>> {
+		Ok(output_writer(self.output.as_deref())?)
+	}
+
+	/// Run the `export-state` command, writing the chain spec to `output`, see
+	/// [`ExportStateCmd::output`].
 	pub async fn run<B, BA, C>(
 		&self,
 		client: Arc<C>,
 		mut input_spec: Box<dyn sc_service::ChainSpec>,
+		mut output: Box<dyn Write>,
 	) -> error::Result<()>
 	where
 		B: BlockT,
@@ -64,9 +76,8 @@ impl ExportStateCmd {
 
 		info!("Generating new chain spec...");
 		let json = sc_service::chain_ops::build_spec(&*input_spec, true)?;
-		if std::io::stdout().write_all(json.as_bytes()).is_err() {
-			let _ = std::io::stderr().write_all(b"Error writing to stdout\n");
-		}
+		output.write_all(json.as_bytes())?;
+		output.flush()?;
 		Ok(())
 	}
 }
@@ -0,0 +1,95 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{error, RunCmd};
+use log::info;
+use sc_client_api::UsageProvider;
+use sp_runtime::traits::{Block as BlockT, UniqueSaturatedInto};
+use std::{sync::Arc, time::{Duration, Instant}};
+use structopt::StructOpt;
+
+/// The `finality-lag` command used to run the node for a while and measure how far finality
+/// trails the best block.
+#[derive(Debug, StructOpt)]
+pub struct FinalityLagCmd {
+	/// How long to measure for, in seconds.
+	#[structopt(long, value_name = "SECONDS")]
+	pub duration: u64,
+
+	/// Interval between two samples, in seconds.
+	#[structopt(long, value_name = "SECONDS", default_value = "6")]
+	pub interval: u64,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub run: RunCmd,
+}
+
+impl FinalityLagCmd {
+	/// Run the `finality-lag` command
+	///
+	/// `client` must be the client of a node started with the configuration of the `run`
+	/// parameters. Every sample is printed, followed by the statistics of the lag.
+	pub async fn run<B, C>(&self, client: Arc<C>) -> error::Result<()>
+	where
+		B: BlockT,
+		C: UsageProvider<B>,
+	{
+		if self.interval == 0 {
+			return Err(error::Error::Input("The interval must not be zero".into()));
+		}
+
+		let started = Instant::now();
+		let duration = Duration::from_secs(self.duration);
+		let mut interval = tokio::time::interval(Duration::from_secs(self.interval));
+		let mut lags = Vec::new();
+
+		println!("seconds\tbest\tfinalized\tlag");
+		loop {
+			interval.tick().await;
+			let elapsed = started.elapsed();
+			if elapsed > duration {
+				break;
+			}
+
+			let info = client.usage_info().chain;
+			let best: u64 = info.best_number.unique_saturated_into();
+			let finalized: u64 = info.finalized_number.unique_saturated_into();
+			let lag = best.saturating_sub(finalized);
+			println!("{}\t{}\t{}\t{}", elapsed.as_secs(), best, finalized, lag);
+			lags.push(lag);
+		}
+
+		if lags.is_empty() {
+			return Err(error::Error::Input("The duration is too short to take any sample".into()));
+		}
+
+		lags.sort();
+		let mean = lags.iter().sum::<u64>() as f64 / lags.len() as f64;
+		info!(
+			"Finality lag over {} sample(s): min {}, median {}, mean {:.1}, max {}",
+			lags.len(),
+			lags[0],
+			lags[lags.len() / 2],
+			mean,
+			lags[lags.len() - 1],
+		);
+
+		Ok(())
+	}
+}
@@ -0,0 +1,98 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{CliConfiguration, DISK_USAGE_EXIT_CODE, RESTART_EXIT_CODE, error, params::SharedParams};
+use sc_service::Configuration;
+use std::fmt::Display;
+use structopt::StructOpt;
+
+/// The `gen-systemd-unit` command used to print a systemd service file running this node.
+#[derive(Debug, StructOpt)]
+pub struct GenSystemdUnitCmd {
+	/// User the service runs as.
+	#[structopt(long, value_name = "USER")]
+	pub user: Option<String>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl GenSystemdUnitCmd {
+	/// Run the `gen-systemd-unit` command
+	///
+	/// The unit runs the current binary with the chain and base path of `config`. Nothing is
+	/// installed, the unit is only printed.
+	pub fn run(&self, config: &Configuration) -> error::Result<()> {
+		let binary = std::env::current_exe()?;
+		let base_path = config.base_path.as_ref()
+			.ok_or_else(|| error::Error::Input("A base path is required".into()))?
+			.path();
+
+		println!("[Unit]");
+		println!("Description={}", config.impl_name);
+		println!("After=network-online.target");
+		println!("Wants=network-online.target");
+		println!();
+		println!("[Service]");
+		if let Some(user) = &self.user {
+			println!("User={}", user);
+		}
+		println!(
+			"ExecStart={} --chain {} --base-path {}",
+			quote(binary.display()),
+			quote(config.chain_spec.id()),
+			quote(base_path.display()),
+		);
+		println!("Restart=on-failure");
+		println!("RestartSec=10");
+		println!("RestartForceExitStatus={}", RESTART_EXIT_CODE);
+		println!("RestartPreventExitStatus={}", DISK_USAGE_EXIT_CODE);
+		println!("LimitNOFILE=65536");
+		println!("NoNewPrivileges=true");
+		println!("ProtectSystem=strict");
+		println!("ProtectHome=true");
+		println!("ReadWritePaths={}", quote(base_path.display()));
+		println!("PrivateTmp=true");
+		println!("PrivateDevices=true");
+		println!("ProtectKernelTunables=true");
+		println!("ProtectKernelModules=true");
+		println!("ProtectControlGroups=true");
+		println!();
+		println!("[Install]");
+		println!("WantedBy=multi-user.target");
+
+		Ok(())
+	}
+}
+
+/// Quote a value of the unit if it contains whitespace.
+fn quote(value: impl Display) -> String {
+	let value = value.to_string();
+	if value.contains(char::is_whitespace) {
+		format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+	} else {
+		value
+	}
+}
+
+impl CliConfiguration for GenSystemdUnitCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+}
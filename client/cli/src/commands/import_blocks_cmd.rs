@@ -19,13 +19,15 @@
 use crate::error;
 use crate::params::ImportParams;
 use crate::params::SharedParams;
-use crate::CliConfiguration;
-use sc_service::chain_ops::import_blocks;
+use crate::{CliConfiguration, CliSubcommand};
+use sc_service::{chain_ops::import_blocks, Configuration};
 use sp_runtime::traits::Block as BlockT;
 use std::fmt::Debug;
 use std::fs;
+use std::future::Future;
 use std::io::{self, Read, Seek};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
 use std::sync::Arc;
 use structopt::StructOpt;
 use sc_client_api::UsageProvider;
@@ -33,7 +35,7 @@ use sc_client_api::UsageProvider;
 /// The `import-blocks` command used to import blocks.
 #[derive(Debug, StructOpt)]
 pub struct ImportBlocksCmd {
-	/// Input file or stdin if unspecified.
+	/// Input file, or stdin if unspecified or `-`.
 	#[structopt(parse(from_os_str))]
 	pub input: Option<PathBuf>,
 
@@ -47,6 +49,13 @@ pub struct ImportBlocksCmd {
 	#[structopt(long)]
 	pub binary: bool,
 
+	/// Skip the blocks that fail to be decoded or imported instead of aborting.
+	///
+	/// Every skipped block is logged with its position in the input and a summary is logged once
+	/// the import is over.
+	#[structopt(long)]
+	pub skip_corrupt: bool,
+
 	#[allow(missing_docs)]
 	#[structopt(flatten)]
 	pub shared_params: SharedParams,
@@ -62,6 +71,21 @@ trait ReadPlusSeek: Read + Seek {}
 impl<T: Read + Seek> ReadPlusSeek for T {}
 
 impl ImportBlocksCmd {
+	/// Open the input of the blocks, the file or stdin.
+	///
+	/// Stdin is read entirely before the import starts, since the blocks are read twice. Only the
+	/// logs are written meanwhile, to stderr.
+	fn input(&self) -> io::Result<Box<dyn ReadPlusSeek + Send>> {
+		Ok(match &self.input {
+			Some(filename) if filename != Path::new("-") => Box::new(fs::File::open(filename)?),
+			_ => {
+				let mut buffer = Vec::new();
+				io::stdin().lock().read_to_end(&mut buffer)?;
+				Box::new(io::Cursor::new(buffer))
+			},
+		})
+	}
+
 	/// Run the import-blocks command
 	pub async fn run<B, C, IQ>(
 		&self,
@@ -73,21 +97,28 @@ impl ImportBlocksCmd {
 		B: BlockT + for<'de> serde::Deserialize<'de>,
 		IQ: sc_service::ImportQueue<B> + 'static,
 	{
-		let file: Box<dyn ReadPlusSeek + Send> = match &self.input {
-			Some(filename) => Box::new(fs::File::open(filename)?),
-			None => {
-				let mut buffer = Vec::new();
-				io::stdin().read_to_end(&mut buffer)?;
-				Box::new(io::Cursor::new(buffer))
-			}
-		};
-
-		import_blocks(client, import_queue, file, false, self.binary)
+		let input = self.input()?;
+		import_blocks(client, import_queue, input, false, self.binary, self.skip_corrupt)
 			.await
 			.map_err(Into::into)
 	}
 }
 
+impl<B, C, IQ> CliSubcommand<B, (Arc<C>, IQ)> for ImportBlocksCmd
+where
+	C: UsageProvider<B> + Send + Sync + 'static,
+	B: BlockT + for<'de> serde::Deserialize<'de>,
+	IQ: sc_service::ImportQueue<B> + 'static,
+{
+	fn run<'a>(
+		&'a self,
+		(client, import_queue): (Arc<C>, IQ),
+		_config: Configuration,
+	) -> Pin<Box<dyn Future<Output = error::Result<()>> + 'a>> {
+		Box::pin(ImportBlocksCmd::run(self, client, import_queue))
+	}
+}
+
 impl CliConfiguration for ImportBlocksCmd {
 	fn shared_params(&self) -> &SharedParams {
 		&self.shared_params
@@ -97,3 +128,97 @@ impl CliConfiguration for ImportBlocksCmd {
 		Some(&self.import_params)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sc_block_builder::BlockBuilderProvider;
+	use sc_service::chain_ops::export_blocks;
+	use std::{env, io::Write, process::{Command, Stdio}};
+	use substrate_test_runtime_client::{
+		prelude::*,
+		runtime::Block,
+		sp_consensus::{
+			import_queue::{BasicQueue, Verifier},
+			BlockImportParams, BlockOrigin, ForkChoiceStrategy,
+		},
+	};
+
+	/// Number of blocks exported, then piped to the import.
+	const PIPED_BLOCKS: u64 = 3;
+
+	/// Imports the blocks as they are, on top of the longest chain.
+	struct PassThroughVerifier;
+
+	impl Verifier<Block> for PassThroughVerifier {
+		fn verify(
+			&mut self,
+			origin: BlockOrigin,
+			header: <Block as BlockT>::Header,
+			justification: Option<sp_runtime::Justification>,
+			body: Option<Vec<<Block as BlockT>::Extrinsic>>,
+		) -> Result<
+			(
+				BlockImportParams<Block, ()>,
+				Option<Vec<(sp_blockchain::well_known_cache_keys::Id, Vec<u8>)>>,
+			),
+			String,
+		> {
+			let mut import = BlockImportParams::new(origin, header);
+			import.body = body;
+			import.justification = justification;
+			import.fork_choice = Some(ForkChoiceStrategy::LongestChain);
+			Ok((import, None))
+		}
+	}
+
+	#[test]
+	fn dash_reads_the_blocks_from_stdin() {
+		let mut client = Arc::new(TestClientBuilder::new().build());
+		for _ in 0..PIPED_BLOCKS {
+			let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+			client.import(BlockOrigin::Own, block).unwrap();
+		}
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("blocks.bin");
+		let file = fs::File::create(&path).unwrap();
+		let export = export_blocks::<Block, _>(client, file, 1, None, true);
+		futures::executor::block_on(export).unwrap();
+
+		let executable = env::current_exe().unwrap();
+		let mut child = Command::new(executable)
+			.env("READ_PIPED_BLOCKS", "1")
+			.args(&["--nocapture", "dash_reads_the_blocks_from_stdin_entrypoint"])
+			.stdin(Stdio::piped())
+			.stdout(Stdio::piped())
+			.spawn()
+			.unwrap();
+		child.stdin.take().unwrap().write_all(&fs::read(&path).unwrap()).unwrap();
+
+		let output = child.wait_with_output().unwrap();
+		let stdout = String::from_utf8(output.stdout).unwrap();
+		assert!(output.status.success(), "{}", stdout);
+		assert!(stdout.contains(&format!("best block #{}", PIPED_BLOCKS)), "{}", stdout);
+	}
+
+	/// This is no actual test, it will be used by the `dash_reads_the_blocks_from_stdin` test.
+	/// It imports the blocks piped by the test to its stdin into a chain of its own.
+	#[test]
+	fn dash_reads_the_blocks_from_stdin_entrypoint() {
+		if env::var("READ_PIPED_BLOCKS").is_err() {
+			return;
+		}
+
+		let client = Arc::new(TestClientBuilder::new().build());
+		let import_queue = BasicQueue::new(
+			PassThroughVerifier,
+			Box::new(client.clone()),
+			None,
+			&sp_core::testing::TaskExecutor::new(),
+			None,
+		);
+		let cmd = ImportBlocksCmd::from_iter(&["import-blocks", "--binary", "-"]);
+		futures::executor::block_on(cmd.run(client.clone(), import_queue)).unwrap();
+		println!("best block #{}", client.usage_info().chain.best_number);
+	}
+}
@@ -0,0 +1,122 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{error, RunCmd};
+use log::info;
+use sc_client_api::UsageProvider;
+use sp_runtime::traits::Block as BlockT;
+use std::{fs, io::{self, Write}, path::PathBuf, sync::Arc, time::{Duration, Instant}};
+use structopt::StructOpt;
+
+/// The `mem-profile` command used to run the node while sampling the memory used by its
+/// subsystems.
+#[derive(Debug, StructOpt)]
+pub struct MemProfileCmd {
+	/// How long to run the node for, in seconds.
+	#[structopt(long, value_name = "SECONDS")]
+	pub duration: u64,
+
+	/// Time between two samples, in seconds.
+	#[structopt(long, value_name = "SECONDS", default_value = "10")]
+	pub interval: u64,
+
+	/// CSV file the samples are written to, stdout if unspecified.
+	#[structopt(long, parse(from_os_str), value_name = "PATH")]
+	pub output: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub run: RunCmd,
+}
+
+impl MemProfileCmd {
+	/// Run the `mem-profile` command
+	///
+	/// `client` must be the client of a node started with the configuration of the `run`
+	/// parameters and `transaction_pool_size` must return the size of its transaction pool, in
+	/// bytes. Every sample is written as a CSV line with the elapsed seconds and the bytes used by
+	/// the state cache, the database cache, the state db and the transaction pool. The sizes the
+	/// client doesn't report are left empty. The network doesn't report the size of its buffers,
+	/// so they aren't sampled.
+	///
+	/// Returns once `duration` has elapsed, which stops the node.
+	pub async fn run<B, C>(
+		&self,
+		client: Arc<C>,
+		transaction_pool_size: impl Fn() -> usize,
+	) -> error::Result<()>
+	where
+		B: BlockT,
+		C: UsageProvider<B>,
+	{
+		if self.interval == 0 {
+			return Err(error::Error::Input("The interval must not be zero".into()));
+		}
+
+		let mut output: Box<dyn Write + Send> = match &self.output {
+			Some(path) => Box::new(fs::File::create(path)?),
+			None => Box::new(io::stdout()),
+		};
+		writeln!(
+			output,
+			"elapsed_secs,state_cache_bytes,database_cache_bytes,state_db_bytes,\
+			transaction_pool_bytes",
+		)?;
+
+		info!("Sampling memory usage every {}s for {}s", self.interval, self.duration);
+		let started = Instant::now();
+		let duration = Duration::from_secs(self.duration);
+
+		loop {
+			let usage = client.usage_info().usage.map(|usage| usage.memory);
+			let (state_cache, database_cache, state_db) = match usage {
+				Some(memory) => {
+					let state_db = memory.state_db.non_canonical.as_bytes()
+						+ memory.state_db.pruning.map_or(0, |size| size.as_bytes())
+						+ memory.state_db.pinned.as_bytes();
+					(
+						memory.state_cache.as_bytes().to_string(),
+						memory.database_cache.as_bytes().to_string(),
+						state_db.to_string(),
+					)
+				},
+				None => Default::default(),
+			};
+			writeln!(
+				output,
+				"{},{},{},{},{}",
+				started.elapsed().as_secs(),
+				state_cache,
+				database_cache,
+				state_db,
+				transaction_pool_size(),
+			)?;
+			output.flush()?;
+
+			let elapsed = started.elapsed();
+			if elapsed >= duration {
+				break;
+			}
+			let interval = Duration::from_secs(self.interval);
+			tokio::time::delay_for(interval.min(duration - elapsed)).await;
+		}
+
+		info!("Memory profiling over");
+		Ok(())
+	}
+}
@@ -0,0 +1,287 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+
+use crate::{
+	CliConfiguration, error,
+	params::{BlockNumberOrHash, DatabaseParams, PruningParams, SharedParams},
+};
+use log::info;
+use sc_client_api::{ExecutorProvider, UsageProvider};
+use sc_service::chain_ops::{runtime_metadata, wasm_metadata};
+use sp_core::traits::CallInWasm;
+use sp_runtime::{generic::BlockId, traits::{Block as BlockT, Header as HeaderT}};
+use std::{collections::BTreeMap, fmt::Debug, fs, path::PathBuf, str::FromStr, sync::Arc};
+use structopt::StructOpt;
+
+/// The items of a runtime metadata, by pallet name.
+///
+/// Each item is keyed by its kind and name, e.g. `call transfer` or `storage Account`, and maps
+/// to a description of its signature that changes whenever the item does.
+pub type MetadataItems = BTreeMap<String, BTreeMap<String, String>>;
+
+/// A difference between two runtime metadata.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MetadataChange {
+	/// A pallet only exists in the new metadata.
+	PalletAdded(String),
+	/// A pallet only exists in the old metadata.
+	PalletRemoved(String),
+	/// An item of a pallet present in both only exists in the new metadata.
+	ItemAdded {
+		/// Name of the pallet.
+		pallet: String,
+		/// Kind and name of the item.
+		item: String,
+		/// Signature of the item.
+		new: String,
+	},
+	/// An item of a pallet present in both only exists in the old metadata.
+	ItemRemoved {
+		/// Name of the pallet.
+		pallet: String,
+		/// Kind and name of the item.
+		item: String,
+		/// Signature of the item.
+		old: String,
+	},
+	/// An item has a different signature in both metadata.
+	ItemChanged {
+		/// Name of the pallet.
+		pallet: String,
+		/// Kind and name of the item.
+		item: String,
+		/// Signature in the old metadata.
+		old: String,
+		/// Signature in the new metadata.
+		new: String,
+	},
+}
+
+impl MetadataChange {
+	fn to_json(&self) -> serde_json::Value {
+		match self {
+			MetadataChange::PalletAdded(pallet) =>
+				serde_json::json!({ "change": "added", "pallet": pallet }),
+			MetadataChange::PalletRemoved(pallet) =>
+				serde_json::json!({ "change": "removed", "pallet": pallet }),
+			MetadataChange::ItemAdded { pallet, item, new } =>
+				serde_json::json!({ "change": "added", "pallet": pallet, "item": item, "new": new }),
+			MetadataChange::ItemRemoved { pallet, item, old } =>
+				serde_json::json!({ "change": "removed", "pallet": pallet, "item": item, "old": old }),
+			MetadataChange::ItemChanged { pallet, item, old, new } => serde_json::json!({
+				"change": "changed", "pallet": pallet, "item": item, "old": old, "new": new,
+			}),
+		}
+	}
+}
+
+impl std::fmt::Display for MetadataChange {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self {
+			MetadataChange::PalletAdded(pallet) => write!(f, "+ {}", pallet),
+			MetadataChange::PalletRemoved(pallet) => write!(f, "- {}", pallet),
+			MetadataChange::ItemAdded { pallet, item, new } =>
+				write!(f, "+ {}: {} {}", pallet, item, new),
+			MetadataChange::ItemRemoved { pallet, item, old } =>
+				write!(f, "- {}: {} {}", pallet, item, old),
+			MetadataChange::ItemChanged { pallet, item, old, new } =>
+				write!(f, "~ {}: {} {} => {}", pallet, item, old, new),
+		}
+	}
+}
+
+/// Compare two runtime metadata.
+///
+/// The items of added or removed pallets are not listed individually.
+pub fn diff_metadata(old: &MetadataItems, new: &MetadataItems) -> Vec<MetadataChange> {
+	let mut changes = Vec::new();
+
+	for (pallet, old_items) in old {
+		let new_items = match new.get(pallet) {
+			Some(new_items) => new_items,
+			None => {
+				changes.push(MetadataChange::PalletRemoved(pallet.clone()));
+				continue;
+			}
+		};
+
+		for (item, old) in old_items {
+			match new_items.get(item) {
+				None => changes.push(MetadataChange::ItemRemoved {
+					pallet: pallet.clone(),
+					item: item.clone(),
+					old: old.clone(),
+				}),
+				Some(new) if new != old => changes.push(MetadataChange::ItemChanged {
+					pallet: pallet.clone(),
+					item: item.clone(),
+					old: old.clone(),
+					new: new.clone(),
+				}),
+				Some(_) => {},
+			}
+		}
+		for (item, new) in new_items.iter().filter(|(item, _)| !old_items.contains_key(*item)) {
+			changes.push(MetadataChange::ItemAdded {
+				pallet: pallet.clone(),
+				item: item.clone(),
+				new: new.clone(),
+			});
+		}
+	}
+
+	for pallet in new.keys().filter(|pallet| !old.contains_key(*pallet)) {
+		changes.push(MetadataChange::PalletAdded(pallet.clone()));
+	}
+
+	changes
+}
+
+/// The `metadata-diff` command used to compare the metadata of the on-chain runtime with the one
+/// of a wasm runtime blob.
+#[derive(Debug, StructOpt)]
+pub struct MetadataDiffCmd {
+	/// Path to the wasm runtime blob to compare against the on-chain runtime.
+	#[structopt(long, parse(from_os_str), value_name = "PATH")]
+	pub wasm: PathBuf,
+
+	/// Block hash or number whose runtime is compared.
+	///
+	/// Default is the best block.
+	#[structopt(long, value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	/// Print the differences as JSON instead of one line each.
+	#[structopt(long)]
+	pub json: bool,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl MetadataDiffCmd {
+	/// Run the `metadata-diff` command
+	///
+	/// The SCALE encoded metadata of both runtimes is turned into [`MetadataItems`] by `items`,
+	/// which depends on the metadata format of the chain.
+	pub async fn run<B, C>(
+		&self,
+		client: Arc<C>,
+		executor: impl CallInWasm,
+		items: impl Fn(&[u8]) -> Result<MetadataItems, String>,
+	) -> error::Result<()>
+	where
+		B: BlockT,
+		C: ExecutorProvider<B> + UsageProvider<B>,
+		B::Hash: FromStr,
+		<B::Hash as FromStr>::Err: Debug,
+		<<B::Header as HeaderT>::Number as FromStr>::Err: Debug,
+	{
+		let block = match self.at {
+			Some(ref at) => at.parse::<B>()?,
+			None => BlockId::Hash(client.usage_info().chain.best_hash),
+		};
+		info!("Comparing the metadata of the runtime at {} with {}", block, self.wasm.display());
+
+		let old = runtime_metadata(&*client, &block)?;
+		let old = items(&old).map_err(|e| format!("Invalid on-chain metadata: {}", e))?;
+		let new = wasm_metadata(&executor, &fs::read(&self.wasm)?)?;
+		let new = items(&new).map_err(|e| format!("Invalid wasm metadata: {}", e))?;
+
+		let changes = diff_metadata(&old, &new);
+		if self.json {
+			let changes = changes.iter().map(MetadataChange::to_json).collect::<Vec<_>>();
+			println!("{}", serde_json::to_string_pretty(&changes).map_err(|e| e.to_string())?);
+		} else {
+			changes.iter().for_each(|change| println!("{}", change));
+		}
+
+		info!("Found {} difference(s)", changes.len());
+		Ok(())
+	}
+}
+
+impl CliConfiguration for MetadataDiffCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn metadata(pallets: &[(&str, &[(&str, &str)])]) -> MetadataItems {
+		pallets.iter().map(|(pallet, items)| (
+			pallet.to_string(),
+			items.iter().map(|(item, signature)| (item.to_string(), signature.to_string())).collect(),
+		)).collect()
+	}
+
+	#[test]
+	fn diff_metadata_reports_added_removed_and_changed_items() {
+		let old = metadata(&[
+			("Balances", &[("call transfer", "(dest: Address)"), ("event Endowed", "(AccountId)")]),
+			("Sudo", &[("call sudo", "(call: Call)")]),
+		]);
+		let new = metadata(&[
+			("Balances", &[("call transfer", "(dest: Address, value: Balance)"), ("error Dust", "")]),
+			("Staking", &[("call bond", "()")]),
+		]);
+
+		assert_eq!(diff_metadata(&old, &new), vec![
+			MetadataChange::ItemChanged {
+				pallet: "Balances".into(),
+				item: "call transfer".into(),
+				old: "(dest: Address)".into(),
+				new: "(dest: Address, value: Balance)".into(),
+			},
+			MetadataChange::ItemRemoved {
+				pallet: "Balances".into(),
+				item: "event Endowed".into(),
+				old: "(AccountId)".into(),
+			},
+			MetadataChange::ItemAdded {
+				pallet: "Balances".into(),
+				item: "error Dust".into(),
+				new: "".into(),
+			},
+			MetadataChange::PalletRemoved("Sudo".into()),
+			MetadataChange::PalletAdded("Staking".into()),
+		]);
+		assert!(diff_metadata(&new, &new).is_empty());
+	}
+}
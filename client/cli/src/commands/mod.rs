@@ -15,17 +15,51 @@
 
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
+mod apply_extrinsic_cmd;
+mod audit_storage_cmd;
+mod authority_set_cmd;
+mod bench_crypto_cmd;
 mod build_spec_cmd;
+mod cache_genesis_cmd;
+mod chain_info_cmd;
 mod check_block_cmd;
+mod check_equivocations_cmd;
+mod check_key_permissions_cmd;
+mod check_offchain_db_cmd;
+mod db_runtime_version_cmd;
+mod decode_cmd;
+mod diff_state_cmd;
+mod dump_pool_cmd;
+mod export_balances_cmd;
 mod export_blocks_cmd;
+mod export_finality_chain_cmd;
+mod export_headers_cmd;
+mod export_index_cmd;
 mod export_state_cmd;
 mod import_blocks_cmd;
+mod mem_profile_cmd;
+mod metadata_diff_cmd;
+mod monitor_cmd;
+mod peers_snapshot_cmd;
 mod purge_chain_cmd;
 mod sign;
+mod simulate_authoring_cmd;
+mod smoke_runtime_cmd;
+mod storage_proof_cmd;
+mod subcommand;
 mod verify;
+mod verify_against_export_cmd;
+mod verify_events_cmd;
+mod verify_genesis_cmd;
 mod vanity;
+mod replay_reorg_cmd;
 mod revert_cmd;
+mod round_trip_check_cmd;
+mod rpc_sessions_cmd;
 mod run_cmd;
+mod finality_lag_cmd;
+mod fork_spec_cmd;
+mod gen_systemd_unit_cmd;
 mod generate_node_key;
 mod generate;
 mod insert;
@@ -35,13 +69,41 @@ mod key;
 pub mod utils;
 
 pub use self::{
+	apply_extrinsic_cmd::ApplyExtrinsicCmd,
+	audit_storage_cmd::AuditStorageCmd,
+	authority_set_cmd::{AuthoritySet, AuthoritySetCmd},
+	bench_crypto_cmd::BenchCryptoCmd,
 	build_spec_cmd::BuildSpecCmd,
+	cache_genesis_cmd::CacheGenesisCmd,
+	chain_info_cmd::ChainInfoCmd,
 	check_block_cmd::CheckBlockCmd,
+	check_equivocations_cmd::CheckEquivocationsCmd,
+	check_key_permissions_cmd::CheckKeyPermissionsCmd,
+	check_offchain_db_cmd::CheckOffchainDbCmd,
+	db_runtime_version_cmd::DbRuntimeVersionCmd,
+	decode_cmd::{DecodeCmd, DecodeFn, decode_to_debug, decode_to_json},
+	diff_state_cmd::DiffStateCmd,
+	dump_pool_cmd::DumpPoolCmd,
+	export_balances_cmd::ExportBalancesCmd,
 	export_blocks_cmd::ExportBlocksCmd,
+	export_finality_chain_cmd::ExportFinalityChainCmd,
+	export_headers_cmd::ExportHeadersCmd,
+	export_index_cmd::ExportIndexCmd,
 	export_state_cmd::ExportStateCmd,
 	import_blocks_cmd::ImportBlocksCmd,
+	mem_profile_cmd::MemProfileCmd,
+	metadata_diff_cmd::{MetadataChange, MetadataDiffCmd, MetadataItems, diff_metadata},
+	monitor_cmd::MonitorCmd,
+	peers_snapshot_cmd::PeersSnapshotCmd,
 	purge_chain_cmd::PurgeChainCmd,
 	sign::SignCmd,
+	simulate_authoring_cmd::SimulateAuthoringCmd,
+	smoke_runtime_cmd::SmokeRuntimeCmd,
+	storage_proof_cmd::StorageProofCmd,
+	subcommand::CliSubcommand,
+	finality_lag_cmd::FinalityLagCmd,
+	fork_spec_cmd::ForkSpecCmd,
+	gen_systemd_unit_cmd::GenSystemdUnitCmd,
 	generate::GenerateCmd,
 	insert::InsertCmd,
 	inspect_key::InspectKeyCmd,
@@ -50,6 +112,12 @@ pub use self::{
 	key::KeySubcommand,
 	vanity::VanityCmd,
 	verify::VerifyCmd,
+	verify_against_export_cmd::VerifyAgainstExportCmd,
+	verify_events_cmd::VerifyEventsCmd,
+	verify_genesis_cmd::VerifyGenesisCmd,
+	replay_reorg_cmd::ReplayReorgCmd,
 	revert_cmd::RevertCmd,
+	round_trip_check_cmd::RoundTripCheckCmd,
+	rpc_sessions_cmd::RpcSessionsCmd,
 	run_cmd::RunCmd,
 };
@@ -0,0 +1,94 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{error, RunCmd};
+use futures::{Stream, StreamExt};
+use log::{info, warn};
+use sc_network::NetworkStatus;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::{Block as BlockT, UniqueSaturatedInto};
+use std::{sync::Arc, time::{Duration, Instant}};
+use structopt::StructOpt;
+
+/// The `monitor` command used to run the node and fail as soon as it stays behind the network
+/// for too long.
+#[derive(Debug, StructOpt)]
+pub struct MonitorCmd {
+	/// Number of blocks the local best block may trail the best block seen on the network.
+	#[structopt(long, value_name = "BLOCKS")]
+	pub max_lag: u64,
+
+	/// How long the lag must exceed `--max-lag` before failing, in seconds.
+	#[structopt(long = "for", value_name = "SECONDS")]
+	pub for_secs: u64,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub run: RunCmd,
+}
+
+impl MonitorCmd {
+	/// Run the `monitor` command
+	///
+	/// `client` must be the client of a node started with the configuration of the `run`
+	/// parameters and `network_status` the status stream of its network. This only returns,
+	/// with an error, once the lag has been sustained for the configured duration, or when the
+	/// network stops.
+	pub async fn run<B, C>(
+		&self,
+		client: Arc<C>,
+		mut network_status: impl Stream<Item = NetworkStatus<B>> + Unpin,
+	) -> error::Result<()>
+	where
+		B: BlockT,
+		C: HeaderBackend<B>,
+	{
+		let allowed = Duration::from_secs(self.for_secs);
+		let mut lagging_since = None;
+
+		info!(
+			"Monitoring sync: failing when more than {} block(s) behind for {}s",
+			self.max_lag,
+			self.for_secs,
+		);
+		while let Some(status) = network_status.next().await {
+			let best: u64 = client.info().best_number.unique_saturated_into();
+			let best_seen: u64 = status.best_seen_block
+				.map(|n| n.unique_saturated_into())
+				.unwrap_or(best);
+			let lag = best_seen.saturating_sub(best);
+
+			if lag <= self.max_lag {
+				if lagging_since.take().is_some() {
+					info!("Caught up with the network (best #{}, best seen #{})", best, best_seen);
+				}
+				continue;
+			}
+
+			let since = *lagging_since.get_or_insert_with(|| {
+				warn!("Node is {} block(s) behind the network (best #{})", lag, best);
+				Instant::now()
+			});
+			if since.elapsed() >= allowed {
+				return Err(error::Error::SyncLag { lag, seconds: since.elapsed().as_secs() });
+			}
+		}
+
+		Err(error::Error::Input("The network status stream ended".into()))
+	}
+}
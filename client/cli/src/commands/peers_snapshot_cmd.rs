@@ -0,0 +1,89 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{error, runner::build_runtime};
+use futures::compat::Future01CompatExt;
+use jsonrpc_core::Params;
+use jsonrpc_core_client::{transports::http, RawClient};
+use serde_json::{json, Value};
+use structopt::StructOpt;
+
+/// The `peers-snapshot` command used to print, as JSON, the peers a running node is connected to.
+///
+/// It combines the `system_peers` and the unsafe `system_unstable_networkState` RPC methods, so
+/// the node must expose its unsafe RPC methods on the given endpoint.
+#[derive(Debug, StructOpt)]
+pub struct PeersSnapshotCmd {
+	/// HTTP RPC endpoint of the node.
+	#[structopt(long, value_name = "URL", default_value = "http://localhost:9933")]
+	pub rpc_url: String,
+}
+
+impl PeersSnapshotCmd {
+	/// Run the `peers-snapshot` command
+	///
+	/// Every peer is printed with its id, the address and direction of the connection, its
+	/// protocol version, version string and roles, and the best block it reported.
+	pub fn run(&self) -> error::Result<()> {
+		let (peers, network_state) = build_runtime()?.block_on(async {
+			let client: RawClient = http::connect(&self.rpc_url).compat().await
+				.map_err(|e| format!("Failed to connect to {}: {:?}", self.rpc_url, e))?;
+			let peers = self.call(&client, "system_peers").await?;
+			let network_state = self.call(&client, "system_unstable_networkState").await?;
+			Ok::<_, error::Error>((peers, network_state))
+		})?;
+
+		let peers = peers.as_array()
+			.ok_or_else(|| error::Error::Input("Unexpected response to `system_peers`".into()))?
+			.iter()
+			.map(|peer| {
+				let peer_id = peer["peerId"].as_str().unwrap_or_default();
+				let connection = &network_state["connectedPeers"][peer_id];
+				let (direction, address) = match &connection["endpoint"] {
+					Value::Object(endpoint) if endpoint.contains_key("dialing") =>
+						("out", endpoint["dialing"].clone()),
+					Value::Object(endpoint) if endpoint.contains_key("listening") =>
+						("in", endpoint["listening"]["sendBackAddr"].clone()),
+					_ => ("unknown", Value::Null),
+				};
+
+				json!({
+					"peerId": peer_id,
+					"address": address,
+					"direction": direction,
+					"protocolVersion": peer["protocolVersion"],
+					"versionString": connection["versionString"],
+					"roles": peer["roles"],
+					"bestHash": peer["bestHash"],
+					"bestNumber": peer["bestNumber"],
+					"latestPingTime": connection["latestPingTime"],
+				})
+			})
+			.collect::<Vec<_>>();
+
+		let output = serde_json::to_string_pretty(&peers)
+			.map_err(|e| format!("Failed to encode the peers: {}", e))?;
+		println!("{}", output);
+		Ok(())
+	}
+
+	async fn call(&self, client: &RawClient, method: &str) -> error::Result<Value> {
+		client.call_method(method, Params::None).compat().await
+			.map_err(|e| format!("Failed to call `{}`: {:?}", method, e).into())
+	}
+}
@@ -0,0 +1,147 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::error;
+use crate::params::{ImportParams, SharedParams};
+use crate::CliConfiguration;
+use futures::{future::FutureExt, pin_mut, select, stream::StreamExt};
+use log::info;
+use sc_client_api::{BlockImportNotification, BlockchainEvents, UsageProvider};
+use sc_service::chain_ops::import_blocks;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use structopt::StructOpt;
+
+/// The `replay-reorg` command used to import a sequence of possibly competing blocks and report
+/// the fork-choice decisions.
+#[derive(Debug, StructOpt)]
+pub struct ReplayReorgCmd {
+	/// File containing the blocks, in the format produced by `export-blocks`.
+	///
+	/// The blocks are imported in the order of the file, which may include competing forks.
+	#[structopt(long, parse(from_os_str), value_name = "PATH")]
+	pub file: PathBuf,
+
+	/// The blocks are stored in binary format rather than JSON.
+	#[structopt(long)]
+	pub binary: bool,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub import_params: ImportParams,
+}
+
+impl ReplayReorgCmd {
+	/// Run the replay-reorg command
+	pub async fn run<B, C, IQ>(
+		&self,
+		client: Arc<C>,
+		import_queue: IQ,
+	) -> error::Result<()>
+	where
+		C: UsageProvider<B> + BlockchainEvents<B> + Send + Sync + 'static,
+		B: BlockT + for<'de> serde::Deserialize<'de>,
+		IQ: sc_service::ImportQueue<B> + 'static,
+	{
+		let mut notifications = client.import_notification_stream().fuse();
+		let file = fs::File::open(&self.file)?;
+		let import = import_blocks(client.clone(), import_queue, file, false, self.binary, false)
+			.fuse();
+		pin_mut!(import);
+
+		let mut reorgs = 0;
+		loop {
+			select! {
+				notification = notifications.next() => {
+					if let Some(notification) = notification {
+						reorgs += report_import(&*client, notification);
+					}
+				},
+				res = import => {
+					res?;
+					break;
+				},
+			}
+		}
+
+		// Notifications of the last imported blocks may still be pending.
+		while let Some(Some(notification)) = notifications.next().now_or_never() {
+			reorgs += report_import(&*client, notification);
+		}
+
+		let info = client.usage_info().chain;
+		info!(
+			"Canonical chain: best #{} ({}), {} reorg(s) occurred",
+			info.best_number, info.best_hash, reorgs,
+		);
+
+		Ok(())
+	}
+}
+
+/// Log an imported block along with the best block of the client after its import.
+///
+/// Returns `1` if the import caused a reorg, `0` otherwise.
+fn report_import<B, C>(client: &C, notification: BlockImportNotification<B>) -> usize
+where
+	B: BlockT,
+	C: UsageProvider<B>,
+{
+	let best = client.usage_info().chain;
+	info!(
+		"Imported #{} ({}), new best: {}, best is now #{} ({})",
+		notification.header.number(),
+		notification.hash,
+		notification.is_new_best,
+		best.best_number,
+		best.best_hash,
+	);
+
+	match notification.tree_route {
+		Some(ref tree_route) if notification.is_new_best && !tree_route.retracted().is_empty() => {
+			let ancestor = tree_route.common_block();
+			info!(
+				"♻️  Reorg to #{} ({}), common ancestor #{} ({}), {} block(s) retracted, {} enacted",
+				notification.header.number(),
+				notification.hash,
+				ancestor.number,
+				ancestor.hash,
+				tree_route.retracted().len(),
+				tree_route.enacted().len() + 1,
+			);
+			1
+		},
+		_ => 0,
+	}
+}
+
+impl CliConfiguration for ReplayReorgCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn import_params(&self) -> Option<&ImportParams> {
+		Some(&self.import_params)
+	}
+}
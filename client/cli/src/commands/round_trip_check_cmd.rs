@@ -0,0 +1,150 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	CliConfiguration, error, params::{DatabaseParams, GenericNumber, PruningParams, SharedParams},
+};
+use log::info;
+use sc_client_api::{BlockBackend, UsageProvider};
+use sc_service::chain_ops::{export_blocks, import_blocks};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::{Block as BlockT, Header as HeaderT, One}};
+use std::{fmt::Debug, io::{self, Write}, str::FromStr, sync::{Arc, Mutex}};
+use structopt::StructOpt;
+
+/// The `round-trip-check` command used to check that exporting the blocks of the chain and
+/// importing them into a fresh database results in the same chain.
+#[derive(Debug, StructOpt)]
+pub struct RoundTripCheckCmd {
+	/// Number of the last block to export and re-import.
+	#[structopt(long, value_name = "BLOCK")]
+	pub to: GenericNumber,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl RoundTripCheckCmd {
+	/// Run the `round-trip-check` command
+	///
+	/// The blocks of `client` are exported in binary, imported into `fresh` through
+	/// `import_queue`, and the resulting headers are compared block by block. `fresh` is
+	/// expected to be a client on an empty database, typically an in-memory one.
+	pub async fn run<B, C, IQ>(
+		&self,
+		client: Arc<C>,
+		fresh: Arc<C>,
+		import_queue: IQ,
+	) -> error::Result<()>
+	where
+		B: BlockT + for<'de> serde::Deserialize<'de>,
+		C: BlockBackend<B> + UsageProvider<B> + HeaderBackend<B> + Send + Sync + 'static,
+		IQ: sc_service::ImportQueue<B> + 'static,
+		<<B::Header as HeaderT>::Number as FromStr>::Err: Debug,
+	{
+		let to = self.to.parse()?;
+		if to > client.usage_info().chain.best_number {
+			return Err(error::Error::Input(format!("Block #{} isn't imported yet", to)));
+		}
+
+		info!("Exporting blocks up to #{}", to);
+		let buffer = SharedBuffer::default();
+		export_blocks(client.clone(), buffer.clone(), One::one(), Some(to), true).await?;
+		let exported = buffer.take();
+
+		info!("Re-importing {} bytes of blocks into a fresh database", exported.len());
+		import_blocks(fresh.clone(), import_queue, io::Cursor::new(exported), false, true, false)
+			.await?;
+
+		let mut number = One::one();
+		while number <= to {
+			let header = |client: &C| client.header(BlockId::Number(number))?
+				.ok_or_else(|| error::Error::Input(format!("Block #{} is missing", number)));
+			let original = header(&*client)?;
+			let reimported = header(&*fresh).map_err(|_| error::Error::Input(
+				format!("Block #{} is missing after the re-import", number),
+			))?;
+
+			if original.state_root() != reimported.state_root() {
+				return Err(error::Error::Input(format!(
+					"Divergence at block #{}: state root {} after the re-import, {} originally",
+					number,
+					reimported.state_root(),
+					original.state_root(),
+				)));
+			}
+			if original.hash() != reimported.hash() {
+				return Err(error::Error::Input(format!(
+					"Divergence at block #{}: hash {} after the re-import, {} originally",
+					number,
+					reimported.hash(),
+					original.hash(),
+				)));
+			}
+
+			number = number + One::one();
+		}
+
+		info!("Blocks #1 to #{} round-trip losslessly", to);
+		Ok(())
+	}
+}
+
+/// A buffer that the blocks are exported to, and that is read back once they are all exported.
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuffer {
+	fn take(&self) -> Vec<u8> {
+		std::mem::take(&mut *self.0.lock().unwrap_or_else(|e| e.into_inner()))
+	}
+}
+
+impl Write for SharedBuffer {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.0.lock().unwrap_or_else(|e| e.into_inner()).extend_from_slice(buf);
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+impl CliConfiguration for RoundTripCheckCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
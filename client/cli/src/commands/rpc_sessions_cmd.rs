@@ -0,0 +1,71 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{error, runner::build_runtime};
+use futures::compat::Future01CompatExt;
+use jsonrpc_core::Params;
+use jsonrpc_core_client::{transports::http, RawClient};
+use serde_json::Value;
+use structopt::StructOpt;
+
+/// The `rpc-sessions` command used to list the WS RPC sessions of a running node, or to close
+/// one of them.
+///
+/// It uses the unsafe `rpc_sessions` and `rpc_killSession` RPC methods, so the node must expose
+/// its unsafe RPC methods on the given endpoint.
+#[derive(Debug, StructOpt)]
+pub struct RpcSessionsCmd {
+	/// HTTP RPC endpoint of the node.
+	#[structopt(long, value_name = "URL", default_value = "http://localhost:9933")]
+	pub rpc_url: String,
+
+	/// Close the session with this id instead of listing the sessions.
+	#[structopt(long, value_name = "ID")]
+	pub kill: Option<u64>,
+}
+
+impl RpcSessionsCmd {
+	/// Run the `rpc-sessions` command
+	///
+	/// Every session is printed, oldest first, with its id, its age in seconds and the origin of
+	/// its handshake, if any.
+	pub fn run(&self) -> error::Result<()> {
+		let (method, params) = match self.kill {
+			Some(id) => ("rpc_killSession", Params::Array(vec![id.into()])),
+			None => ("rpc_sessions", Params::None),
+		};
+
+		let response = build_runtime()?.block_on(async {
+			let client: RawClient = http::connect(&self.rpc_url).compat().await
+				.map_err(|e| format!("Failed to connect to {}: {:?}", self.rpc_url, e))?;
+			client.call_method(method, params).compat().await
+				.map_err(|e| error::Error::from(format!("Failed to call `{}`: {:?}", method, e)))
+		})?;
+
+		match (self.kill, response) {
+			(Some(id), Value::Bool(true)) => println!("Session {} closed", id),
+			(Some(id), _) => return Err(error::Error::Input(format!("No session {}", id))),
+			(None, sessions) => {
+				let output = serde_json::to_string_pretty(&sessions)
+					.map_err(|e| format!("Failed to encode the sessions: {}", e))?;
+				println!("{}", output);
+			},
+		}
+		Ok(())
+	}
+}
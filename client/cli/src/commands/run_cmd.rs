@@ -27,11 +27,15 @@ use crate::params::TransactionPoolParams;
 use crate::CliConfiguration;
 use regex::Regex;
 use sc_service::{
-	config::{BasePath, MultiaddrWithPeerId, PrometheusConfig, TransactionPoolOptions},
+	config::{
+		BasePath, MultiaddrWithPeerId, PrometheusConfig, RpcEndpoint, RpcTransport,
+		TransactionPoolOptions,
+	},
 	ChainSpec, Role,
 };
 use sc_telemetry::TelemetryEndpoints;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
 use structopt::StructOpt;
 
 /// The `run` command used to run a node.
@@ -135,9 +139,40 @@ pub struct RunCmd {
 	pub ws_port: Option<u16>,
 
 	/// Maximum number of WS RPC server connections.
+	///
+	/// Connections above the limit are rejected with an HTTP 503 response.
 	#[structopt(long = "ws-max-connections", value_name = "COUNT")]
 	pub ws_max_connections: Option<usize>,
 
+	/// Reject the calls to the RPC servers until the node has caught up with the network.
+	///
+	/// Meanwhile every call fails with a "syncing" error and the HTTP health endpoint reports
+	/// the node as unhealthy, so that load balancers don't route traffic to it.
+	#[structopt(long = "rpc-wait-for-sync")]
+	pub rpc_wait_for_sync: bool,
+
+	/// Start an additional RPC server with its own policy. Can be passed multiple times.
+	///
+	/// The format is `<http|ws>://<ADDRESS>[?<OPTIONS>]`, where the options are `&`-separated
+	/// among `methods=<auto|safe|unsafe>` (`auto` by default), `allow=<PATTERNS>` and
+	/// `deny=<PATTERNS>`. Patterns are comma-separated method names, or prefixes followed by `*`.
+	/// Example: `ws://0.0.0.0:9945?methods=safe&allow=chain_*,state_*&deny=state_getKeys`.
+	#[structopt(
+		long = "rpc-endpoint",
+		value_name = "ENDPOINT",
+		parse(try_from_str = parse_rpc_endpoint),
+		number_of_values = 1,
+	)]
+	pub rpc_endpoints: Vec<RpcEndpoint>,
+
+	/// Maximum number of notifications buffered for a subscription to new or finalized heads,
+	/// while the subscriber reads them slower than blocks are imported.
+	///
+	/// A subscriber with more notifications pending is sent an error and dropped. Unlimited by
+	/// default.
+	#[structopt(long = "rpc-max-buffered-notifications", value_name = "COUNT")]
+	pub rpc_max_buffered_notifications: Option<usize>,
+
 	/// Specify browser Origins allowed to access the HTTP & WS RPC servers.
 	///
 	/// A comma-separated list of origins (protocol://domain or special `null`
@@ -264,6 +299,11 @@ pub struct RunCmd {
 	/// which includes: database, node key and keystore.
 	#[structopt(long, conflicts_with = "base-path")]
 	pub tmp: bool,
+
+	/// Load the genesis state from a file written by the `cache-genesis` subcommand instead of
+	/// building it from the chain spec.
+	#[structopt(long, parse(from_os_str), value_name = "PATH")]
+	pub genesis_cache: Option<PathBuf>,
 }
 
 impl RunCmd {
@@ -409,6 +449,18 @@ impl CliConfiguration for RunCmd {
 		Ok(self.ws_max_connections)
 	}
 
+	fn rpc_wait_for_sync(&self) -> Result<bool> {
+		Ok(self.rpc_wait_for_sync)
+	}
+
+	fn rpc_endpoints(&self) -> Result<Vec<RpcEndpoint>> {
+		Ok(self.rpc_endpoints.clone())
+	}
+
+	fn rpc_max_buffered_notifications(&self) -> Result<Option<usize>> {
+		Ok(self.rpc_max_buffered_notifications)
+	}
+
 	fn rpc_cors(&self, is_dev: bool) -> Result<Option<Vec<String>>> {
 		Ok(self
 			.rpc_cors
@@ -560,6 +612,48 @@ fn parse_telemetry_endpoints(s: &str) -> std::result::Result<(String, u8), Telem
 	}
 }
 
+fn parse_rpc_endpoint(s: &str) -> std::result::Result<RpcEndpoint, String> {
+	let (transport, rest) = if s.starts_with("http://") {
+		(RpcTransport::Http, &s["http://".len()..])
+	} else if s.starts_with("ws://") {
+		(RpcTransport::Ws, &s["ws://".len()..])
+	} else {
+		return Err(format!("Invalid RPC endpoint {}: expected `http://` or `ws://`", s));
+	};
+
+	let (address, options) = match rest.find('?') {
+		Some(pos) => (&rest[..pos], &rest[pos + 1..]),
+		None => (rest, ""),
+	};
+	let address = address.parse()
+		.map_err(|e| format!("Invalid RPC endpoint address {}: {}", address, e))?;
+
+	let patterns = |value: &str| value.split(',')
+		.filter(|pattern| !pattern.is_empty())
+		.map(String::from)
+		.collect::<Vec<_>>();
+	let mut endpoint = RpcEndpoint {
+		transport,
+		address,
+		methods: sc_service::config::RpcMethods::Auto,
+		allow: None,
+		deny: Vec::new(),
+	};
+	for option in options.split('&').filter(|option| !option.is_empty()) {
+		let pos = option.find('=')
+			.ok_or_else(|| format!("Invalid RPC endpoint option {}: expected `<KEY>=<VALUE>`", option))?;
+		let value = &option[pos + 1..];
+		match &option[..pos] {
+			"methods" => endpoint.methods = value.parse::<RpcMethods>()?.into(),
+			"allow" => endpoint.allow = Some(patterns(value)),
+			"deny" => endpoint.deny = patterns(value),
+			key => return Err(format!("Unknown RPC endpoint option {}", key)),
+		}
+	}
+
+	Ok(endpoint)
+}
+
 /// CORS setting
 ///
 /// The type is introduced to overcome `Option<Option<T>>`
@@ -622,4 +716,24 @@ mod tests {
 		assert!(is_node_name_valid("www.visit.me").is_err());
 		assert!(is_node_name_valid("email@domain").is_err());
 	}
+
+	#[test]
+	fn parses_rpc_endpoints() {
+		let endpoint = parse_rpc_endpoint(
+			"ws://0.0.0.0:9945?methods=safe&allow=chain_*,state_call&deny=chain_getBlock",
+		).unwrap();
+		assert_eq!(endpoint.transport, RpcTransport::Ws);
+		assert_eq!(endpoint.address, "0.0.0.0:9945".parse().unwrap());
+		assert!(matches!(endpoint.methods, sc_service::config::RpcMethods::Safe));
+		assert_eq!(endpoint.allow, Some(vec!["chain_*".to_string(), "state_call".to_string()]));
+		assert_eq!(endpoint.deny, vec!["chain_getBlock".to_string()]);
+
+		let endpoint = parse_rpc_endpoint("http://127.0.0.1:9934").unwrap();
+		assert_eq!(endpoint.transport, RpcTransport::Http);
+		assert!(endpoint.allow.is_none());
+
+		assert!(parse_rpc_endpoint("tcp://127.0.0.1:9934").is_err());
+		assert!(parse_rpc_endpoint("http://127.0.0.1:9934?methods=some").is_err());
+		assert!(parse_rpc_endpoint("http://127.0.0.1:9934?pattern=x").is_err());
+	}
 }
@@ -0,0 +1,130 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{CliConfiguration, error, params::{DatabaseParams, PruningParams, SharedParams}};
+use chrono::{TimeZone, Utc};
+use log::info;
+use std::time::{SystemTime, UNIX_EPOCH};
+use structopt::StructOpt;
+
+/// The `simulate-authoring` command used to print the upcoming slots of the chain and the ones
+/// a hypothetical validator would author.
+#[derive(Debug, StructOpt)]
+pub struct SimulateAuthoringCmd {
+	/// Number of upcoming slots to simulate.
+	#[structopt(long, value_name = "COUNT")]
+	pub slots: u64,
+
+	/// Index of the hypothetical validator in the authority set.
+	#[structopt(long, value_name = "INDEX", default_value = "0")]
+	pub validator_index: u32,
+
+	/// Size of the authority set. Defaults to the one of the chain.
+	#[structopt(long, value_name = "COUNT")]
+	pub authorities: Option<u32>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl SimulateAuthoringCmd {
+	/// Run the `simulate-authoring` command
+	///
+	/// `slot_duration` is in milliseconds and `epoch_start` is the slot that started the current
+	/// epoch. Slots are assigned to the validators in turn, which is how Aura and the secondary
+	/// slots of BABE work; the primary slots of BABE are won through a VRF and can't be
+	/// predicted without the keys of the validator.
+	pub fn run(
+		&self,
+		slot_duration: u64,
+		epoch_length: u64,
+		epoch_start: u64,
+		authorities: u32,
+	) -> error::Result<()> {
+		let authorities = self.authorities.unwrap_or(authorities);
+		if slot_duration == 0 || epoch_length == 0 || authorities == 0 {
+			return Err(error::Error::Input(
+				"The slot duration, epoch length and authority count must not be zero".into(),
+			));
+		}
+		if self.validator_index >= authorities {
+			return Err(error::Error::Input(format!(
+				"The validator index must be lower than the number of authorities ({})",
+				authorities,
+			)));
+		}
+
+		let now = SystemTime::now().duration_since(UNIX_EPOCH)
+			.map_err(|e| format!("Invalid system time: {}", e))?;
+		let current_slot = now.as_millis() as u64 / slot_duration;
+		info!(
+			"Slot duration {}ms, epochs of {} slot(s), current slot #{}, {} authorities",
+			slot_duration,
+			epoch_length,
+			current_slot,
+			authorities,
+		);
+
+		let mut authored = 0;
+		println!("slot\tstart\tepoch\tauthor");
+		for slot in current_slot..current_slot.saturating_add(self.slots) {
+			let epoch = slot.saturating_sub(epoch_start) / epoch_length;
+			if slot > epoch_start && (slot - epoch_start) % epoch_length == 0 {
+				println!("# epoch {} starts", epoch);
+			}
+
+			let start = Utc.timestamp_millis((slot * slot_duration) as i64);
+			let author = slot % authorities as u64 == self.validator_index as u64;
+			if author {
+				authored += 1;
+			}
+			println!("{}\t{}\t+{}\t{}", slot, start.to_rfc3339(), epoch, if author { "yes" } else { "" });
+		}
+
+		info!(
+			"Validator #{} would author {} of the {} simulated slot(s)",
+			self.validator_index,
+			authored,
+			self.slots,
+		);
+		Ok(())
+	}
+}
+
+impl CliConfiguration for SimulateAuthoringCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
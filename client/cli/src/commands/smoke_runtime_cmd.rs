@@ -0,0 +1,105 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	CliConfiguration, error,
+	params::{BlockNumberOrHash, DatabaseParams, PruningParams, SharedParams},
+};
+use log::info;
+use parity_scale_codec::Decode;
+use sc_client_api::{CallExecutor, ExecutionStrategy, ExecutorProvider, UsageProvider};
+use sp_runtime::{generic::BlockId, traits::{Block as BlockT, Header as HeaderT}};
+use sp_version::RuntimeVersion;
+use std::{fmt::Debug, str::FromStr, sync::Arc, time::Instant};
+use structopt::StructOpt;
+
+/// The `smoke-runtime` command used to check that the on-chain wasm runtime can be loaded and
+/// executed.
+#[derive(Debug, StructOpt)]
+pub struct SmokeRuntimeCmd {
+	/// Block hash or number.
+	///
+	/// Default is the best block.
+	#[structopt(long, value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl SmokeRuntimeCmd {
+	/// Run the `smoke-runtime` command
+	///
+	/// `Core_version` is always executed with the wasm runtime from `:code`, never with the native
+	/// one, so that a broken blob or a missing host function makes the command fail.
+	pub async fn run<B, C>(&self, client: Arc<C>) -> error::Result<()>
+	where
+		B: BlockT,
+		C: ExecutorProvider<B> + UsageProvider<B>,
+		B::Hash: FromStr,
+		<B::Hash as FromStr>::Err: Debug,
+		<<B::Header as HeaderT>::Number as FromStr>::Err: Debug,
+	{
+		let block = match self.at {
+			Some(ref at) => at.parse::<B>()?,
+			None => BlockId::Hash(client.usage_info().chain.best_hash),
+		};
+		info!("Executing the wasm runtime at {}", block);
+
+		let started = Instant::now();
+		let result = client.executor().call(
+			&block,
+			"Core_version",
+			&[],
+			ExecutionStrategy::AlwaysWasm,
+			None,
+		);
+		let elapsed = started.elapsed();
+
+		let version = result
+			.map_err(|e| format!("Failed to execute the wasm runtime: {}", e))
+			.and_then(|encoded| RuntimeVersion::decode(&mut &encoded[..])
+				.map_err(|e| format!("Failed to decode the runtime version: {}", e)))?;
+
+		info!("✅ Wasm runtime {} executed `Core_version` in {:?}", version, elapsed);
+		Ok(())
+	}
+}
+
+impl CliConfiguration for SmokeRuntimeCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
@@ -0,0 +1,116 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	CliConfiguration, commands::utils::decode_hex, error,
+	params::{BlockNumberOrHash, DatabaseParams, PruningParams, SharedParams},
+};
+use log::info;
+use sc_client_api::ProofProvider;
+use sp_blockchain::HeaderBackend;
+use sp_core::hexdisplay::HexDisplay;
+use sp_runtime::{generic::BlockId, traits::{Block as BlockT, Header as HeaderT}};
+use std::{fmt::Debug, fs, path::PathBuf, str::FromStr, sync::Arc};
+use structopt::StructOpt;
+
+/// The `storage-proof` command used to generate the merkle proof of a storage key at a given
+/// block.
+#[derive(Debug, StructOpt)]
+pub struct StorageProofCmd {
+	/// Storage key to prove, in hex.
+	#[structopt(long, value_name = "HEX")]
+	pub key: String,
+
+	/// Block hash or number at which the proof is generated. Defaults to the best block.
+	#[structopt(long, value_name = "HASH or NUMBER")]
+	pub at: Option<BlockNumberOrHash>,
+
+	/// File the proof is written to.
+	#[structopt(long, parse(from_os_str), value_name = "PATH")]
+	pub out: PathBuf,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl StorageProofCmd {
+	/// Run the `storage-proof` command
+	///
+	/// The proof is written as JSON, along with the block hash and the state root it can be
+	/// checked against.
+	pub async fn run<B, C>(&self, client: Arc<C>) -> error::Result<()>
+	where
+		B: BlockT,
+		C: ProofProvider<B> + HeaderBackend<B>,
+		B::Hash: FromStr,
+		<B::Hash as FromStr>::Err: Debug,
+		<<B::Header as HeaderT>::Number as FromStr>::Err: Debug,
+	{
+		let key = decode_hex(&self.key)?;
+		let block_id = match &self.at {
+			Some(at) => at.parse::<B>()?,
+			None => BlockId::Hash(client.info().best_hash),
+		};
+		let header = client.header(block_id)?
+			.ok_or_else(|| format!("Block {} not found", block_id))?;
+		let hash = header.hash();
+
+		info!("Generating storage proof of 0x{} at {}", HexDisplay::from(&key), hash);
+		let proof = client.read_proof(&BlockId::Hash(hash), &mut std::iter::once(&key[..]))?;
+		let nodes = proof.iter_nodes()
+			.map(|node| format!("0x{}", HexDisplay::from(&node)))
+			.collect::<Vec<_>>();
+		let node_count = nodes.len();
+
+		let json = serde_json::json!({
+			"at": format!("{:?}", hash),
+			"stateRoot": format!("{:?}", header.state_root()),
+			"key": format!("0x{}", HexDisplay::from(&key)),
+			"proof": nodes,
+		});
+		let json = serde_json::to_string_pretty(&json)
+			.map_err(|e| format!("Failed to serialize the proof: {}", e))?;
+		fs::write(&self.out, json)?;
+
+		info!("Wrote a proof of {} trie node(s) to {}", node_count, self.out.display());
+		Ok(())
+	}
+}
+
+impl CliConfiguration for StorageProofCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
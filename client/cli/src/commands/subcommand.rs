@@ -0,0 +1,36 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::error;
+use sc_service::Configuration;
+use sp_runtime::traits::Block as BlockT;
+use std::{future::Future, pin::Pin};
+
+/// A subcommand run on components of the node, see [`Runner::run_cmd`](crate::Runner::run_cmd).
+///
+/// This lets node binaries add their own subcommands, running like the built-in ones, without the
+/// runner knowing about them. `Components` is whatever the subcommand needs from the node, e.g.
+/// the client, built for the configuration of the subcommand.
+pub trait CliSubcommand<Block: BlockT, Components> {
+	/// Run the subcommand on the `components` built for `config`.
+	fn run<'a>(
+		&'a self,
+		components: Components,
+		config: Configuration,
+	) -> Pin<Box<dyn Future<Output = error::Result<()>> + 'a>>;
+}
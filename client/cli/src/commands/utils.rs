@@ -17,7 +17,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 //! subcommand utilities
-use std::{io::Read, path::PathBuf, convert::TryFrom};
+use std::{fs, io::{self, Read, Write}, path::{Path, PathBuf}, convert::TryFrom};
 use sp_core::{
 	Pair, hexdisplay::HexDisplay,
 	crypto::{Ss58Codec, Ss58AddressFormat},
@@ -32,6 +32,21 @@ pub type PublicFor<P> = <P as sp_core::Pair>::Public;
 /// Seed type for Runtime
 pub type SeedFor<P> = <P as sp_core::Pair>::Seed;
 
+/// Capacity of the buffer of [`output_writer`], so that multi-gigabyte exports piped to another
+/// process aren't written in small chunks.
+const OUTPUT_BUFFER_CAPACITY: usize = 1 << 20;
+
+/// Open the output of an export, the file at `path` or stdout when it is `-` or unspecified.
+///
+/// The logs are written to stderr, so they never interleave with an export to stdout.
+pub fn output_writer(path: Option<&Path>) -> io::Result<Box<dyn Write>> {
+	let output: Box<dyn Write> = match path {
+		Some(path) if path != Path::new("-") => Box::new(fs::File::create(path)?),
+		_ => Box::new(io::stdout()),
+	};
+	Ok(Box::new(io::BufWriter::with_capacity(OUTPUT_BUFFER_CAPACITY, output)))
+}
+
 /// helper method to fetch uri from `Option<String>` either as a file or read from stdin
 pub fn read_uri(uri: Option<&String>) -> error::Result<String> {
 	let uri = if let Some(uri) = uri {
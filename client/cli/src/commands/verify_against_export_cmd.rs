@@ -0,0 +1,103 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{CliConfiguration, error, params::{DatabaseParams, PruningParams, SharedParams}};
+use log::info;
+use sc_client_api::BlockBackend;
+use sc_service::chain_ops::{verify_against_export, ExportMismatch};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::{Block as BlockT, MaybeSerializeDeserialize};
+use std::{fs, path::PathBuf, sync::Arc};
+use structopt::StructOpt;
+
+/// The `verify-against-export` command used to check that the blocks of an export are in the
+/// database.
+#[derive(Debug, StructOpt)]
+pub struct VerifyAgainstExportCmd {
+	/// Export made by `export-blocks` to compare with the database.
+	#[structopt(long, parse(from_os_str), value_name = "PATH")]
+	pub file: PathBuf,
+
+	/// Read the export in binary format rather than JSON.
+	#[structopt(long)]
+	pub binary: bool,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl VerifyAgainstExportCmd {
+	/// Run the `verify-against-export` command
+	///
+	/// Fails on the first block of the export that doesn't match the database.
+	pub async fn run<B, C>(&self, client: Arc<C>) -> error::Result<()>
+	where
+		B: BlockT + MaybeSerializeDeserialize,
+		C: BlockBackend<B> + HeaderBackend<B>,
+	{
+		info!("Comparing {} with the database", self.file.display());
+
+		let file = fs::File::open(&self.file)?;
+		let verification = verify_against_export(&*client, file, self.binary)?;
+
+		let mismatch = match verification.mismatch {
+			Some(mismatch) => mismatch,
+			None => {
+				info!("All {} block(s) of the export match the database", verification.matching);
+				return Ok(());
+			}
+		};
+
+		info!("{} block(s) matched before the first mismatch", verification.matching);
+		let reason = match mismatch {
+			ExportMismatch::Missing { number, hash } =>
+				format!("Block #{} ({}) is not in the database", number, hash),
+			ExportMismatch::NotCanonical { number, hash, canonical: Some(canonical) } =>
+				format!("Block #{} ({}) is not canonical, {} is", number, hash, canonical),
+			ExportMismatch::NotCanonical { number, hash, canonical: None } =>
+				format!("Block #{} ({}) is not canonical, no block is at this height", number, hash),
+			ExportMismatch::Different { number, hash, reason } =>
+				format!("Block #{} ({}) has different {} in the database", number, hash, reason),
+		};
+
+		Err(error::Error::Input(reason))
+	}
+}
+
+impl CliConfiguration for VerifyAgainstExportCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
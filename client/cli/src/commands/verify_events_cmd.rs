@@ -0,0 +1,144 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::{
+	CliConfiguration, error, params::{DatabaseParams, GenericNumber, PruningParams, SharedParams},
+};
+use log::{info, warn};
+use sc_client_api::{BlockBackend, StorageProvider, backend::Backend};
+use sc_service::chain_ops::{verify_events, EventsDivergence};
+use sp_api::{ApiExt, Core, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::{hashing::twox_128, storage::StorageKey};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use std::{fmt::Debug, str::FromStr, sync::Arc};
+use structopt::StructOpt;
+
+/// The `verify-events` command used to re-execute blocks and compare the events they produce
+/// against the stored ones.
+#[derive(Debug, StructOpt)]
+pub struct VerifyEventsCmd {
+	/// Number of the first block to re-execute.
+	#[structopt(long, value_name = "BLOCK")]
+	pub from: GenericNumber,
+
+	/// Number of the last block to re-execute.
+	#[structopt(long, value_name = "BLOCK")]
+	pub to: GenericNumber,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl VerifyEventsCmd {
+	/// Run the `verify-events` command
+	///
+	/// The events are read from `System::Events`. `describe` decodes encoded events into one
+	/// line per event, so that the diverging events can be told apart; when it returns `None`,
+	/// only the encoded events are printed.
+	pub async fn run<B, BA, C>(
+		&self,
+		client: Arc<C>,
+		backend: Arc<BA>,
+		describe: impl Fn(&[u8]) -> Option<Vec<String>>,
+	) -> error::Result<()>
+	where
+		B: BlockT,
+		BA: Backend<B>,
+		C: BlockBackend<B> + HeaderBackend<B> + StorageProvider<B, BA> + ProvideRuntimeApi<B>,
+		C::Api: Core<B, Error = sp_blockchain::Error> + ApiExt<B, StateBackend = BA::State>,
+		<<B::Header as HeaderT>::Number as FromStr>::Err: Debug,
+	{
+		let from = self.from.parse()?;
+		let to = self.to.parse()?;
+		let mut events_key = twox_128(b"System").to_vec();
+		events_key.extend_from_slice(&twox_128(b"Events"));
+
+		info!("Re-executing blocks #{} to #{}", from, to);
+		let divergences = verify_events(
+			&*client,
+			&*backend,
+			&StorageKey(events_key),
+			from,
+			to,
+			|divergence| {
+				report_divergence(&divergence, &describe);
+				Ok(())
+			},
+		)?;
+
+		if divergences > 0 {
+			return Err(error::Error::Input(
+				format!("Events of {} block(s) differ after re-execution", divergences),
+			));
+		}
+
+		info!("Events of blocks #{} to #{} match", from, to);
+		Ok(())
+	}
+}
+
+/// Print the events of a block that differ after re-executing it.
+fn report_divergence<B: BlockT>(
+	divergence: &EventsDivergence<B>,
+	describe: impl Fn(&[u8]) -> Option<Vec<String>>,
+) {
+	warn!("Events of block #{} ({}) differ", divergence.number, divergence.hash);
+
+	let events = |encoded: &Option<Vec<u8>>| match encoded {
+		Some(encoded) => describe(encoded)
+			.unwrap_or_else(|| vec![format!("0x{}", sp_core::hexdisplay::HexDisplay::from(encoded))]),
+		None => Vec::new(),
+	};
+	let stored = events(&divergence.stored);
+	let computed = events(&divergence.computed);
+
+	for index in 0..stored.len().max(computed.len()) {
+		match (stored.get(index), computed.get(index)) {
+			(Some(s), Some(c)) if s == c => {},
+			(s, c) => {
+				println!("#{}\tevent {}", divergence.number, index);
+				println!("\tstored:   {}", s.map_or("<none>", |s| s.as_str()));
+				println!("\tcomputed: {}", c.map_or("<none>", |c| c.as_str()));
+			},
+		}
+	}
+}
+
+impl CliConfiguration for VerifyEventsCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
@@ -0,0 +1,88 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::error;
+use crate::params::{DatabaseParams, PruningParams, SharedParams};
+use crate::CliConfiguration;
+use log::info;
+use sc_service::{config::DatabaseConfig, chain_ops::verify_genesis, ChainSpec};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::Block as BlockT;
+use std::sync::Arc;
+use structopt::StructOpt;
+
+/// The `verify-genesis` command used to check that the genesis of the chain spec matches the
+/// one in the database.
+#[derive(Debug, StructOpt)]
+pub struct VerifyGenesisCmd {
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[structopt(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl VerifyGenesisCmd {
+	/// Make sure the database exists before opening it.
+	///
+	/// Opening a database that doesn't exist initializes it with the genesis of the chain spec,
+	/// which would make the verification pointless.
+	pub fn ensure_database_exists(&self, database: &DatabaseConfig) -> error::Result<()> {
+		match database.path() {
+			Some(path) if !path.exists() => Err(error::Error::Input(
+				format!("Database {} does not exist", path.display()),
+			)),
+			_ => Ok(()),
+		}
+	}
+
+	/// Run the verify-genesis command
+	pub async fn run<B, C>(
+		&self,
+		client: Arc<C>,
+		chain_spec: Box<dyn ChainSpec>,
+	) -> error::Result<()>
+	where
+		B: BlockT,
+		C: HeaderBackend<B>,
+	{
+		let genesis = verify_genesis(client, chain_spec.as_storage_builder())?;
+		info!("✅ Genesis of {} matches the database: {}", chain_spec.name(), genesis);
+		Ok(())
+	}
+}
+
+impl CliConfiguration for VerifyGenesisCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
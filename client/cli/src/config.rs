@@ -19,17 +19,19 @@
 //! Configuration trait for a CLI based on substrate
 
 use crate::arg_enums::Database;
-use crate::error::Result;
+use crate::error::{Error, Result};
 use crate::{
 	init_logger, DatabaseParams, ImportParams, KeystoreParams, NetworkParams, NodeKeyParams,
 	OffchainWorkerParams, PruningParams, SharedParams, SubstrateCli,
 };
 use log::warn;
 use names::{Generator, Name};
+use sc_tracing::logging::LogDedup;
 use sc_client_api::execution_extensions::ExecutionStrategies;
 use sc_service::config::{
-	BasePath, Configuration, DatabaseConfig, ExtTransport, KeystoreConfig, NetworkConfiguration,
-	NodeKeyConfig, OffchainWorkerConfig, PrometheusConfig, PruningMode, Role, RpcMethods,
+	BasePath, Configuration, DatabaseConfig, ExtTransport, FutureBlockPolicy, InformantOutputFormat,
+	KeystoreConfig, NetworkConfiguration,
+	NodeKeyConfig, OffchainWorkerConfig, PrometheusConfig, PruningMode, Role, RpcEndpoint, RpcMethods,
 	TaskExecutor, TelemetryEndpoints, TransactionPoolOptions, WasmExecutionMethod,
 };
 use sc_service::{ChainSpec, TracingReceiver};
@@ -210,6 +212,13 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		Ok(self.database_params().and_then(|x| x.database()))
 	}
 
+	/// Get the base path of the read replica to use instead of the database of this node.
+	///
+	/// By default this is retrieved from `DatabaseParams` if it is available. Otherwise its `None`.
+	fn read_replica(&self) -> Result<Option<PathBuf>> {
+		Ok(self.database_params().and_then(|x| x.read_replica().cloned()))
+	}
+
 	/// Get the database configuration object for the parameters provided
 	fn database_config(
 		&self,
@@ -225,6 +234,7 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			Database::ParityDb => DatabaseConfig::ParityDb {
 				path: base_path.join("paritydb"),
 			},
+			Database::Memory => DatabaseConfig::in_memory(),
 		})
 	}
 
@@ -237,6 +247,24 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			.unwrap_or_default())
 	}
 
+	/// Get the maximum number of justifications waiting in the import queue (if any).
+	///
+	/// By default this is retrieved from `ImportParams` if it is available. Otherwise its `None`.
+	fn max_pending_justifications(&self) -> Result<Option<usize>> {
+		Ok(self.import_params()
+			.and_then(|x| x.max_pending_justifications()))
+	}
+
+	/// Get the policy of the import queue for the blocks from the future.
+	///
+	/// By default this is retrieved from `ImportParams` if it is available. Otherwise its the
+	/// default policy.
+	fn future_block_policy(&self) -> Result<FutureBlockPolicy> {
+		Ok(self.import_params()
+			.map(|x| x.future_block_policy())
+			.unwrap_or_default())
+	}
+
 	/// Get the state cache child ratio (if any).
 	///
 	/// By default this is `None`.
@@ -338,6 +366,28 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		Ok(None)
 	}
 
+	/// Returns `true` if the RPC servers should reject calls until the node is synced.
+	///
+	/// By default this is `false`.
+	fn rpc_wait_for_sync(&self) -> Result<bool> {
+		Ok(false)
+	}
+
+	/// Get the additional RPC servers to start, each with its own policy.
+	///
+	/// By default this is empty.
+	fn rpc_endpoints(&self) -> Result<Vec<RpcEndpoint>> {
+		Ok(Vec::new())
+	}
+
+	/// Get the maximum number of notifications buffered for a head subscription before it is
+	/// dropped (`None` if unlimited)
+	///
+	/// By default this is `None`.
+	fn rpc_max_buffered_notifications(&self) -> Result<Option<usize>> {
+		Ok(None)
+	}
+
 	/// Get the RPC cors (`None` if disabled)
 	///
 	/// By default this is `Some(Vec::new())`.
@@ -454,7 +504,8 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 	) -> Result<Configuration> {
 		let is_dev = self.is_dev()?;
 		let chain_id = self.chain_id(is_dev)?;
-		let chain_spec = cli.load_spec(chain_id.as_str())?;
+		let chain_spec = cli.load_spec(chain_id.as_str())
+			.map_err(|e| chain_spec_error::<C>(&chain_id, e))?;
 		let base_path = self
 			.base_path()?
 			.unwrap_or_else(|| BasePath::from_project("", "", &C::executable_name()));
@@ -521,7 +572,18 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			announce_block: self.announce_block()?,
 			role,
 			base_path: Some(base_path),
-			informant_output_format: Default::default(),
+			// The logs are written to stderr, see `init_logger`.
+			informant_output_format: InformantOutputFormat::for_terminal(
+				atty::is(atty::Stream::Stderr),
+			),
+			task_filter: Default::default(),
+			max_pending_justifications: self.max_pending_justifications()?,
+			future_block_policy: self.future_block_policy()?,
+			rpc_wait_for_sync: self.rpc_wait_for_sync()?,
+			rpc_endpoints: self.rpc_endpoints()?,
+			rpc_max_buffered_notifications: self.rpc_max_buffered_notifications()?,
+			min_peers_before_authoring: None,
+			max_catch_up_import_rate: None,
 		})
 	}
 
@@ -540,6 +602,42 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		Ok(self.shared_params().is_log_filter_reloading_disabled())
 	}
 
+	/// Get the settings for collapsing repeated log records, if enabled.
+	///
+	/// By default this is retrieved from `SharedParams`.
+	fn log_dedup(&self) -> Result<Option<LogDedup>> {
+		Ok(self.shared_params().log_dedup())
+	}
+
+	/// Get the number of worker threads of the tokio runtime, `None` for tokio's default.
+	///
+	/// By default this is retrieved from `SharedParams`.
+	fn tokio_worker_threads(&self) -> Result<Option<usize>> {
+		Ok(self.shared_params().tokio_worker_threads())
+	}
+
+	/// Get the maximum number of threads of the tokio runtime, `None` for tokio's default.
+	///
+	/// By default this is retrieved from `SharedParams`.
+	fn tokio_max_threads(&self) -> Result<Option<usize>> {
+		Ok(self.shared_params().tokio_max_threads())
+	}
+
+	/// Get the stack size of the threads of the tokio runtime, in bytes, `None` for tokio's
+	/// default.
+	///
+	/// By default this is retrieved from `SharedParams`.
+	fn tokio_thread_stack_size(&self) -> Result<Option<usize>> {
+		Ok(self.shared_params().tokio_thread_stack_size())
+	}
+
+	/// Get the prefix of the names of the threads of the tokio runtime.
+	///
+	/// By default this is [`DEFAULT_TOKIO_THREAD_NAME`](crate::DEFAULT_TOKIO_THREAD_NAME).
+	fn tokio_thread_name(&self) -> Result<String> {
+		Ok(crate::DEFAULT_TOKIO_THREAD_NAME.into())
+	}
+
 	/// Initialize substrate. This must be done only once per process.
 	///
 	/// This method:
@@ -552,6 +650,7 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 		let tracing_receiver = self.tracing_receiver()?;
 		let tracing_targets = self.tracing_targets()?;
 		let disable_log_reloading = self.is_log_filter_reloading_disabled()?;
+		let log_dedup = self.log_dedup()?;
 
 		sp_panic_handler::set(&C::support_url(), &C::impl_version());
 
@@ -560,6 +659,8 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 			tracing_receiver,
 			tracing_targets,
 			disable_log_reloading,
+			log_dedup,
+			C::log_redactor(),
 		)?;
 
 		if let Some(new_limit) = fdlimit::raise_fd_limit() {
@@ -576,6 +677,32 @@ pub trait CliConfiguration<DCV: DefaultConfigurationValues = ()>: Sized {
 	}
 }
 
+/// Describe why loading the chain spec `id` with [`SubstrateCli::load_spec`] failed.
+fn chain_spec_error<C: SubstrateCli>(id: &str, error: String) -> Error {
+	let builtin = C::builtin_chain_specs();
+	let reason = if builtin.contains(&id) {
+		format!("the built-in chain spec failed to load: {}", error)
+	} else {
+		let path = std::env::current_dir()
+			.map(|dir| dir.join(id))
+			.unwrap_or_else(|_| id.into());
+		let known = builtin.iter().filter(|id| !id.is_empty()).cloned().collect::<Vec<_>>();
+		let known = if known.is_empty() {
+			String::new()
+		} else {
+			format!(" (known ones: {})", known.join(", "))
+		};
+		format!(
+			"it isn't a built-in chain spec{}, and loading it as a file from {} failed: {}",
+			known,
+			path.display(),
+			error,
+		)
+	};
+
+	Error::ChainSpec { id: id.into(), reason }
+}
+
 /// Generate a valid random name for the node
 pub fn generate_node_name() -> String {
 	loop {
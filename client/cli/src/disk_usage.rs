@@ -0,0 +1,111 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Monitoring of the disk usage of the node, shutting it down before the disk is full.
+
+use log::{error, warn};
+use std::{collections::VecDeque, path::{Path, PathBuf}, time::{Duration, Instant}};
+
+/// Exit code of the process when the node shut down because its disk usage reached the
+/// high-water mark of its [`DiskUsageLimit`].
+///
+/// The database has been flushed, but the node shouldn't be started again before some disk space
+/// is freed.
+pub const DISK_USAGE_EXIT_CODE: i32 = 74;
+
+/// Number of samples logged along with the shutdown, to show how the usage grew.
+const TRAJECTORY_LEN: usize = 10;
+
+/// Disk usage after which the node is shut down, see
+/// [`Runner::set_disk_usage_limit`](crate::Runner::set_disk_usage_limit).
+///
+/// Usages are percentages of the size of the filesystem of the database.
+#[derive(Clone, Debug)]
+pub struct DiskUsageLimit {
+	/// Usage at which the node is shut down.
+	pub high_water: f64,
+	/// Usage above which every check is logged as a warning, until the usage goes back under it.
+	///
+	/// `None` only logs when the node is shut down.
+	pub low_water: Option<f64>,
+	/// How often the usage is checked.
+	pub interval: Duration,
+}
+
+/// Check the usage of the filesystem of `path` every `limit.interval`, resolving once it reaches
+/// the high-water mark.
+pub(crate) async fn monitor_disk_usage(
+	path: PathBuf,
+	limit: DiskUsageLimit,
+) -> sc_service::error::Result<()> {
+	let mut interval = tokio::time::interval(limit.interval);
+	let mut trajectory = VecDeque::with_capacity(TRAJECTORY_LEN);
+	let started = Instant::now();
+
+	loop {
+		interval.tick().await;
+
+		let usage = match disk_usage(&path) {
+			Ok(usage) => usage,
+			Err(e) => {
+				warn!("Failed to read the disk usage of {}: {}", path.display(), e);
+				continue;
+			},
+		};
+
+		if trajectory.len() == TRAJECTORY_LEN {
+			trajectory.pop_front();
+		}
+		trajectory.push_back((started.elapsed().as_secs(), usage));
+
+		if usage >= limit.high_water {
+			error!(
+				"💽 Disk usage of {} is {:.1}%, past the high-water mark of {:.1}%. \
+				Shutting down. Recent usage: {}",
+				path.display(),
+				usage,
+				limit.high_water,
+				trajectory.iter()
+					.map(|(secs, usage)| format!("{:.1}% at {}s", usage, secs))
+					.collect::<Vec<_>>()
+					.join(", "),
+			);
+			return Err(sc_service::Error::DiskUsageLimitReached);
+		}
+
+		match limit.low_water {
+			Some(low_water) if usage >= low_water => warn!(
+				"💽 Disk usage of {} is {:.1}%, the node shuts down at {:.1}%",
+				path.display(),
+				usage,
+				limit.high_water,
+			),
+			_ => {},
+		}
+	}
+}
+
+/// Percentage of the filesystem of `path` that is used.
+fn disk_usage(path: &Path) -> std::io::Result<f64> {
+	let total = fs2::total_space(path)?;
+	let available = fs2::available_space(path)?;
+	if total == 0 {
+		return Ok(0.0);
+	}
+	Ok(total.saturating_sub(available) as f64 * 100.0 / total as f64)
+}
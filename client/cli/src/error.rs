@@ -59,9 +59,31 @@ pub enum Error {
 		expected: usize,
 	},
 
+	#[error("Failed to load the chain spec `{id}`: {reason}")]
+	ChainSpec {
+		/// Identifier or path of the chain spec.
+		id: String,
+		/// Why it couldn't be loaded.
+		reason: String,
+	},
+
+	#[error("The node stayed {lag} block(s) behind the network for {seconds}s")]
+	SyncLag {
+		/// Number of blocks the node was behind at the last check.
+		lag: u64,
+		/// For how long the node has been behind, in seconds.
+		seconds: u64,
+	},
+
 	#[error("The base path is missing, please provide one")]
 	MissingBasePath,
 
+	#[error(
+		"The database at {} is locked by another process, e.g. a node running on the same base path",
+		.0.display(),
+	)]
+	DatabaseLocked(std::path::PathBuf),
+
 	#[error("Unknown key type, must be a known 4-character sequence")]
 	KeyTypeInvalid,
 
@@ -26,14 +26,19 @@
 pub mod arg_enums;
 mod commands;
 mod config;
+mod disk_usage;
 mod error;
+mod log_retention;
 mod params;
 mod runner;
+mod sd_notify;
 
 pub use arg_enums::*;
 pub use commands::*;
 pub use config::*;
+pub use disk_usage::{DiskUsageLimit, DISK_USAGE_EXIT_CODE};
 pub use error::*;
+pub use log_retention::LogRetention;
 pub use params::*;
 pub use runner::*;
 pub use sc_cli_proc_macro::*;
@@ -236,8 +241,35 @@ pub trait SubstrateCli: Sized {
 		Runner::new(self, command)
 	}
 
-	/// Native runtime version.
-	fn native_runtime_version(chain_spec: &Box<dyn ChainSpec>) -> &'static RuntimeVersion;
+	/// Create a runner for the command provided in argument that spawns its tasks on the tokio
+	/// runtime of `runtime_handle` instead of creating its own. See [`EmbeddedRunner`].
+	fn create_embedded_runner<T: CliConfiguration>(
+		&self,
+		command: &T,
+		runtime_handle: tokio::runtime::Handle,
+	) -> error::Result<EmbeddedRunner<Self>> {
+		command.init::<Self>()?;
+		EmbeddedRunner::new(self, command, runtime_handle)
+	}
+
+	/// Identifiers of the chain specs built into [`load_spec`](SubstrateCli::load_spec).
+	///
+	/// They are only used to explain why loading a chain spec failed. Empty by default.
+	fn builtin_chain_specs() -> Vec<&'static str> {
+		Vec::new()
+	}
+
+	/// Function rewriting every log line before it is written, e.g. to redact IP addresses or
+	/// account ids. `None` by default.
+	///
+	/// It is installed for the whole process along with the logger, see
+	/// [`sc_tracing::logging::set_log_redactor`] for the limitations and the performance cost.
+	fn log_redactor() -> Option<sc_tracing::logging::LogRedactor> {
+		None
+	}
+
+	/// Native runtime version, `None` for nodes only running the wasm runtime.
+	fn native_runtime_version(chain_spec: &dyn ChainSpec) -> Option<&'static RuntimeVersion>;
 }
 
 /// Initialize the global logger
@@ -248,6 +280,8 @@ pub fn init_logger(
 	tracing_receiver: sc_tracing::TracingReceiver,
 	profiling_targets: Option<String>,
 	disable_log_reloading: bool,
+	log_dedup: Option<sc_tracing::logging::LogDedup>,
+	log_redactor: Option<sc_tracing::logging::LogRedactor>,
 ) -> std::result::Result<(), String> {
 	use sc_tracing::parse_default_directive;
 
@@ -315,6 +349,10 @@ pub fn init_logger(
 		env_filter = parse_user_directives(env_filter, &profiling_targets)?;
 	}
 
+	if let Some(log_redactor) = log_redactor {
+		logging::set_log_redactor(log_redactor);
+	}
+
 	let enable_color = atty::is(atty::Stream::Stderr);
 	let timer = ChronoLocal::with_format(if simple {
 		"%Y-%m-%d %H:%M:%S".to_string()
@@ -331,6 +369,7 @@ pub fn init_logger(
 			display_target: !simple,
 			display_level: !simple,
 			display_thread_name: !simple,
+			dedup: log_dedup.map(logging::LogDeduplicator::new),
 		});
 	if disable_log_reloading {
 		let subscriber = subscriber_builder
@@ -383,7 +422,14 @@ mod tests {
 	#[test]
 	fn test_logger_filters() {
 		let test_pattern = "afg=debug,sync=trace,client=warn,telemetry,something-with-dash=error";
-		init_logger(&test_pattern, Default::default(), Default::default(), false).unwrap();
+		init_logger(
+			&test_pattern,
+			Default::default(),
+			Default::default(),
+			false,
+			None,
+			None,
+		).unwrap();
 
 		tracing::dispatcher::get_default(|dispatcher| {
 			let test_filter = |target, level| {
@@ -442,7 +488,14 @@ mod tests {
 	fn log_something_with_dash_target_name() {
 		if env::var("ENABLE_LOGGING").is_ok() {
 			let test_pattern = "test-target=info";
-			init_logger(&test_pattern, Default::default(), Default::default(), false).unwrap();
+			init_logger(
+				&test_pattern,
+				Default::default(),
+				Default::default(),
+				false,
+				None,
+				None,
+			).unwrap();
 
 			log::info!(target: "test-target", "{}", EXPECTED_LOG_MESSAGE);
 		}
@@ -478,7 +531,14 @@ mod tests {
 	fn prefix_in_log_lines_entrypoint() {
 		if env::var("ENABLE_LOGGING").is_ok() {
 			let test_pattern = "test-target=info";
-			init_logger(&test_pattern, Default::default(), Default::default(), false).unwrap();
+			init_logger(
+				&test_pattern,
+				Default::default(),
+				Default::default(),
+				false,
+				None,
+				None,
+			).unwrap();
 			prefix_in_log_lines_process();
 		}
 	}
@@ -494,7 +554,7 @@ mod tests {
 	#[test]
 	fn do_not_write_with_colors_on_tty_entrypoint() {
 		if env::var("ENABLE_LOGGING").is_ok() {
-			init_logger("", Default::default(), Default::default(), false).unwrap();
+			init_logger("", Default::default(), Default::default(), false, None, None).unwrap();
 			log::info!("{}", ansi_term::Colour::Yellow.paint(EXPECTED_LOG_MESSAGE));
 		}
 	}
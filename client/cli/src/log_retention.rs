@@ -0,0 +1,111 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Pruning of the oldest log files of the node, keeping its logs directory bounded.
+
+use log::{info, warn};
+use std::{fs, io, path::PathBuf, time::{Duration, SystemTime}};
+
+/// Which log files are kept, see [`Runner::set_log_retention`](crate::Runner::set_log_retention).
+///
+/// The files of `dir` are pruned from the oldest, by modification time, until both limits are
+/// met. The newest file, which is usually the one being written, is always kept.
+#[derive(Clone, Debug)]
+pub struct LogRetention {
+	/// Directory of the log files. Its subdirectories are left alone.
+	pub dir: PathBuf,
+	/// Total size, in bytes, of the kept files.
+	///
+	/// `None` doesn't limit the size.
+	pub max_total_size: Option<u64>,
+	/// Age of the oldest kept file.
+	///
+	/// `None` doesn't limit the age.
+	pub max_age: Option<Duration>,
+	/// How often the files are pruned, after they were once when the node starts.
+	pub interval: Duration,
+}
+
+/// Prune the log files of `retention` every `retention.interval`, starting one interval from
+/// now.
+pub(crate) async fn prune_logs_periodically(retention: LogRetention) {
+	let start = tokio::time::Instant::now() + retention.interval;
+	let mut interval = tokio::time::interval_at(start, retention.interval);
+
+	loop {
+		interval.tick().await;
+		log_pruned(&retention, prune_logs(&retention, SystemTime::now()));
+	}
+}
+
+/// Prune the log files of `retention` once.
+pub(crate) fn prune_logs_now(retention: &LogRetention) {
+	log_pruned(retention, prune_logs(retention, SystemTime::now()));
+}
+
+fn log_pruned(retention: &LogRetention, pruned: io::Result<Vec<PathBuf>>) {
+	match pruned {
+		Ok(pruned) if pruned.is_empty() => {},
+		Ok(pruned) => info!(
+			"🗑  Pruned {} log file(s) of {}: {}",
+			pruned.len(),
+			retention.dir.display(),
+			pruned.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(", "),
+		),
+		Err(e) => warn!("Failed to prune the log files of {}: {}", retention.dir.display(), e),
+	}
+}
+
+/// Delete the files of `retention.dir` beyond its limits at `now`, returning the deleted files.
+///
+/// A file that can't be deleted is skipped with a warning, and still counts towards the size.
+pub(crate) fn prune_logs(retention: &LogRetention, now: SystemTime) -> io::Result<Vec<PathBuf>> {
+	let mut files = Vec::new();
+	for entry in fs::read_dir(&retention.dir)? {
+		let entry = entry?;
+		let metadata = entry.metadata()?;
+		if metadata.is_file() {
+			files.push((metadata.modified()?, metadata.len(), entry.path()));
+		}
+	}
+	// Newest first.
+	files.sort_by(|a, b| b.0.cmp(&a.0));
+
+	let mut pruned = Vec::new();
+	let mut total_size = 0u64;
+	for (index, (modified, size, path)) in files.into_iter().enumerate() {
+		total_size = total_size.saturating_add(size);
+		let too_big = retention.max_total_size.map_or(false, |max| total_size > max);
+		let too_old = retention.max_age.map_or(false, |max| {
+			now.duration_since(modified).map_or(false, |age| age > max)
+		});
+		if index == 0 || !(too_big || too_old) {
+			continue;
+		}
+
+		match fs::remove_file(&path) {
+			Ok(()) => {
+				total_size -= size;
+				pruned.push(path);
+			},
+			Err(e) => warn!("Failed to prune the log file {}: {}", path.display(), e),
+		}
+	}
+
+	Ok(pruned)
+}
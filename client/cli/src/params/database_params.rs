@@ -17,6 +17,7 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::arg_enums::Database;
+use std::path::PathBuf;
 use structopt::StructOpt;
 
 /// Parameters for block import.
@@ -34,6 +35,14 @@ pub struct DatabaseParams {
 	/// Limit the memory the database cache can use.
 	#[structopt(long = "db-cache", value_name = "MiB")]
 	pub database_cache_size: Option<usize>,
+
+	/// Read from the database of a replica node, whose base path is given, instead of the one
+	/// of this node.
+	///
+	/// The replica must use RocksDB and contain the same chain as this node. It is opened
+	/// read-only, so it can keep running while being read from.
+	#[structopt(long, value_name = "PATH", parse(from_os_str))]
+	pub read_replica: Option<PathBuf>,
 }
 
 impl DatabaseParams {
@@ -46,4 +55,9 @@ impl DatabaseParams {
 	pub fn database_cache_size(&self) -> Option<usize> {
 		self.database_cache_size
 	}
+
+	/// Base path of the replica node to read from, if any.
+	pub fn read_replica(&self) -> Option<&PathBuf> {
+		self.read_replica.as_ref()
+	}
 }
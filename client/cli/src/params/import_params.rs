@@ -17,13 +17,14 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::arg_enums::{
-	ExecutionStrategy, WasmExecutionMethod, DEFAULT_EXECUTION_BLOCK_CONSTRUCTION,
+	ExecutionStrategy, FutureBlockAction, WasmExecutionMethod, DEFAULT_EXECUTION_BLOCK_CONSTRUCTION,
 	DEFAULT_EXECUTION_IMPORT_BLOCK, DEFAULT_EXECUTION_IMPORT_BLOCK_VALIDATOR,
 	DEFAULT_EXECUTION_OFFCHAIN_WORKER, DEFAULT_EXECUTION_OTHER, DEFAULT_EXECUTION_SYNCING,
 };
 use crate::params::DatabaseParams;
 use crate::params::PruningParams;
 use sc_client_api::execution_extensions::ExecutionStrategies;
+use sc_service::config::FutureBlockPolicy;
 use structopt::StructOpt;
 use std::path::PathBuf;
 
@@ -73,9 +74,61 @@ pub struct ImportParams {
 		default_value = "67108864"
 	)]
 	pub state_cache_size: usize,
+
+	/// Maximum number of justifications waiting to be imported.
+	///
+	/// Justifications received beyond this limit wait outside of the import queue until there
+	/// is room for them. No limit by default.
+	#[structopt(long, value_name = "COUNT")]
+	pub max_pending_justifications: Option<usize>,
+
+	/// Number of slots a block may be ahead of the local clock and still be imported right away.
+	///
+	/// Raising it helps on chains with loose time synchronization, but lets authors whose clock
+	/// runs ahead build on slots that haven't started yet for everyone else.
+	#[structopt(long, value_name = "SLOTS", default_value = "1")]
+	pub future_block_tolerance: u64,
+
+	/// What to do with the blocks further ahead than `--future-block-tolerance`.
+	///
+	/// `Queue` holds such a block, and all the blocks behind it in the import queue, until it is
+	/// within the tolerance. `Reject` fails its import, it is then imported once received again.
+	#[structopt(
+		long,
+		value_name = "ACTION",
+		possible_values = &FutureBlockAction::variants(),
+		case_insensitive = true,
+		default_value = "Reject"
+	)]
+	pub future_block_action: FutureBlockAction,
+
+	/// Maximum number of slots to hold a block from the future with `--future-block-action Queue`.
+	///
+	/// Blocks that would need to be held longer are rejected.
+	#[structopt(long, value_name = "SLOTS", default_value = "1")]
+	pub future_block_max_wait: u64,
 }
 
 impl ImportParams {
+	/// Maximum number of justifications waiting to be imported (if any).
+	pub fn max_pending_justifications(&self) -> Option<usize> {
+		self.max_pending_justifications
+	}
+
+	/// Get the policy of the import queue for the blocks from the future.
+	pub fn future_block_policy(&self) -> FutureBlockPolicy {
+		let action = match self.future_block_action {
+			FutureBlockAction::Reject => sc_service::config::FutureBlockAction::Reject,
+			FutureBlockAction::Queue => sc_service::config::FutureBlockAction::Queue {
+				max_wait: self.future_block_max_wait,
+			},
+		};
+
+		FutureBlockPolicy {
+			tolerance: self.future_block_tolerance,
+			action,
+		}
+	}
 
 	/// Specify the state cache size.
 	pub fn state_cache_size(&self) -> usize {
@@ -88,6 +88,14 @@ pub struct NetworkParams {
 	)]
 	pub max_parallel_downloads: u32,
 
+	/// Maximum number of block requests in flight at once, across all peers.
+	///
+	/// Unlimited by default. Lower it to bound the bandwidth and memory used by syncing on
+	/// constrained links, at the cost of a slower sync and of a higher latency to fetch newly
+	/// announced blocks.
+	#[structopt(long = "max-block-requests", value_name = "COUNT")]
+	pub max_block_requests: Option<u32>,
+
 	#[allow(missing_docs)]
 	#[structopt(flatten)]
 	pub node_key_params: NodeKeyParams,
@@ -171,6 +179,7 @@ impl NetworkParams {
 				wasm_external_transport: None,
 			},
 			max_parallel_downloads: self.max_parallel_downloads,
+			max_block_requests: self.max_block_requests,
 			allow_non_globals_in_dht,
 			kademlia_disjoint_query_paths: self.kademlia_disjoint_query_paths,
 		}
@@ -17,12 +17,13 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use sc_service::config::BasePath;
-use std::path::PathBuf;
+use sc_tracing::logging::LogDedup;
+use std::{path::PathBuf, time::Duration};
 use structopt::StructOpt;
 use crate::arg_enums::TracingReceiver;
 
 /// Shared parameters used by all `CoreParams`.
-#[derive(Debug, StructOpt)]
+#[derive(Debug, Clone, StructOpt)]
 pub struct SharedParams {
 	/// Specify the chain specification.
 	///
@@ -54,6 +55,19 @@ pub struct SharedParams {
 	#[structopt(long = "disable-log-reloading")]
 	pub disable_log_reloading: bool,
 
+	/// Collapse the identical log records seen within this number of seconds.
+	///
+	/// Once `--log-dedup-threshold` identical records have been written within the window, the
+	/// next ones are dropped and a single line reports how many were dropped when the window
+	/// ends. Disabled by default.
+	#[structopt(long = "log-dedup-window", value_name = "SECONDS")]
+	pub log_dedup_window: Option<u64>,
+
+	/// Number of identical log records written within a `--log-dedup-window` before the next
+	/// ones are dropped.
+	#[structopt(long = "log-dedup-threshold", value_name = "COUNT", default_value = "1")]
+	pub log_dedup_threshold: u32,
+
 	/// Sets a custom profiling filter. Syntax is the same as for logging: <target>=<level>
 	#[structopt(long = "tracing-targets", value_name = "TARGETS")]
 	pub tracing_targets: Option<String>,
@@ -67,6 +81,25 @@ pub struct SharedParams {
 		default_value = "Log"
 	)]
 	pub tracing_receiver: TracingReceiver,
+
+	/// Number of worker threads of the tokio runtime.
+	///
+	/// Defaults to the number of cores of the machine.
+	#[structopt(long = "tokio-worker-threads", value_name = "COUNT")]
+	pub tokio_worker_threads: Option<usize>,
+
+	/// Maximum number of threads of the tokio runtime, worker and blocking ones included.
+	///
+	/// Defaults to tokio's own limit.
+	#[structopt(long = "tokio-max-threads", value_name = "COUNT")]
+	pub tokio_max_threads: Option<usize>,
+
+	/// Stack size of the threads of the tokio runtime, worker and blocking ones included, in
+	/// bytes.
+	///
+	/// Defaults to tokio's own stack size.
+	#[structopt(long = "tokio-thread-stack-size", value_name = "BYTES")]
+	pub tokio_thread_stack_size: Option<usize>,
 }
 
 impl SharedParams {
@@ -104,6 +137,14 @@ impl SharedParams {
 		self.disable_log_reloading
 	}
 
+	/// Settings for collapsing repeated log records, if enabled.
+	pub fn log_dedup(&self) -> Option<LogDedup> {
+		self.log_dedup_window.map(|window| LogDedup {
+			window: Duration::from_secs(window),
+			threshold: self.log_dedup_threshold,
+		})
+	}
+
 	/// Receiver to process tracing messages.
 	pub fn tracing_receiver(&self) -> sc_service::TracingReceiver {
 		self.tracing_receiver.clone().into()
@@ -113,4 +154,19 @@ impl SharedParams {
 	pub fn tracing_targets(&self) -> Option<String> {
 		self.tracing_targets.clone()
 	}
+
+	/// Number of worker threads of the tokio runtime, if capped.
+	pub fn tokio_worker_threads(&self) -> Option<usize> {
+		self.tokio_worker_threads
+	}
+
+	/// Maximum number of threads of the tokio runtime, if capped.
+	pub fn tokio_max_threads(&self) -> Option<usize> {
+		self.tokio_max_threads
+	}
+
+	/// Stack size of the threads of the tokio runtime, if set.
+	pub fn tokio_thread_stack_size(&self) -> Option<usize> {
+		self.tokio_thread_stack_size
+	}
 }
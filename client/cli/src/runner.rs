@@ -16,69 +16,692 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::CliConfiguration;
+use crate::{CliConfiguration, CliSubcommand};
+use crate::disk_usage::{DiskUsageLimit, DISK_USAGE_EXIT_CODE, monitor_disk_usage};
+use crate::log_retention::{LogRetention, prune_logs_now, prune_logs_periodically};
+use crate::sd_notify;
 use crate::Result;
 use crate::SubstrateCli;
 use chrono::prelude::*;
 use futures::pin_mut;
 use futures::select;
-use futures::{future, future::FutureExt, Future};
-use log::info;
-use sc_service::{Configuration, TaskType, TaskManager};
-use sp_utils::metrics::{TOKIO_THREADS_ALIVE, TOKIO_THREADS_TOTAL};
+use futures::{future, future::FutureExt, stream::StreamExt, Future};
+use log::{debug, error, info, warn};
+use prometheus_endpoint::{Gauge, Registry, U64};
+use sc_service::{
+	ChainSpec, Configuration, InformantBlock, MinPeersBeforeAuthoring, SpawnEssentialTaskHandle,
+	SpawnTaskHandle, RunningTasks, TaskExecutor, TaskFilter, TaskType, TaskManager,
+};
+use sp_utils::metrics::{
+	PROCESS_START_TIME_SECONDS, TOKIO_BLOCKING_TASKS_QUEUED, TOKIO_TASKS_ACTIVE, TOKIO_THREADS_ALIVE,
+	TOKIO_THREADS_TOTAL,
+};
+use sp_utils::mpsc::{tracing_unbounded, TracingUnboundedReceiver, TracingUnboundedSender};
+use sc_service::chain_ops::{read_genesis_cache, read_replica_database};
+use sp_runtime::traits::Block as BlockT;
 use std::marker::PhantomData;
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
+/// Exit code of the process when the node shut down because a restart was requested through the
+/// `system_prepareRestart` RPC.
+///
+/// The node has then stopped all of its work and flushed its database, so that a supervisor
+/// seeing this code can start it again right away.
+pub const RESTART_EXIT_CODE: i32 = 75;
+
+/// The exit code of a process running a node, see [`Runner::run_node_until_exit_code`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExitCode(pub i32);
+
+impl ExitCode {
+	/// The node stopped cleanly.
+	pub const SUCCESS: ExitCode = ExitCode(0);
+	/// The node failed.
+	pub const FAILURE: ExitCode = ExitCode(1);
+
+	/// Exit the process with this code.
+	pub fn exit(self) -> ! {
+		std::process::exit(self.0)
+	}
+}
+
+/// The exit code of the process when the node stopped because of `error`.
+///
+/// A requested restart and a full disk get [`RESTART_EXIT_CODE`] and [`DISK_USAGE_EXIT_CODE`],
+/// which the supervisor of the node expects, any other error [`ExitCode::FAILURE`].
+pub fn default_exit_code(error: &sc_service::Error) -> ExitCode {
+	match error {
+		sc_service::Error::RestartRequested => ExitCode(RESTART_EXIT_CODE),
+		sc_service::Error::DiskUsageLimitReached => ExitCode(DISK_USAGE_EXIT_CODE),
+		_ => ExitCode::FAILURE,
+	}
+}
+
+/// Exit code of the process when it is interrupted a second time while shutting down, following
+/// the shell convention for a process killed by `SIGINT`.
+pub const FORCED_EXIT_CODE: i32 = 130;
+
+/// A callback invoked when the process receives `SIGHUP`, see [`Runner::set_on_sighup`].
+pub type SighupCallback = Box<dyn Fn() + Send + Sync>;
+
+/// A callback invoked when the process receives `SIGUSR1`, see [`Runner::set_on_sigusr1`].
+pub type DiagnosticsCallback = Box<dyn Fn() + Send + Sync>;
+
+/// The default [`DiagnosticsCallback`], logging the tasks of the node that are running.
+fn log_running_tasks(running_tasks: RunningTasks) -> DiagnosticsCallback {
+	Box::new(move || info!("Running tasks: {}", format_tasks(&running_tasks.get())))
+}
+
+/// Format the tasks of [`RunningTasks::get`] for the logs.
+fn format_tasks(tasks: &[(&'static str, usize)]) -> String {
+	tasks.iter()
+		.map(|(name, count)| format!("{} ({})", name, count))
+		.collect::<Vec<_>>()
+		.join(", ")
+}
+
+/// A hook invoked once when the node is stopped by a signal, see [`Runner::set_on_shutdown`].
+pub type ShutdownHook = Box<dyn FnOnce() + Send>;
+
+/// Lets a command run by [`Runner::async_run_cancellable`] notice that it is asked to stop.
+///
+/// The command is cancelled when the signal stopping it is received, and then has a grace period
+/// to wind down, see [`Runner::set_cancellation_grace_period`].
+#[derive(Clone)]
+pub struct ShutdownToken(tokio::sync::watch::Receiver<bool>);
+
+impl ShutdownToken {
+	/// Whether the command was cancelled.
+	pub fn is_cancelled(&self) -> bool {
+		*self.0.borrow()
+	}
+
+	/// Wait for the command to be cancelled.
+	pub async fn cancelled(&self) {
+		let mut cancelled = self.0.clone();
+		while let Some(value) = cancelled.recv().await {
+			if value {
+				return;
+			}
+		}
+		// The command can't be cancelled anymore.
+		future::pending().await
+	}
+}
+
+/// Default of [`Runner::set_cancellation_grace_period`].
+pub const DEFAULT_CANCELLATION_GRACE_PERIOD: Duration = Duration::from_secs(30);
+
+/// The cancellation of the command driven by [`run_until_exit`], see [`ShutdownToken`].
+struct Cancellation {
+	trigger: tokio::sync::watch::Sender<bool>,
+	grace_period: Duration,
+}
+
+impl Cancellation {
+	/// A cancellation giving `grace_period` to the command, to which the token is passed.
+	fn new(grace_period: Duration) -> (Self, ShutdownToken) {
+		let (trigger, token) = tokio::sync::watch::channel(false);
+		(Cancellation { trigger, grace_period }, ShutdownToken(token))
+	}
+}
+
+/// How the command or node driven by [`run_until_exit`] is stopped and torn down.
+#[derive(Default)]
+struct ShutdownSettings {
+	/// Called once a signal stops the command, see [`Runner::set_on_shutdown`].
+	on_shutdown: Option<ShutdownHook>,
+	/// The signals stopping the command, see [`Runner::set_signal_set`].
+	signal_set: SignalSet,
+	/// The tasks logged if they are still running once `shutdown_timeout` elapsed.
+	running_tasks: Option<RunningTasks>,
+	/// How long the tasks have to wind down, see [`shutdown_within`].
+	shutdown_timeout: Option<Duration>,
+	/// How long the blocking tasks have to complete, see [`shutdown_runtime`].
+	runtime_shutdown_timeout: Option<Duration>,
+	/// The cancellation of the command, see [`run_cancellable`].
+	cancellation: Option<Cancellation>,
+}
+
+/// The messages of the tasks that panicked, see [`task_executor`].
+type TaskPanics = TracingUnboundedReceiver<String>;
+
+/// Wait for the next task to panic and return its message, or forever without `task_panics`.
+async fn next_task_panic(task_panics: &mut Option<&mut TaskPanics>) -> String {
+	match task_panics.as_mut() {
+		Some(task_panics) => match task_panics.next().await {
+			Some(message) => message,
+			None => future::pending().await,
+		},
+		None => future::pending().await,
+	}
+}
+
+/// The error returned by [`main`] when a task panicked.
+fn task_panicked(message: String) -> Box<dyn std::error::Error> {
+	Box::new(sc_service::Error::Other(format!("A task panicked: {}", message)))
+}
+
+/// A signal terminating the node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Termination {
+	/// `SIGINT`, or Ctrl-C on the platforms without unix signals.
+	Interrupt,
+	/// `SIGTERM`, or the console being closed, the user logging off or the system shutting down
+	/// on Windows.
+	#[cfg_attr(not(any(unix, windows)), allow(dead_code))]
+	Terminate,
+}
+
+/// Which of the signals terminating the node are handled by a [`Runner`], see
+/// [`Runner::set_signal_set`].
+///
+/// A signal that isn't handled keeps the default disposition of the OS, e.g. it kills the process
+/// without a graceful shutdown, which is what some debuggers and supervisors expect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignalSet {
+	/// Handle `SIGINT`, or Ctrl-C on the platforms without unix signals.
+	pub handle_sigint: bool,
+	/// Handle `SIGTERM`, or the console being closed, the user logging off or the system shutting
+	/// down on Windows.
+	pub handle_sigterm: bool,
+}
+
+impl Default for SignalSet {
+	fn default() -> Self {
+		SignalSet {
+			handle_sigint: true,
+			handle_sigterm: true,
+		}
+	}
+}
+
+/// Wait for `next`, e.g. the next signal of a stream, or forever without it.
+async fn recv_or_pending<T>(next: Option<impl Future<Output = T>>) -> T {
+	match next {
+		Some(next) => next.await,
+		None => future::pending().await,
+	}
+}
+
+/// The streams of the signals terminating the node, shared by [`main`] and
+/// [`Runner::shutdown_signal`].
+///
+/// They must be created from within a tokio runtime, which delivers the signals. Only the
+/// signals of the given [`SignalSet`] are listened to.
+#[cfg(target_family = "unix")]
+struct TerminationSignals {
+	interrupt: Option<tokio::signal::unix::Signal>,
+	terminate: Option<tokio::signal::unix::Signal>,
+}
+
+#[cfg(target_family = "unix")]
+impl TerminationSignals {
+	fn new(set: SignalSet) -> std::io::Result<Self> {
+		use tokio::signal::unix::{signal, SignalKind};
+
+		Ok(TerminationSignals {
+			interrupt: if set.handle_sigint { Some(signal(SignalKind::interrupt())?) } else { None },
+			terminate: if set.handle_sigterm { Some(signal(SignalKind::terminate())?) } else { None },
+		})
+	}
+
+	/// Wait for the next terminating signal.
+	async fn recv(&mut self) -> Termination {
+		let interrupt = recv_or_pending(self.interrupt.as_mut().map(|s| s.recv())).fuse();
+		let terminate = recv_or_pending(self.terminate.as_mut().map(|s| s.recv())).fuse();
+		pin_mut!(interrupt, terminate);
+
+		select! {
+			_ = interrupt => Termination::Interrupt,
+			_ = terminate => Termination::Terminate,
+		}
+	}
+}
+
+/// The console events that terminate the process on Windows, beyond Ctrl-C.
+///
+/// Windows kills the process a few seconds after delivering them, whatever the handler does, so
+/// the node only gets this long to shut down: with [`Runner::set_shutdown_timeout`] tuned
+/// accordingly, it abandons its tasks rather than being killed while flushing its database.
+#[cfg(windows)]
+mod console_events {
+	use lazy_static::lazy_static;
+	use sp_utils::mpsc::{tracing_unbounded, TracingUnboundedReceiver, TracingUnboundedSender};
+	use std::{sync::{Mutex, Once}, time::Duration};
+	use winapi::shared::minwindef::{BOOL, DWORD, FALSE, TRUE};
+	use winapi::um::{
+		consoleapi::SetConsoleCtrlHandler,
+		wincon::{CTRL_CLOSE_EVENT, CTRL_LOGOFF_EVENT, CTRL_SHUTDOWN_EVENT},
+	};
+
+	lazy_static! {
+		static ref LISTENERS: Mutex<Vec<TracingUnboundedSender<()>>> = Mutex::new(Vec::new());
+	}
+
+	static REGISTER: Once = Once::new();
+
+	/// Get a stream yielding whenever the console is closed, the user logs off or the system
+	/// shuts down.
+	pub(super) fn listen() -> std::io::Result<TracingUnboundedReceiver<()>> {
+		let mut registered = Ok(());
+		REGISTER.call_once(|| {
+			// Handlers are called from the last registered, so this one is called before the one
+			// of tokio, which only handles Ctrl-C and Ctrl-Break.
+			if unsafe { SetConsoleCtrlHandler(Some(on_console_event), TRUE) } == FALSE {
+				registered = Err(std::io::Error::last_os_error());
+			}
+		});
+		registered?;
+
+		let (tx, rx) = tracing_unbounded("mpsc_console_events");
+		LISTENERS.lock().unwrap_or_else(|e| e.into_inner()).push(tx);
+		Ok(rx)
+	}
+
+	unsafe extern "system" fn on_console_event(event: DWORD) -> BOOL {
+		match event {
+			CTRL_CLOSE_EVENT | CTRL_LOGOFF_EVENT | CTRL_SHUTDOWN_EVENT => {
+				let listened = {
+					let mut listeners = LISTENERS.lock().unwrap_or_else(|e| e.into_inner());
+					listeners.retain(|listener| listener.unbounded_send(()).is_ok());
+					!listeners.is_empty()
+				};
+				if !listened {
+					return FALSE;
+				}
+
+				// The process is terminated as soon as this returns, so keep it alive until the
+				// node exits or Windows kills it.
+				loop {
+					std::thread::sleep(Duration::from_secs(1));
+				}
+			},
+			_ => FALSE,
+		}
+	}
+}
+
+#[cfg(windows)]
+struct TerminationSignals {
+	interrupt: bool,
+	console_events: Option<TracingUnboundedReceiver<()>>,
+}
+
+#[cfg(windows)]
+impl TerminationSignals {
+	fn new(set: SignalSet) -> std::io::Result<Self> {
+		Ok(TerminationSignals {
+			interrupt: set.handle_sigint,
+			console_events: if set.handle_sigterm { Some(console_events::listen()?) } else { None },
+		})
+	}
+
+	/// Wait for the next terminating signal.
+	async fn recv(&mut self) -> Termination {
+		let interrupt = recv_or_pending(Some(tokio::signal::ctrl_c()).filter(|_| self.interrupt))
+			.fuse();
+		let terminate = recv_or_pending(self.console_events.as_mut().map(|e| e.next())).fuse();
+		pin_mut!(interrupt, terminate);
+
+		select! {
+			_ = interrupt => Termination::Interrupt,
+			_ = terminate => Termination::Terminate,
+		}
+	}
+}
+
+#[cfg(not(any(unix, windows)))]
+struct TerminationSignals {
+	interrupt: bool,
+}
+
+#[cfg(not(any(unix, windows)))]
+impl TerminationSignals {
+	fn new(set: SignalSet) -> std::io::Result<Self> {
+		Ok(TerminationSignals { interrupt: set.handle_sigint })
+	}
+
+	/// Wait for the next terminating signal.
+	async fn recv(&mut self) -> Termination {
+		let _ = recv_or_pending(Some(tokio::signal::ctrl_c()).filter(|_| self.interrupt)).await;
+		Termination::Interrupt
+	}
+}
+
+/// Why [`main`] returned successfully.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stopped {
+	/// A signal stopped the node or command.
+	Signal,
+	/// The node or command completed on its own.
+	Completed,
+}
+
+/// The result of the node once [`main`] returned.
+///
+/// A node never completes on its own, unlike a command: its tasks only end once terminated, so
+/// this means that an essential one has ended.
+fn node_result(
+	stopped: std::result::Result<Stopped, Box<dyn std::error::Error>>,
+) -> std::result::Result<(), Box<dyn std::error::Error>> {
+	match stopped? {
+		Stopped::Signal => Ok(()),
+		Stopped::Completed => Err(Box::new(sc_service::Error::EssentialTaskFailed)),
+	}
+}
+
+/// What stops the node or command driven by [`main`].
+enum Terminations {
+	/// The signals of the process.
+	Signals(TerminationSignals),
+	/// A future standing in for them, see [`Runner::run_node_until_signal`]. It stops the node
+	/// once, as `SIGTERM` would, and never again.
+	Trigger(Option<Pin<Box<dyn Future<Output = ()>>>>),
+}
+
+impl Terminations {
+	/// Listen to the signals of `signal_set`, which are delivered by `tokio_runtime`.
+	fn signals(
+		tokio_runtime: &tokio::runtime::Runtime,
+		signal_set: SignalSet,
+	) -> std::io::Result<Self> {
+		tokio_runtime.enter(|| TerminationSignals::new(signal_set)).map(Terminations::Signals)
+	}
+
+	/// Wait for the next termination.
+	async fn recv(&mut self) -> Termination {
+		match self {
+			Terminations::Signals(signals) => signals.recv().await,
+			Terminations::Trigger(trigger) => {
+				match trigger.as_mut() {
+					Some(next) => next.await,
+					None => future::pending().await,
+				}
+				*trigger = None;
+				Termination::Terminate
+			},
+		}
+	}
+}
+
+/// Drive `func` on `tokio_runtime` until it completes or one of the `terminations` stops it, see
+/// [`main`], then let a second interrupt force the process to exit during the shutdown.
+///
+/// The `on_shutdown` hook of `shutdown` is taken if it is called.
+fn block_on_main<F, E>(
+	tokio_runtime: &mut tokio::runtime::Runtime,
+	func: F,
+	mut terminations: Terminations,
+	on_sighup: Option<&SighupCallback>,
+	on_sigusr1: Option<&DiagnosticsCallback>,
+	task_panics: Option<&mut TaskPanics>,
+	shutdown: &mut ShutdownSettings,
+) -> std::result::Result<Stopped, Box<dyn std::error::Error>>
+where
+	F: Future<Output = std::result::Result<(), E>>,
+	E: 'static + std::error::Error,
+{
+	let on_shutdown = &mut shutdown.on_shutdown;
+	let res = tokio_runtime.block_on(
+		main(func, &mut terminations, on_sighup, on_sigusr1, task_panics, on_shutdown),
+	);
+	force_exit_on_interrupt(tokio_runtime.handle(), terminations);
+	res
+}
+
+/// Exit the process immediately on the next interrupt.
+///
+/// The graceful shutdown happens once [`main`] returns; a second interrupt meanwhile means the
+/// user doesn't want to wait for it. Only the signals of the process are listened to for this.
+#[cfg(target_family = "unix")]
+fn force_exit_on_interrupt(runtime_handle: &tokio::runtime::Handle, terminations: Terminations) {
+	let mut signals = match terminations {
+		Terminations::Signals(signals) => signals,
+		Terminations::Trigger(_) => return,
+	};
+	runtime_handle.spawn(async move {
+		while signals.recv().await != Termination::Interrupt {}
+		warn!("Got a second interrupt, exiting immediately");
+		std::process::exit(FORCED_EXIT_CODE);
+	});
+}
+
+#[cfg(not(unix))]
+fn force_exit_on_interrupt(_runtime_handle: &tokio::runtime::Handle, _terminations: Terminations) {}
+
+/// Drive `func` until it completes, a task panics or one of the `terminations` stops it.
+///
+/// `func` is fused here, and only here, so that any future can be run by a [`Runner`].
 #[cfg(target_family = "unix")]
-async fn main<F, E>(func: F) -> std::result::Result<(), Box<dyn std::error::Error>>
+async fn main<F, E>(
+	func: F,
+	terminations: &mut Terminations,
+	on_sighup: Option<&SighupCallback>,
+	on_sigusr1: Option<&DiagnosticsCallback>,
+	mut task_panics: Option<&mut TaskPanics>,
+	on_shutdown: &mut Option<ShutdownHook>,
+) -> std::result::Result<Stopped, Box<dyn std::error::Error>>
 where
-	F: Future<Output = std::result::Result<(), E>> + future::FusedFuture,
+	F: Future<Output = std::result::Result<(), E>>,
 	E: 'static + std::error::Error,
 {
 	use tokio::signal::unix::{signal, SignalKind};
 
-	let mut stream_int = signal(SignalKind::interrupt())?;
-	let mut stream_term = signal(SignalKind::terminate())?;
+	// `SIGHUP` keeps terminating the process unless there is a callback to handle it.
+	let mut stream_hup = match on_sighup {
+		Some(_) => Some(signal(SignalKind::hangup())?),
+		None => None,
+	};
+	// So does `SIGUSR1` without a diagnostics callback.
+	let mut stream_usr1 = match on_sigusr1 {
+		Some(_) => Some(signal(SignalKind::user_defined1())?),
+		None => None,
+	};
 
-	let t1 = stream_int.recv().fuse();
-	let t2 = stream_term.recv().fuse();
-	let t3 = func;
+	let t2 = func.fuse();
+	pin_mut!(t2);
 
-	pin_mut!(t1, t2, t3);
+	loop {
+		let t1 = terminations.recv().fuse();
+		let t3 = async {
+			match stream_hup.as_mut() {
+				Some(stream_hup) => stream_hup.recv().await,
+				None => future::pending().await,
+			}
+		}.fuse();
+		let t4 = next_task_panic(&mut task_panics).fuse();
+		let t5 = recv_or_pending(stream_usr1.as_mut().map(|s| s.recv())).fuse();
+		pin_mut!(t1, t3, t4, t5);
 
-	select! {
-		_ = t1 => {},
-		_ = t2 => {},
-		res = t3 => res?,
+		select! {
+			termination = t1 => {
+				if termination == Termination::Interrupt {
+					info!("Got interrupt, shutting down... (press Ctrl-C again to force)");
+				}
+				sd_notify::notify(sd_notify::STOPPING);
+				if let Some(on_shutdown) = on_shutdown.take() {
+					on_shutdown();
+				}
+				break Ok(Stopped::Signal);
+			},
+			res = t2 => break res
+				.map(|()| Stopped::Completed)
+				.map_err(|e| Box::new(e) as Box<dyn std::error::Error>),
+			_ = t3 => if let Some(on_sighup) = on_sighup {
+				on_sighup();
+			},
+			message = t4 => break Err(task_panicked(message)),
+			_ = t5 => if let Some(on_sigusr1) = on_sigusr1 {
+				on_sigusr1();
+			},
+		}
 	}
-
-	Ok(())
 }
 
 #[cfg(not(unix))]
-async fn main<F, E>(func: F) -> std::result::Result<(), Box<dyn std::error::Error>>
+async fn main<F, E>(
+	func: F,
+	terminations: &mut Terminations,
+	_on_sighup: Option<&SighupCallback>,
+	_on_sigusr1: Option<&DiagnosticsCallback>,
+	mut task_panics: Option<&mut TaskPanics>,
+	on_shutdown: &mut Option<ShutdownHook>,
+) -> std::result::Result<Stopped, Box<dyn std::error::Error>>
 where
-	F: Future<Output = std::result::Result<(), E>> + future::FusedFuture,
+	F: Future<Output = std::result::Result<(), E>>,
 	E: 'static + std::error::Error,
 {
-	use tokio::signal::ctrl_c;
+	let t1 = terminations.recv().fuse();
+	let t2 = func.fuse();
+	let t3 = next_task_panic(&mut task_panics).fuse();
 
-	let t1 = ctrl_c().fuse();
-	let t2 = func;
-
-	pin_mut!(t1, t2);
+	pin_mut!(t1, t2, t3);
 
 	select! {
-		_ = t1 => {},
-		res = t2 => res?,
+		_ = t1 => {
+			sd_notify::notify(sd_notify::STOPPING);
+			if let Some(on_shutdown) = on_shutdown.take() {
+				on_shutdown();
+			}
+			Ok(Stopped::Signal)
+		},
+		res = t2 => res.map(|()| Stopped::Completed).map_err(Into::into),
+		message = t3 => Err(task_panicked(message)),
 	}
+}
 
-	Ok(())
+/// The scheduler of the tokio runtime of a [`Runner`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuntimeFlavor {
+	/// Tasks run on a pool of worker threads.
+	MultiThread,
+	/// Tasks run on the thread driving the runtime; only blocking tasks get their own threads.
+	CurrentThread,
 }
 
+/// Default prefix of the names of the threads of the tokio runtime, see
+/// [`CliConfiguration::tokio_thread_name`].
+pub const DEFAULT_TOKIO_THREAD_NAME: &str = "substrate-worker";
+
 /// Build a tokio runtime with all features
 pub fn build_runtime() -> std::result::Result<tokio::runtime::Runtime, std::io::Error> {
-	tokio::runtime::Builder::new()
-		.threaded_scheduler()
+	build_runtime_with(None, None)
+}
+
+/// Build a tokio runtime with all features, with `core_threads` worker threads and at most
+/// `max_blocking` threads in total.
+///
+/// `max_blocking` is forwarded to tokio's `max_threads`, so it counts the worker threads along
+/// with the blocking ones. `None` keeps tokio's default for either value.
+pub fn build_runtime_with(
+	core_threads: Option<usize>,
+	max_blocking: Option<usize>,
+) -> std::result::Result<tokio::runtime::Runtime, std::io::Error> {
+	build_runtime_with_flavor(
+		RuntimeFlavor::MultiThread,
+		core_threads,
+		max_blocking,
+		DEFAULT_TOKIO_THREAD_NAME,
+		None,
+	)
+}
+
+/// Set [`PROCESS_START_TIME_SECONDS`], unless it already was, to the start of the process as told
+/// by the OS, or else to now, since the runtime or runner of the node is built right after the
+/// process started.
+fn set_process_start_time() {
+	if PROCESS_START_TIME_SECONDS.get() == 0 {
+		let now = std::time::SystemTime::now()
+			.duration_since(std::time::UNIX_EPOCH)
+			.unwrap_or_default();
+		PROCESS_START_TIME_SECONDS.set(process_start_time().unwrap_or_else(|| now.as_secs()));
+	}
+}
+
+/// The number of seconds between the unix epoch and the start of the process.
+#[cfg(target_os = "linux")]
+fn process_start_time() -> Option<u64> {
+	// The start time, in clock ticks since the boot, is the 22nd field, the 20th after the name of
+	// the executable, which is in parentheses and may hold spaces.
+	let stat = std::fs::read_to_string("/proc/self/stat").ok()?;
+	let fields = &stat[stat.rfind(')')? + 1..];
+	let start_ticks: u64 = fields.split_whitespace().nth(19)?.parse().ok()?;
+	let boot_time: u64 = std::fs::read_to_string("/proc/stat").ok()?
+		.lines()
+		.find_map(|line| line.strip_prefix("btime "))?
+		.trim()
+		.parse()
+		.ok()?;
+	let ticks_per_second = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+	if ticks_per_second <= 0 {
+		return None;
+	}
+	Some(boot_time + start_ticks / ticks_per_second as u64)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn process_start_time() -> Option<u64> {
+	None
+}
+
+/// Build a tokio runtime with all features and the given scheduler.
+///
+/// See [`build_runtime_with`] for the thread counts; `core_threads` is ignored by
+/// [`RuntimeFlavor::CurrentThread`], which has no worker threads.
+///
+/// The threads are named `<thread_name>-<n>`, numbered in the order they are started, so that
+/// they can be told apart by tools like `perf` or `htop`.
+///
+/// All the threads, the blocking ones included, get a stack of `stack_size` bytes, or tokio's
+/// default. How long idle blocking threads are kept alive isn't configurable with the version of
+/// tokio in use.
+pub fn build_runtime_with_flavor(
+	flavor: RuntimeFlavor,
+	core_threads: Option<usize>,
+	max_blocking: Option<usize>,
+	thread_name: &str,
+	stack_size: Option<usize>,
+) -> std::result::Result<tokio::runtime::Runtime, std::io::Error> {
+	set_process_start_time();
+
+	let invalid = |msg: &str| Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, msg));
+	match (core_threads, max_blocking) {
+		(Some(0), _) | (_, Some(0)) => return invalid("The tokio thread counts must be positive"),
+		(Some(core), Some(max)) if core > max =>
+			return invalid("The tokio worker threads cannot outnumber the maximum threads"),
+		_ => {},
+	}
+
+	let mut builder = tokio::runtime::Builder::new();
+	match flavor {
+		RuntimeFlavor::MultiThread => {
+			builder.threaded_scheduler();
+			if let Some(core_threads) = core_threads {
+				builder.core_threads(core_threads);
+			}
+		},
+		RuntimeFlavor::CurrentThread => {
+			builder.basic_scheduler();
+		},
+	}
+	if let Some(max_blocking) = max_blocking {
+		builder.max_threads(max_blocking);
+	}
+	if let Some(stack_size) = stack_size {
+		builder.thread_stack_size(stack_size);
+	}
+
+	let thread_name = thread_name.to_owned();
+	let thread_id = AtomicUsize::new(0);
+	builder
+		.thread_name_fn(move || {
+			format!("{}-{}", thread_name, thread_id.fetch_add(1, Ordering::Relaxed))
+		})
 		.on_thread_start(|| {
 			TOKIO_THREADS_ALIVE.inc();
 			TOKIO_THREADS_TOTAL.inc();
@@ -90,114 +713,1455 @@ pub fn build_runtime() -> std::result::Result<tokio::runtime::Runtime, std::io::
 		.build()
 }
 
-fn run_until_exit<FUT, ERR>(
+/// Build a task executor spawning the tasks of the node on the runtime of `runtime_handle`.
+///
+/// Blocking tasks get a thread of the blocking pool, which both flavors of runtime have, so that
+/// they never stall the thread driving a [`RuntimeFlavor::CurrentThread`] runtime.
+///
+/// With `task_panics`, the panics of the tasks are caught and their messages sent there, so that
+/// [`main`] stops the node instead of letting it run without the task. The tasks are then polled
+/// within an [`AbortGuard::never_abort`](sp_panic_handler::AbortGuard::never_abort), since the
+/// panic handler set by [`CliConfiguration::init`] exits the process otherwise.
+///
+/// With the `task-gauges` feature, the tasks are counted by the `tokio_tasks_active` gauge until
+/// they finish or are dropped, and the blocking ones by `tokio_blocking_tasks_queued` until they
+/// get a thread.
+///
+/// With the `tokio-console` feature and [`TASK_TRACES_ENV`] set to `1`, each task is polled
+/// within a `runtime.spawn` span of the `tokio::task` target, at the trace level, like the tasks
+/// of tokio 1.x are, so that task stalls can be told apart by the subscriber of the spans.
+fn task_executor(
+	runtime_handle: tokio::runtime::Handle,
+	task_panics: Option<TracingUnboundedSender<String>>,
+) -> TaskExecutor {
+	let task_traces = task_traces_from_env();
+	(move |mut fut: Pin<Box<dyn Future<Output = ()> + Send>>, task_type| {
+		let task_panics = task_panics.clone();
+		let active = GaugeGuard::inc(&TOKIO_TASKS_ACTIVE);
+		let span = if task_traces { Some(task_span(&task_type)) } else { None };
+		let unwind = task_panics.is_some();
+		let fut = future::poll_fn(move |cx| {
+			let _guard = if unwind {
+				Some(sp_panic_handler::AbortGuard::never_abort())
+			} else {
+				None
+			};
+			match &span {
+				Some(span) => span.in_scope(|| fut.as_mut().poll(cx)),
+				None => fut.as_mut().poll(cx),
+			}
+		});
+		let fut = AssertUnwindSafe(fut).catch_unwind().map(move |res| {
+			let _active = active;
+			if let (Err(panic), Some(task_panics)) = (res, task_panics) {
+				let _ = task_panics.unbounded_send(panic_message(&*panic));
+			}
+		});
+		match task_type {
+			TaskType::Async => runtime_handle.spawn(fut).map(drop),
+			TaskType::Blocking => {
+				let queued = GaugeGuard::inc(&TOKIO_BLOCKING_TASKS_QUEUED);
+				runtime_handle.spawn_blocking(move || {
+					drop(queued);
+					futures::executor::block_on(fut)
+				}).map(drop)
+			},
+		}
+	}).into()
+}
+
+/// The span a task of `task_type` is polled within, see [`task_executor`].
+fn task_span(task_type: &TaskType) -> tracing::Span {
+	match task_type {
+		TaskType::Async =>
+			tracing::trace_span!(target: "tokio::task", "runtime.spawn", kind = "task"),
+		TaskType::Blocking =>
+			tracing::trace_span!(target: "tokio::task", "runtime.spawn", kind = "blocking"),
+	}
+}
+
+/// Increments a gauge, and decrements it once dropped along with what it counts.
+struct GaugeGuard(&'static Gauge<U64>);
+
+impl GaugeGuard {
+	/// Increment `gauge`, unless the `task-gauges` feature is disabled.
+	fn inc(gauge: &'static Gauge<U64>) -> Option<Self> {
+		if !cfg!(feature = "task-gauges") {
+			return None;
+		}
+		gauge.inc();
+		Some(GaugeGuard(gauge))
+	}
+}
+
+impl Drop for GaugeGuard {
+	fn drop(&mut self) {
+		self.0.dec();
+	}
+}
+
+/// Turn a panic of `node` into an error of the node, so that it can be restarted.
+///
+/// `node` is polled within a never-abort [`AbortGuard`](sp_panic_handler::AbortGuard), since the
+/// panic handler set by [`CliConfiguration::init`] exits the process otherwise.
+fn catch_node_panic<F>(mut node: F) -> impl Future<Output = sc_service::error::Result<()>>
+where
+	F: Future<Output = sc_service::error::Result<()>> + Unpin,
+{
+	let node = future::poll_fn(move |cx| {
+		let _guard = sp_panic_handler::AbortGuard::never_abort();
+		Pin::new(&mut node).poll(cx)
+	});
+	AssertUnwindSafe(node).catch_unwind().map(|res| {
+		res.unwrap_or_else(|panic| Err(sc_service::Error::Other(
+			format!("The node panicked: {}", panic_message(&*panic)),
+		)))
+	})
+}
+
+/// The message of a panic, as given to `panic!`.
+fn panic_message(panic: &(dyn std::any::Any + Send)) -> String {
+	match panic.downcast_ref::<&str>() {
+		Some(message) => message.to_string(),
+		None => match panic.downcast_ref::<String>() {
+			Some(message) => message.clone(),
+			None => "Box<Any>".to_string(),
+		},
+	}
+}
+
+/// Shut the tokio runtime down, waiting at most `timeout` for the outstanding blocking tasks.
+///
+/// Without a timeout, this waits for all of them to complete, like dropping the runtime does.
+pub fn shutdown_runtime(tokio_runtime: tokio::runtime::Runtime, timeout: Option<Duration>) {
+	let timeout = match timeout {
+		Some(timeout) => timeout,
+		None => return drop(tokio_runtime),
+	};
+
+	let started = Instant::now();
+	tokio_runtime.shutdown_timeout(timeout);
+
+	if started.elapsed() >= timeout {
+		warn!(
+			"Blocking tasks were still running {:?} after the shutdown began, abandoning them",
+			timeout,
+		);
+	}
+}
+
+/// Log information about the node itself.
+///
+/// # Example:
+///
+/// ```text
+/// 2020-06-03 16:14:21 Substrate Node
+/// 2020-06-03 16:14:21 ✌️  version 2.0.0-rc3-f4940588c-x86_64-linux-gnu
+/// 2020-06-03 16:14:21 ❤️  by Parity Technologies <admin@parity.io>, 2017-2020
+/// 2020-06-03 16:14:21 📋 Chain specification: Flaming Fir
+/// 2020-06-03 16:14:21 🏷 Node name: jolly-rod-7462
+/// 2020-06-03 16:14:21 👤 Role: FULL
+/// 2020-06-03 16:14:21 💾 Database: RocksDb at /tmp/c/chains/flamingfir7/db
+/// 2020-06-03 16:14:21 ⛓  Native runtime: node-251 (substrate-node-1.tx1.au10)
+/// ```
+fn print_node_infos<C: SubstrateCli>(config: &Configuration) {
+	let emoji = |emoji| if config.informant_output_format.enable_emoji { emoji } else { "" };
+
+	info!("{}", C::impl_name());
+	info!("{}version {}", emoji("✌️  "), C::impl_version());
+	info!(
+		"{}by {}, {}-{}",
+		emoji("❤️  "),
+		C::author(),
+		C::copyright_start_year(),
+		Local::today().year(),
+	);
+	info!("{}Chain specification: {}", emoji("📋 "), config.chain_spec.name());
+	info!("{}Node name: {}", emoji("🏷 "), config.network.node_name);
+	info!("{}Role: {}", emoji("👤 "), config.display_role());
+	info!("{}Database: {} at {}",
+		emoji("💾 "),
+		config.database,
+		config.database.path().map_or_else(|| "<unknown>".to_owned(), |p| p.display().to_string())
+	);
+	info!("{}Native runtime: {}", emoji("⛓  "), native_runtime::<C>(&*config.chain_spec));
+}
+
+/// The native runtime version of [`print_node_infos`], if the node has one.
+fn native_runtime<C: SubstrateCli>(chain_spec: &dyn ChainSpec) -> String {
+	match C::native_runtime_version(chain_spec) {
+		Some(version) => version.to_string(),
+		None => "<none, wasm-only>".into(),
+	}
+}
+
+/// Log the information of [`print_node_infos`] as a single JSON line, for log pipelines.
+///
+/// # Example:
+///
+/// ```text
+/// 2020-06-03 16:14:21 {"implName":"Substrate Node","version":"2.0.0-rc3-f4940588c-x86_64-linux-gnu",...}
+/// ```
+fn print_node_infos_json<C: SubstrateCli>(config: &Configuration) {
+	let infos = serde_json::json!({
+		"implName": C::impl_name(),
+		"version": C::impl_version(),
+		"author": C::author(),
+		"chainSpec": config.chain_spec.name(),
+		"nodeName": config.network.node_name,
+		"role": config.display_role(),
+		"database": config.database.to_string(),
+		"databasePath": config.database.path().map(|p| p.display().to_string()),
+		"nativeRuntime": C::native_runtime_version(&*config.chain_spec).map(|v| v.to_string()),
+	});
+	info!("{}", infos);
+}
+
+/// Target of the line logged once the node is ready, see [`Runner::set_node_ready_line`].
+pub const NODE_READY_TARGET: &str = "node-ready";
+
+/// The line telling the tools waiting for the node that it is ready, but for the best block that
+/// [`log_node_ready`] adds once the node is initialized.
+///
+/// # Example:
+///
+/// ```text
+/// NODE_READY name="Alice" role="AUTHORITY" chain="Development" best="#0 (0x8c0f…c5f5)"
+/// ```
+fn node_ready_line(config: &Configuration) -> String {
+	format!(
+		"NODE_READY name={:?} role={:?} chain={:?}",
+		config.network.node_name,
+		config.display_role(),
+		config.chain_spec.name(),
+	)
+}
+
+/// Log the line of [`node_ready_line`], unless it already was, with the best block of the node of
+/// `task_manager` if it is known, see [`TaskManager::set_best_block`].
+fn log_node_ready(ready: &mut Option<String>, task_manager: &TaskManager) {
+	if let Some(line) = ready.take() {
+		match task_manager.best_block() {
+			Some(best) => info!(target: NODE_READY_TARGET, "{} best={:?}", line, best),
+			None => info!(target: NODE_READY_TARGET, "{}", line),
+		}
+	}
+}
+
+/// Environment variable setting the default shutdown timeout of a [`Runner`], in seconds.
+pub const SHUTDOWN_TIMEOUT_ENV: &str = "SUBSTRATE_SHUTDOWN_TIMEOUT_SECS";
+
+/// Environment variable turning on the task traces of the `tokio-console` feature when set to `1`,
+/// see [`task_executor`]. It is ignored without the feature.
+pub const TASK_TRACES_ENV: &str = "SUBSTRATE_TASK_TRACES";
+
+/// Whether the task traces are turned on by [`TASK_TRACES_ENV`].
+fn task_traces_from_env() -> bool {
+	cfg!(feature = "tokio-console") &&
+		std::env::var(TASK_TRACES_ENV).map_or(false, |value| value.trim() == "1")
+}
+
+/// Read the shutdown timeout from [`SHUTDOWN_TIMEOUT_ENV`], if set.
+///
+/// An invalid value is ignored with a warning, leaving the shutdown without timeout.
+fn shutdown_timeout_from_env() -> Option<Duration> {
+	let value = std::env::var(SHUTDOWN_TIMEOUT_ENV).ok()?;
+	match value.trim().parse::<u64>() {
+		Ok(secs) => Some(Duration::from_secs(secs)),
+		Err(e) => {
+			warn!(
+				"Invalid {} {:?}, shutting down without timeout: {}",
+				SHUTDOWN_TIMEOUT_ENV,
+				value,
+				e,
+			);
+			None
+		},
+	}
+}
+
+/// Wait for the `cleanup` of the tasks of the node, then shut the tokio runtime down.
+///
+/// If the tasks are still winding down `shutdown_timeout` after the cleanup began, they are
+/// abandoned along with the runtime, see [`wait_for_cleanup`]. Otherwise, the blocking tasks are
+/// given `runtime_shutdown_timeout` to complete, see [`shutdown_runtime`].
+///
+/// This is how every node and command of a [`Runner`] is torn down: the task manager, owned by
+/// `cleanup`, is dropped first, then the runtime. The other way around, the tasks would be dropped
+/// by the runtime shutting down after the task manager, and panic using the state it owned.
+///
+/// Returns whether the tasks were done in time.
+fn shutdown_within(
+	mut tokio_runtime: tokio::runtime::Runtime,
+	cleanup: impl Future<Output = ()>,
+	running_tasks: Option<RunningTasks>,
+	shutdown_timeout: Option<Duration>,
+	runtime_shutdown_timeout: Option<Duration>,
+) -> bool {
+	// `cleanup`, and the task manager with it, is dropped by the time this returns.
+	let cleaned_up = wait_for_cleanup(&mut tokio_runtime, cleanup, running_tasks, shutdown_timeout);
+	if cleaned_up {
+		shutdown_runtime(tokio_runtime, runtime_shutdown_timeout);
+	} else {
+		tokio_runtime.shutdown_timeout(Duration::from_secs(0));
+	}
+	cleaned_up
+}
+
+/// Wait for the `cleanup` of the tasks of the node, at most `shutdown_timeout` if any.
+///
+/// Returns whether the tasks are done. Otherwise a warning is logged, listing the `running_tasks`
+/// left if known. Either way, `cleanup` is dropped before this returns.
+fn wait_for_cleanup(
+	tokio_runtime: &mut tokio::runtime::Runtime,
+	cleanup: impl Future<Output = ()>,
+	running_tasks: Option<RunningTasks>,
+	shutdown_timeout: Option<Duration>,
+) -> bool {
+	let timeout = match shutdown_timeout {
+		Some(timeout) => timeout,
+		None => {
+			tokio_runtime.block_on(cleanup);
+			return true;
+		},
+	};
+
+	let cleaned_up = tokio_runtime.block_on(async {
+		let cleanup = cleanup.fuse();
+		let deadline = tokio::time::delay_for(timeout).fuse();
+		pin_mut!(cleanup, deadline);
+
+		select! {
+			_ = cleanup => true,
+			_ = deadline => false,
+		}
+	});
+
+	if !cleaned_up {
+		warn!("Tasks were still running {:?} after the shutdown began, abandoning them", timeout);
+		let running = running_tasks.map(|tasks| tasks.get()).unwrap_or_default();
+		if !running.is_empty() {
+			warn!("Abandoned tasks: {}", format_tasks(&running));
+		}
+	}
+	cleaned_up
+}
+
+/// Drive the `future` of a command until it completes or a signal of `shutdown` stops it, then
+/// clean up.
+///
+/// Without a cancellation, the future is dropped as soon as the signal is received. The output
+/// of the future is returned if it completed, see [`run_cancellable`].
+fn run_until_exit<FUT, T, ERR, S>(
 	mut tokio_runtime: tokio::runtime::Runtime,
 	future: FUT,
-	task_manager: TaskManager,
-) -> Result<()>
+	task_panics: Option<&mut TaskPanics>,
+	cleanup: impl FnOnce() -> S,
+	mut shutdown: ShutdownSettings,
+) -> Result<Option<T>>
 where
-	FUT: Future<Output = std::result::Result<(), ERR>> + future::Future,
+	FUT: Future<Output = std::result::Result<T, ERR>>,
 	ERR: 'static + std::error::Error,
+	S: Future<Output = ()>,
 {
-	let f = future.fuse();
-	pin_mut!(f);
-
-	tokio_runtime.block_on(main(f)).map_err(|e| e.to_string())?;
-	tokio_runtime.block_on(task_manager.clean_shutdown());
+	let terminations = Terminations::signals(&tokio_runtime, shutdown.signal_set)?;
+	let res = run_cancellable(&mut tokio_runtime, future, terminations, task_panics, &mut shutdown);
+	shutdown_within(
+		tokio_runtime,
+		cleanup(),
+		shutdown.running_tasks,
+		shutdown.shutdown_timeout,
+		shutdown.runtime_shutdown_timeout,
+	);
 
-	Ok(())
+	res.map_err(Into::into)
 }
 
-/// A Substrate CLI runtime that can be used to run a node or a command
-pub struct Runner<C: SubstrateCli> {
-	config: Configuration,
-	tokio_runtime: tokio::runtime::Runtime,
-	phantom: PhantomData<C>,
+/// Drive `future` until it completes or one of the `terminations` stops it.
+///
+/// With a cancellation in `shutdown`, the command is cancelled right when it is stopped, before
+/// the `on_shutdown` hook runs, and the future keeps being driven for the grace period so that it
+/// can return by itself. It is dropped if it doesn't. Both are taken from `shutdown`.
+///
+/// The output of the future is returned if it completed, `None` if it was stopped before.
+fn run_cancellable<FUT, T, ERR>(
+	tokio_runtime: &mut tokio::runtime::Runtime,
+	future: FUT,
+	terminations: Terminations,
+	task_panics: Option<&mut TaskPanics>,
+	shutdown: &mut ShutdownSettings,
+) -> std::result::Result<Option<T>, String>
+where
+	FUT: Future<Output = std::result::Result<T, ERR>>,
+	ERR: 'static + std::error::Error,
+{
+	let mut output = None;
+	let res = {
+		let output = &mut output;
+		let future = future.map(move |res| res.map(|value| *output = Some(value)));
+		drive_cancellable(tokio_runtime, future, terminations, task_panics, shutdown)
+	};
+	res.map(|()| output)
 }
 
-impl<C: SubstrateCli> Runner<C> {
-	/// Create a new runtime with the command provided in argument
-	pub fn new<T: CliConfiguration>(cli: &C, command: &T) -> Result<Runner<C>> {
-		let tokio_runtime = build_runtime()?;
-		let runtime_handle = tokio_runtime.handle().clone();
+/// [`run_cancellable`], for a future without output.
+fn drive_cancellable<FUT, ERR>(
+	tokio_runtime: &mut tokio::runtime::Runtime,
+	future: FUT,
+	terminations: Terminations,
+	task_panics: Option<&mut TaskPanics>,
+	shutdown: &mut ShutdownSettings,
+) -> std::result::Result<(), String>
+where
+	FUT: Future<Output = std::result::Result<(), ERR>>,
+	ERR: 'static + std::error::Error,
+{
+	let mut future = Box::pin(future);
+	let grace_period = match shutdown.cancellation.take() {
+		Some(Cancellation { trigger, grace_period }) => {
+			let on_shutdown = shutdown.on_shutdown.take();
+			shutdown.on_shutdown = Some(Box::new(move || {
+				let _ = trigger.broadcast(true);
+				if let Some(on_shutdown) = on_shutdown {
+					on_shutdown();
+				}
+			}));
+			Some(grace_period)
+		},
+		None => None,
+	};
 
-		let task_executor = move |fut, task_type| {
-			match task_type {
-				TaskType::Async => runtime_handle.spawn(fut).map(drop),
-				TaskType::Blocking =>
-					runtime_handle.spawn_blocking(move || futures::executor::block_on(fut))
-						.map(drop),
+	let res = block_on_main(
+		tokio_runtime,
+		&mut future,
+		terminations,
+		None,
+		None,
+		task_panics,
+		shutdown,
+	);
+	match (res, grace_period) {
+		(Ok(Stopped::Signal), Some(grace_period)) => {
+			match tokio_runtime.block_on(tokio::time::timeout(grace_period, future)) {
+				Ok(res) => res.map_err(|e| e.to_string()),
+				Err(_) => {
+					warn!("Command still running {:?} after being cancelled, dropping it", grace_period);
+					Ok(())
+				},
 			}
-		};
+		},
+		(res, _) => res.map(drop).map_err(|e| e.to_string()),
+	}
+}
 
-		Ok(Runner {
-			config: command.create_configuration(cli, task_executor.into())?,
-			tokio_runtime,
-			phantom: PhantomData,
-		})
+/// The path of the database of `config` if it is a RocksDB database, see
+/// [`ensure_database_unlocked`].
+fn rocksdb_path(config: &Configuration) -> Option<PathBuf> {
+	match &config.database {
+		sc_service::DatabaseConfig::RocksDb { path, .. } => Some(path.clone()),
+		_ => None,
 	}
+}
 
-	/// Log information about the node itself.
-	///
-	/// # Example:
-	///
-	/// ```text
-	/// 2020-06-03 16:14:21 Substrate Node
-	/// 2020-06-03 16:14:21 ✌️  version 2.0.0-rc3-f4940588c-x86_64-linux-gnu
-	/// 2020-06-03 16:14:21 ❤️  by Parity Technologies <admin@parity.io>, 2017-2020
-	/// 2020-06-03 16:14:21 📋 Chain specification: Flaming Fir
-	/// 2020-06-03 16:14:21 🏷 Node name: jolly-rod-7462
-	/// 2020-06-03 16:14:21 👤 Role: FULL
-	/// 2020-06-03 16:14:21 💾 Database: RocksDb at /tmp/c/chains/flamingfir7/db
-	/// 2020-06-03 16:14:21 ⛓  Native runtime: node-251 (substrate-node-1.tx1.au10)
-	/// ```
-	fn print_node_infos(&self) {
-		info!("{}", C::impl_name());
-		info!("✌️  version {}", C::impl_version());
-		info!(
-			"❤️  by {}, {}-{}",
-			C::author(),
-			C::copyright_start_year(),
-			Local::today().year(),
-		);
-		info!("📋 Chain specification: {}", self.config.chain_spec.name());
-		info!("🏷 Node name: {}", self.config.network.node_name);
-		info!("👤 Role: {}", self.config.display_role());
-		info!("💾 Database: {} at {}",
-			self.config.database,
-			self.config.database.path().map_or_else(|| "<unknown>".to_owned(), |p| p.display().to_string())
-		);
-		info!("⛓  Native runtime: {}", C::native_runtime_version(&self.config.chain_spec));
+/// Fail with [`Error::DatabaseLocked`](crate::Error::DatabaseLocked) if the RocksDB database at
+/// `path` is open in another process, e.g. a node running on the same base path, rather than
+/// with the error of RocksDB once opening it.
+///
+/// A lock that can't be probed, e.g. on a file system without locks, is left to RocksDB.
+fn ensure_database_unlocked(path: Option<&Path>) -> Result<()> {
+	let path = match path {
+		Some(path) => path,
+		None => return Ok(()),
+	};
+	match database_lock_held(path) {
+		Ok(true) => Err(crate::Error::DatabaseLocked(path.to_path_buf())),
+		Ok(false) => Ok(()),
+		Err(e) => {
+			debug!("Failed to probe the lock of the database at {}: {}", path.display(), e);
+			Ok(())
+		},
 	}
+}
 
-	/// A helper function that runs a node with tokio and stops if the process receives the signal
-	/// `SIGTERM` or `SIGINT`.
-	pub fn run_node_until_exit<F: Future<Output = sc_service::error::Result<TaskManager>>>(
-		mut self,
-		initialize: impl FnOnce(Configuration) -> F,
-	) -> Result<()> {
-		self.print_node_infos();
-		let mut task_manager = self.tokio_runtime.block_on(initialize(self.config))?;
-		let res = self.tokio_runtime.block_on(main(task_manager.future().fuse()));
-		self.tokio_runtime.block_on(task_manager.clean_shutdown());
-		res.map_err(|e| e.to_string().into())
+/// Whether another process holds the lock that RocksDB takes on the `LOCK` file of the database
+/// at `path` while it is open.
+fn database_lock_held(path: &Path) -> std::io::Result<bool> {
+	let lock = match std::fs::File::open(path.join("LOCK")) {
+		Ok(lock) => lock,
+		Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+		// `ERROR_SHARING_VIOLATION`: RocksDB opens its `LOCK` file for itself only.
+		#[cfg(windows)]
+		Err(e) if e.raw_os_error() == Some(32) => return Ok(true),
+		Err(e) => return Err(e),
+	};
+	lock_held(&lock)
+}
+
+/// RocksDB locks the whole file with `fcntl`, which `F_GETLK` reports unless this process holds
+/// the lock.
+#[cfg(unix)]
+fn lock_held(file: &std::fs::File) -> std::io::Result<bool> {
+	use std::os::unix::io::AsRawFd;
+
+	// `l_start` and `l_len` of 0 cover the whole file.
+	let mut lock: libc::flock = unsafe { std::mem::zeroed() };
+	lock.l_type = libc::F_WRLCK as _;
+	lock.l_whence = libc::SEEK_SET as _;
+	if unsafe { libc::fcntl(file.as_raw_fd(), libc::F_GETLK, &mut lock) } == -1 {
+		return Err(std::io::Error::last_os_error());
 	}
+	Ok(lock.l_type != libc::F_UNLCK as _)
+}
 
-	/// A helper function that runs a command with the configuration of this node.
-	pub fn sync_run(self, runner: impl FnOnce(Configuration) -> Result<()>) -> Result<()> {
-		runner(self.config)
+#[cfg(not(unix))]
+fn lock_held(file: &std::fs::File) -> std::io::Result<bool> {
+	use fs2::FileExt;
+
+	match file.try_lock_exclusive() {
+		Ok(()) => file.unlock().map(|()| false),
+		Err(e) if e.kind() == fs2::lock_contended_error().kind() => Ok(true),
+		Err(e) => Err(e),
 	}
+}
 
-	/// A helper function that runs a future with tokio and stops if the process receives
-	/// the signal `SIGTERM` or `SIGINT`.
-	pub fn async_run<FUT>(
-		self, runner: impl FnOnce(Configuration) -> Result<(FUT, TaskManager)>,
-	) -> Result<()>
-	where
-		FUT: Future<Output = Result<()>>,
-	{
-		let (future, task_manager) = runner(self.config)?;
-		run_until_exit(self.tokio_runtime, future, task_manager)
+/// Turn the `error` that stopped the node into the error of the runner.
+///
+/// The errors of the node stay [`sc_service::Error`]s, so that the caller can tell a requested
+/// restart or a full disk from a failure, e.g. with [`default_exit_code`].
+fn node_error(error: Box<dyn std::error::Error>) -> crate::Error {
+	let error = match error.downcast::<sc_service::Error>() {
+		Ok(error) => {
+			match *error {
+				sc_service::Error::RestartRequested => info!("🔁 Node ready to be restarted"),
+				sc_service::Error::DiskUsageLimitReached =>
+					info!("💽 Node stopped, free some disk space before restarting it"),
+				_ => {},
+			}
+			return crate::Error::Service(*error);
+		},
+		Err(error) => error,
+	};
+	match error.downcast::<crate::Error>() {
+		Ok(error) => *error,
+		Err(error) => error.to_string().into(),
+	}
+}
+
+/// The path whose disk usage is monitored for the node of `config`, see
+/// [`Runner::set_disk_usage_limit`].
+fn monitored_path(config: &Configuration) -> Option<PathBuf> {
+	config.database.path()
+		.or_else(|| config.base_path.as_ref().map(|p| p.path()))
+		.map(|p| p.to_path_buf())
+}
+
+/// The future of the node of `task_manager`, also failing once the disk usage of `monitored_path`
+/// reaches `disk_usage_limit`.
+fn node_future<'a>(
+	task_manager: &'a mut TaskManager,
+	disk_usage_limit: Option<DiskUsageLimit>,
+	monitored_path: Option<PathBuf>,
+) -> Pin<Box<dyn Future<Output = sc_service::error::Result<()>> + Send + 'a>> {
+	let node = task_manager.future();
+	match (disk_usage_limit, monitored_path) {
+		(Some(limit), Some(path)) => {
+			let monitor = Box::pin(monitor_disk_usage(path, limit));
+			Box::pin(future::select(node, monitor).map(|res| match res {
+				future::Either::Left((res, _)) | future::Either::Right((res, _)) => res,
+			}))
+		},
+		(Some(_), None) => {
+			warn!("The database isn't on disk, its usage isn't monitored");
+			node
+		},
+		(None, _) => node,
+	}
+}
+
+/// Prune the log files according to `retention`, now and then periodically in a task of
+/// `task_manager`, see [`Runner::set_log_retention`].
+fn start_log_retention(task_manager: &TaskManager, retention: Option<LogRetention>) {
+	if let Some(retention) = retention {
+		prune_logs_now(&retention);
+		task_manager.spawn_handle().spawn("log-retention", prune_logs_periodically(retention));
+	}
+}
+
+/// File marking a node as running, in the directory of its chain under its base path, e.g.
+/// `chains/dev`, see [`Runner::was_unclean_shutdown`].
+pub const RUNNING_MARKER_FILE: &str = "running.lock";
+
+/// The directory of the [`RUNNING_MARKER_FILE`] of the node of `config`, the one of its chain,
+/// which holds the directory of its database, so that the latter is only written by the database.
+///
+/// `None` when the database isn't on disk, since nothing of the node then outlives it.
+fn marker_dir(config: &Configuration) -> Option<PathBuf> {
+	config.database.path()?;
+	let base_path = config.base_path.as_ref()?;
+	Some(base_path.path().join("chains").join(config.chain_spec.id()))
+}
+
+/// Whether the marker of [`mark_running`] was left in `dir` by a previous run.
+fn was_running(dir: &Path) -> bool {
+	dir.join(RUNNING_MARKER_FILE).exists()
+}
+
+/// Mark the node whose marker is in `dir` as running, until [`clear_running`] is called with the
+/// returned marker.
+///
+/// This is best-effort: the node starts regardless, with a warning, when the marker can't be
+/// created.
+fn mark_running(dir: &Path) -> PathBuf {
+	let marker = dir.join(RUNNING_MARKER_FILE);
+	let created = std::fs::create_dir_all(dir)
+		.and_then(|()| std::fs::write(&marker, std::process::id().to_string()));
+	if let Err(e) = created {
+		warn!("Failed to create {}, crashes won't be detected: {}", marker.display(), e);
+	}
+	marker
+}
+
+/// Remove the `marker` of [`mark_running`] once the node shut down cleanly.
+fn clear_running(marker: &Path) {
+	match std::fs::remove_file(marker) {
+		Ok(()) => {},
+		Err(e) if e.kind() == std::io::ErrorKind::NotFound => {},
+		Err(e) => warn!("Failed to remove {}: {}", marker.display(), e),
+	}
+}
+
+/// Notify the service manager that the node of `task_manager` is ready, pinging its watchdog from
+/// then on if it has one, see [`sd_notify`].
+fn notify_ready(task_manager: &TaskManager) {
+	sd_notify::notify(sd_notify::READY);
+	if let Some(interval) = sd_notify::watchdog_interval() {
+		task_manager.spawn_handle().spawn("sd-notify-watchdog", sd_notify::watchdog(interval));
+	}
+}
+
+/// How [`Runner::run_node_until_exit_with_restart`] restarts a node that failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RestartPolicy {
+	/// How many times the node is restarted at most.
+	pub max_attempts: u32,
+	/// How long to wait before each restart.
+	pub backoff: Duration,
+}
+
+impl Default for RestartPolicy {
+	fn default() -> Self {
+		RestartPolicy {
+			max_attempts: 3,
+			backoff: Duration::from_secs(10),
+		}
+	}
+}
+
+/// A Substrate CLI runtime that can be used to run a node or a command
+pub struct Runner<C: SubstrateCli> {
+	config: Configuration,
+	tokio_runtime: tokio::runtime::Runtime,
+	task_manager: TaskManager,
+	task_panics: TaskPanics,
+	shutdown_timeout: Option<Duration>,
+	runtime_shutdown_timeout: Option<Duration>,
+	disk_usage_limit: Option<DiskUsageLimit>,
+	log_retention: Option<LogRetention>,
+	on_sighup: Option<SighupCallback>,
+	on_sigusr1: Option<DiagnosticsCallback>,
+	on_shutdown: Option<ShutdownHook>,
+	signal_set: SignalSet,
+	cancellation_grace_period: Duration,
+	node_infos_json: bool,
+	node_ready_line: bool,
+	unclean_shutdown: bool,
+	phantom: PhantomData<C>,
+}
+
+impl<C: SubstrateCli> Runner<C> {
+	/// Create a new runtime with the command provided in argument
+	pub fn new<T: CliConfiguration>(cli: &C, command: &T) -> Result<Runner<C>> {
+		Self::new_with_flavor(cli, command, RuntimeFlavor::MultiThread)
+	}
+
+	/// Create a new runtime with the command provided in argument, whose tasks are scheduled
+	/// according to `flavor`.
+	pub fn new_with_flavor<T: CliConfiguration>(
+		cli: &C,
+		command: &T,
+		flavor: RuntimeFlavor,
+	) -> Result<Runner<C>> {
+		let tokio_runtime = build_runtime_with_flavor(
+			flavor,
+			command.tokio_worker_threads()?,
+			command.tokio_max_threads()?,
+			&command.tokio_thread_name()?,
+			command.tokio_thread_stack_size()?,
+		)?;
+
+		Self::new_with_runtime(cli, command, tokio_runtime)
+	}
+
+	/// Create a new runner with the command provided in argument, running on `tokio_runtime`
+	/// instead of a runtime of its own, e.g. one the application already uses.
+	///
+	/// The tokio settings of `command` are then ignored. The runner takes the ownership of the
+	/// runtime: it is shut down, within [`Runner::set_runtime_shutdown_timeout`] if any, once the
+	/// node or command run by the runner exits, so the application shouldn't rely on it past
+	/// that point.
+	pub fn new_with_runtime<T: CliConfiguration>(
+		cli: &C,
+		command: &T,
+		tokio_runtime: tokio::runtime::Runtime,
+	) -> Result<Runner<C>> {
+		set_process_start_time();
+		let (task_panics_tx, task_panics) = tracing_unbounded("mpsc_task_panics");
+		let task_executor = task_executor(tokio_runtime.handle().clone(), Some(task_panics_tx));
+		let task_manager = TaskManager::new(task_executor.clone(), None)
+			.map_err(sc_service::Error::from)?;
+
+		let config = command.create_configuration(cli, task_executor)?;
+		let unclean_shutdown = marker_dir(&config).map_or(false, |dir| was_running(&dir));
+
+		Ok(Runner {
+			config,
+			tokio_runtime,
+			task_manager,
+			task_panics,
+			shutdown_timeout: shutdown_timeout_from_env(),
+			runtime_shutdown_timeout: None,
+			disk_usage_limit: None,
+			log_retention: None,
+			on_sighup: None,
+			on_sigusr1: None,
+			on_shutdown: None,
+			signal_set: SignalSet::default(),
+			cancellation_grace_period: DEFAULT_CANCELLATION_GRACE_PERIOD,
+			node_infos_json: false,
+			node_ready_line: true,
+			unclean_shutdown,
+			phantom: PhantomData,
+		})
+	}
+
+	/// A helper function that runs a node with tokio and stops if the process receives the signal
+	/// `SIGTERM` or `SIGINT`.
+	///
+	/// A node stopped by [`sc_service::RestartHandle::request_restart`] fails with
+	/// [`sc_service::Error::RestartRequested`], for which [`default_exit_code`] gives the
+	/// [`RESTART_EXIT_CODE`] its supervisor expects, see also [`Runner::run_node_until_exit_code`].
+	/// Likewise for [`sc_service::Error::DiskUsageLimitReached`], see
+	/// [`Runner::set_disk_usage_limit`].
+	pub fn run_node_until_exit<F: Future<Output = sc_service::error::Result<TaskManager>>>(
+		self,
+		initialize: impl FnOnce(Configuration) -> F,
+	) -> Result<()> {
+		ensure_database_unlocked(rocksdb_path(&self.config).as_deref())?;
+		self.run_node(initialize, None)?.map_err(node_error)
+	}
+
+	/// Like [`Runner::run_node_until_exit`], but the node is stopped once `trigger` resolves, as
+	/// it would be by `SIGTERM`, instead of by the signals of the process, which are left alone.
+	///
+	/// This lets tests drive the shutdown of a real node. `SIGHUP` and `SIGUSR1` aren't listened to
+	/// either, so [`Runner::set_on_sighup`] and [`Runner::set_on_sigusr1`] don't apply.
+	pub fn run_node_until_signal<F: Future<Output = sc_service::error::Result<TaskManager>>>(
+		self,
+		initialize: impl FnOnce(Configuration) -> F,
+		trigger: impl Future<Output = ()> + 'static,
+	) -> Result<()> {
+		ensure_database_unlocked(rocksdb_path(&self.config).as_deref())?;
+		self.run_node(initialize, Some(Box::pin(trigger)))?.map_err(node_error)
+	}
+
+	/// Like [`Runner::run_node_until_exit`], but the node is restarted in-process according to
+	/// `policy` when it fails or panics, instead of relying on an external supervisor.
+	///
+	/// Each restart shuts the failed node down, dropping its [`TaskManager`], then builds a new
+	/// node on the same tokio runtime with `initialize`, from a configuration created anew from
+	/// `cli` and `command`. A signal stopping the node, while it runs, is initialized or waits to
+	/// be restarted, stops it for good, as does the node running out of disk space or requesting
+	/// to be restarted by its supervisor. Failing to initialize the node stops it too. Once
+	/// `policy` allows no more restarts, the node stops with its last error.
+	pub fn run_node_until_exit_with_restart<T, F>(
+		self,
+		cli: &C,
+		command: &T,
+		mut initialize: impl FnMut(Configuration) -> F,
+		policy: RestartPolicy,
+	) -> Result<()>
+	where
+		T: CliConfiguration,
+		F: Future<Output = sc_service::error::Result<TaskManager>>,
+	{
+		let Runner {
+			config,
+			mut tokio_runtime,
+			task_manager: mut runner_tasks,
+			mut task_panics,
+			shutdown_timeout,
+			runtime_shutdown_timeout,
+			disk_usage_limit,
+			log_retention,
+			on_sighup,
+			on_sigusr1,
+			mut on_shutdown,
+			signal_set,
+			node_infos_json,
+			node_ready_line: log_ready,
+			..
+		} = self;
+
+		if node_infos_json {
+			print_node_infos_json::<C>(&config);
+		} else {
+			print_node_infos::<C>(&config);
+		}
+		start_log_retention(&runner_tasks, log_retention);
+		let task_executor = config.task_executor.clone();
+		let marker_dir = marker_dir(&config);
+		// Only created once the node was initialized, so that failing to initialize it isn't
+		// mistaken for a crash.
+		let mut marker = None;
+		let mut ready = Some(node_ready_line(&config)).filter(|_| log_ready);
+		let mut config = Some(config);
+		let mut attempts = 0;
+		// Listened to for all the attempts, so that no signal is missed between them.
+		let mut signals = Terminations::signals(&tokio_runtime, signal_set)?;
+
+		let res = loop {
+			let config = match config.take() {
+				Some(config) => config,
+				None => match command.create_configuration(cli, task_executor.clone()) {
+					Ok(config) => config,
+					Err(e) => break Err(Box::new(e) as Box<dyn std::error::Error>),
+				},
+			};
+			if let Err(e) = ensure_database_unlocked(rocksdb_path(&config).as_deref()) {
+				break Err(Box::new(e) as Box<dyn std::error::Error>);
+			}
+			let monitored_path = monitored_path(&config);
+			let initialized = tokio_runtime.block_on(async {
+				let initialize = initialize(config).fuse();
+				let signal = signals.recv().fuse();
+				pin_mut!(initialize, signal);
+
+				select! {
+					res = initialize => Some(res),
+					_ = signal => None,
+				}
+			});
+			let mut task_manager = match initialized {
+				Some(Ok(task_manager)) => task_manager,
+				Some(Err(e)) => break Err(Box::new(e) as Box<dyn std::error::Error>),
+				None => {
+					info!("Got a signal, not starting the node");
+					if let Some(on_shutdown) = on_shutdown.take() {
+						on_shutdown();
+					}
+					break Ok(());
+				},
+			};
+			if marker.is_none() {
+				marker = marker_dir.as_deref().map(mark_running);
+			}
+			notify_ready(&task_manager);
+			log_node_ready(&mut ready, &task_manager);
+
+			let res = {
+				let diagnostics = log_running_tasks(task_manager.running_tasks());
+				let node = future::select(
+					node_future(&mut task_manager, disk_usage_limit.clone(), monitored_path),
+					runner_tasks.future(),
+				).map(|res| match res {
+					future::Either::Left((res, _)) | future::Either::Right((res, _)) => res,
+				});
+				node_result(tokio_runtime.block_on(main(
+					catch_node_panic(node),
+					&mut signals,
+					on_sighup.as_ref(),
+					Some(on_sigusr1.as_ref().unwrap_or(&diagnostics)),
+					Some(&mut task_panics),
+					&mut on_shutdown,
+				)))
+			};
+
+			let error = match res {
+				Ok(()) => {
+					runner_tasks.add_child(task_manager);
+					break Ok(());
+				},
+				Err(error) => error,
+			};
+			// The supervisor of the node handles these, see `default_exit_code`.
+			let exit_requested = matches!(
+				error.downcast_ref(),
+				Some(sc_service::Error::DiskUsageLimitReached) |
+					Some(sc_service::Error::RestartRequested),
+			);
+			if exit_requested || attempts >= policy.max_attempts {
+				runner_tasks.add_child(task_manager);
+				break Err(error);
+			}
+
+			attempts += 1;
+			error!(
+				"💥 Node failed: {}, restarting it in {:?} (attempt {}/{})",
+				error,
+				policy.backoff,
+				attempts,
+				policy.max_attempts,
+			);
+			let running_tasks = task_manager.running_tasks();
+			wait_for_cleanup(
+				&mut tokio_runtime,
+				task_manager.clean_shutdown(),
+				Some(running_tasks),
+				shutdown_timeout,
+			);
+			// The tasks of the failed node that panicked don't fail the next one.
+			while let Ok(Some(_)) = task_panics.try_next() {}
+
+			let stopped = tokio_runtime.block_on(async {
+				let backoff = tokio::time::delay_for(policy.backoff).fuse();
+				let signal = signals.recv().fuse();
+				pin_mut!(backoff, signal);
+
+				select! {
+					_ = backoff => false,
+					_ = signal => true,
+				}
+			});
+			if stopped {
+				info!("Got a signal, not restarting the node");
+				if let Some(on_shutdown) = on_shutdown.take() {
+					on_shutdown();
+				}
+				break Ok(());
+			}
+		};
+
+		force_exit_on_interrupt(tokio_runtime.handle(), signals);
+		let running_tasks = runner_tasks.running_tasks();
+		let cleaned_up = shutdown_within(
+			tokio_runtime,
+			runner_tasks.clean_shutdown(),
+			Some(running_tasks),
+			shutdown_timeout,
+			runtime_shutdown_timeout,
+		);
+		if let (true, Some(marker)) = (cleaned_up, marker) {
+			clear_running(&marker);
+		}
+		res.map_err(node_error)
+	}
+
+	/// Like [`Runner::run_node_until_exit`], but return the exit code of the process instead of
+	/// failing.
+	///
+	/// The code is [`ExitCode::SUCCESS`] when the node stopped because of `SIGTERM` or `SIGINT`.
+	/// Otherwise the error that stopped the node, or failed its initialization, is logged and
+	/// mapped to a code by `exit_code`, e.g. [`default_exit_code`].
+	pub fn run_node_until_exit_code<F: Future<Output = sc_service::error::Result<TaskManager>>>(
+		self,
+		initialize: impl FnOnce(Configuration) -> F,
+		exit_code: impl FnOnce(&sc_service::Error) -> ExitCode,
+	) -> ExitCode {
+		if let Err(e) = ensure_database_unlocked(rocksdb_path(&self.config).as_deref()) {
+			error!("{}", e);
+			return ExitCode::FAILURE;
+		}
+		let error = match self.run_node(initialize, None) {
+			Ok(Ok(())) => return ExitCode::SUCCESS,
+			Ok(Err(e)) => match e.downcast::<sc_service::Error>() {
+				Ok(e) => *e,
+				Err(e) => {
+					error!("{}", e);
+					return ExitCode::FAILURE;
+				},
+			},
+			Err(e) => e,
+		};
+
+		error!("{}", error);
+		exit_code(&error)
+	}
+
+	/// Run the node until it stops, returning the error of the node, if any, once its tasks have
+	/// wound down. Fails if the node couldn't be initialized.
+	fn run_node<F: Future<Output = sc_service::error::Result<TaskManager>>>(
+		mut self,
+		initialize: impl FnOnce(Configuration) -> F,
+		trigger: Option<Pin<Box<dyn Future<Output = ()>>>>,
+	) -> std::result::Result<
+		std::result::Result<(), Box<dyn std::error::Error>>,
+		sc_service::Error,
+	> {
+		if self.node_infos_json {
+			print_node_infos_json::<C>(&self.config);
+		} else {
+			print_node_infos::<C>(&self.config);
+		}
+		let monitored_path = monitored_path(&self.config);
+		let marker_dir = marker_dir(&self.config);
+		let mut ready = Some(node_ready_line(&self.config)).filter(|_| self.node_ready_line);
+		start_log_retention(&self.task_manager, self.log_retention.take());
+		let mut task_manager = self.tokio_runtime.block_on(initialize(self.config))?;
+		task_manager.add_child(self.task_manager);
+
+		let running_tasks = task_manager.running_tasks();
+		let on_sigusr1 = self.on_sigusr1.unwrap_or_else(|| log_running_tasks(running_tasks.clone()));
+		let mut shutdown = ShutdownSettings {
+			on_shutdown: self.on_shutdown,
+			signal_set: self.signal_set,
+			running_tasks: Some(running_tasks),
+			shutdown_timeout: self.shutdown_timeout,
+			runtime_shutdown_timeout: self.runtime_shutdown_timeout,
+			cancellation: None,
+		};
+		// With a trigger, none of the signals of the process is listened to.
+		let (terminations, on_sighup, on_sigusr1) = match trigger {
+			Some(trigger) => (Terminations::Trigger(Some(trigger)), None, None),
+			None => (
+				Terminations::signals(&self.tokio_runtime, shutdown.signal_set)?,
+				self.on_sighup.as_ref(),
+				Some(&on_sigusr1),
+			),
+		};
+		// Only created once the node was initialized, so that failing to initialize it isn't
+		// mistaken for a crash.
+		let marker = marker_dir.as_deref().map(mark_running);
+		notify_ready(&task_manager);
+		log_node_ready(&mut ready, &task_manager);
+		let node = node_future(&mut task_manager, self.disk_usage_limit, monitored_path);
+		let res = node_result(block_on_main(
+			&mut self.tokio_runtime,
+			node,
+			terminations,
+			on_sighup,
+			on_sigusr1,
+			Some(&mut self.task_panics),
+			&mut shutdown,
+		));
+		let cleaned_up = shutdown_within(
+			self.tokio_runtime,
+			task_manager.clean_shutdown(),
+			shutdown.running_tasks,
+			shutdown.shutdown_timeout,
+			shutdown.runtime_shutdown_timeout,
+		);
+		if let (true, Some(marker)) = (cleaned_up, marker) {
+			clear_running(&marker);
+		}
+
+		Ok(res)
+	}
+
+	/// A helper function that runs a command with the configuration of this node.
+	///
+	/// `runner` is called on the current thread, outside of the tokio runtime: creating tokio
+	/// types there panics, see [`Runner::sync_run_with_runtime`] for that. The tasks spawned
+	/// through [`Runner::spawn_handle`] are terminated once `runner` returns.
+	///
+	/// Not all commands open the database, so it is only when `runner` fails that a database open
+	/// in another process is reported with [`Error::DatabaseLocked`](crate::Error::DatabaseLocked)
+	/// instead, unlike the other methods checking it before running anything.
+	pub fn sync_run(self, runner: impl FnOnce(Configuration) -> Result<()>) -> Result<()> {
+		let database_path = rocksdb_path(&self.config);
+		let res = runner(self.config)
+			.or_else(|e| ensure_database_unlocked(database_path.as_deref()).and(Err(e)));
+		let running_tasks = self.task_manager.running_tasks();
+		shutdown_within(
+			self.tokio_runtime,
+			self.task_manager.clean_shutdown(),
+			Some(running_tasks),
+			self.shutdown_timeout,
+			self.runtime_shutdown_timeout,
+		);
+		res
+	}
+
+	/// Like [`Runner::sync_run`], but `runner` is called within the context of the tokio runtime,
+	/// for the mostly synchronous commands that need tokio for an occasional async call.
+	///
+	/// Tokio types can then be created, and `futures::executor::block_on` resolves futures
+	/// relying on the runtime, as long as it has worker threads to drive them, i.e. with
+	/// [`RuntimeFlavor::MultiThread`].
+	pub fn sync_run_with_runtime(
+		self,
+		runner: impl FnOnce(Configuration) -> Result<()>,
+	) -> Result<()> {
+		let database_path = rocksdb_path(&self.config);
+		let config = self.config;
+		let res = self.tokio_runtime.enter(|| runner(config))
+			.or_else(|e| ensure_database_unlocked(database_path.as_deref()).and(Err(e)));
+		let running_tasks = self.task_manager.running_tasks();
+		shutdown_within(
+			self.tokio_runtime,
+			self.task_manager.clean_shutdown(),
+			Some(running_tasks),
+			self.shutdown_timeout,
+			self.runtime_shutdown_timeout,
+		);
+		res
+	}
+
+	/// A helper function that runs a future with tokio and stops if the process receives
+	/// the signal `SIGTERM` or `SIGINT`.
+	///
+	/// The future is dropped as soon as the signal is received, wherever it is in its work, which
+	/// may leave it half done. Commands that should rather stop at a point of their choosing use
+	/// [`Runner::async_run_cancellable`].
+	pub fn async_run<FUT>(
+		self, runner: impl FnOnce(Configuration) -> Result<(FUT, TaskManager)>,
+	) -> Result<()>
+	where
+		FUT: Future<Output = Result<()>>,
+	{
+		self.async_run_inner(runner, None).map(drop)
+	}
+
+	/// Like [`Runner::async_run`], but the output of the future is returned, e.g. a report
+	/// computed by the command.
+	///
+	/// It is an error for the command to be stopped by a signal before it completed.
+	pub fn async_run_with_output<T, FUT>(
+		self, runner: impl FnOnce(Configuration) -> Result<(FUT, TaskManager)>,
+	) -> Result<T>
+	where
+		FUT: Future<Output = Result<T>>,
+	{
+		self.async_run_inner(runner, None)?
+			.ok_or_else(|| "The command was stopped before it completed".into())
+	}
+
+	/// Like [`Runner::async_run`], but the future is passed a [`ShutdownToken`] and cancelled
+	/// instead of dropped when the signal is received.
+	///
+	/// The cancellation is cooperative: the future is responsible for watching the token, e.g. to
+	/// checkpoint its work and return. It is still dropped if it hasn't returned within
+	/// [`Runner::set_cancellation_grace_period`]; a second interrupt meanwhile exits the process.
+	pub fn async_run_cancellable<FUT>(
+		self, runner: impl FnOnce(Configuration, ShutdownToken) -> Result<(FUT, TaskManager)>,
+	) -> Result<()>
+	where
+		FUT: Future<Output = Result<()>>,
+	{
+		let (cancellation, token) = Cancellation::new(self.cancellation_grace_period);
+		self.async_run_inner(|config| runner(config, token), Some(cancellation)).map(drop)
+	}
+
+	fn async_run_inner<T, FUT>(
+		self,
+		runner: impl FnOnce(Configuration) -> Result<(FUT, TaskManager)>,
+		cancellation: Option<Cancellation>,
+	) -> Result<Option<T>>
+	where
+		FUT: Future<Output = Result<T>>,
+	{
+		ensure_database_unlocked(rocksdb_path(&self.config).as_deref())?;
+		let (future, mut task_manager) = runner(self.config)?;
+		task_manager.add_child(self.task_manager);
+		let shutdown = ShutdownSettings {
+			on_shutdown: self.on_shutdown,
+			signal_set: self.signal_set,
+			running_tasks: Some(task_manager.running_tasks()),
+			shutdown_timeout: self.shutdown_timeout,
+			runtime_shutdown_timeout: self.runtime_shutdown_timeout,
+			cancellation,
+		};
+		let mut task_panics = self.task_panics;
+		run_until_exit(
+			self.tokio_runtime,
+			future,
+			Some(&mut task_panics),
+			|| task_manager.clean_shutdown(),
+			shutdown,
+		)
+	}
+
+	/// Run a [`CliSubcommand`] like [`Runner::async_run`] does, on the components returned by
+	/// `builder` for the configuration of this runner.
+	///
+	/// `builder` also returns the task manager of the components, so that their tasks get stopped
+	/// with the subcommand.
+	pub fn run_cmd<B, S, Components, Builder>(self, cmd: &S, builder: Builder) -> Result<()>
+	where
+		B: BlockT,
+		S: CliSubcommand<B, Components>,
+		Builder: FnOnce(&Configuration) -> sc_service::error::Result<(Components, TaskManager)>,
+	{
+		self.async_run(|config| {
+			let (components, task_manager) = builder(&config)?;
+			Ok((cmd.run(components, config), task_manager))
+		})
+	}
+
+	/// Get a future resolving when the process receives a signal that stops the node, `SIGTERM`
+	/// or `SIGINT`, or Ctrl-C on the platforms without unix signals. On Windows, the console being
+	/// closed, the user logging off and the system shutting down stop it too.
+	///
+	/// This is what the node run by this runner waits for, so that code embedding it can compose
+	/// it with its own futures. The signals are delivered by the tokio runtime of this runner,
+	/// which must therefore be running for the future to resolve. The signals left out by
+	/// [`Runner::set_signal_set`] don't resolve it.
+	pub fn shutdown_signal(&self) -> Result<impl Future<Output = ()>> {
+		let signal_set = self.signal_set;
+		let mut signals = self.tokio_runtime.handle().enter(|| TerminationSignals::new(signal_set))?;
+
+		Ok(async move {
+			signals.recv().await;
+		})
+	}
+
+	/// Get a handle for spawning background tasks, e.g. a metrics exporter, on the tokio runtime
+	/// of this runner.
+	///
+	/// The tasks are terminated and awaited with the ones of the node or command when it exits,
+	/// whichever of `sync_run`, `async_run` or `run_node_until_exit` runs it.
+	pub fn spawn_handle(&self) -> SpawnTaskHandle {
+		self.task_manager.spawn_handle()
+	}
+
+	/// Get a handle for spawning essential background tasks, see [`Runner::spawn_handle`].
+	///
+	/// Under `run_node_until_exit`, the node stops when one of them fails.
+	pub fn spawn_essential_handle(&self) -> SpawnEssentialTaskHandle {
+		self.task_manager.spawn_essential_handle()
+	}
+
+	/// Set how long to wait for the tasks of the node to wind down once the node or command has
+	/// exited, e.g. after a `SIGINT` or `SIGTERM`.
+	///
+	/// Tasks still running after the timeout are abandoned along with the tokio runtime, and a
+	/// warning is logged, naming them; the node or command still exits successfully. `None` waits
+	/// for them indefinitely. The default is read from [`SHUTDOWN_TIMEOUT_ENV`], in seconds, and
+	/// is `None` when it isn't set or is invalid.
+	pub fn set_shutdown_timeout(&mut self, timeout: Option<Duration>) {
+		self.shutdown_timeout = timeout;
+	}
+
+	/// Set how long a command run by [`Runner::async_run_cancellable`] has to return once
+	/// cancelled, before being dropped. The default is [`DEFAULT_CANCELLATION_GRACE_PERIOD`].
+	pub fn set_cancellation_grace_period(&mut self, grace_period: Duration) {
+		self.cancellation_grace_period = grace_period;
+	}
+
+	/// Set how long to wait for the outstanding `spawn_blocking` tasks when the tokio runtime is
+	/// shut down, once the node or command has exited.
+	///
+	/// Blocking tasks still running after the timeout are abandoned and a warning is logged.
+	/// `None`, the default, waits for them indefinitely.
+	pub fn set_runtime_shutdown_timeout(&mut self, timeout: Option<Duration>) {
+		self.runtime_shutdown_timeout = timeout;
+	}
+
+	/// Shut the node down cleanly once the disk usage of its database reaches the high-water mark
+	/// of `limit`, failing with [`sc_service::Error::DiskUsageLimitReached`], for which
+	/// [`default_exit_code`] gives [`DISK_USAGE_EXIT_CODE`].
+	///
+	/// `None`, the default, doesn't monitor the disk usage. Only applies to
+	/// [`Runner::run_node_until_exit`].
+	pub fn set_disk_usage_limit(&mut self, limit: Option<DiskUsageLimit>) {
+		self.disk_usage_limit = limit;
+	}
+
+	/// Delete the oldest log files of the node according to `retention`, when the node starts and
+	/// then every `retention.interval`, logging the deleted files.
+	///
+	/// `None`, the default, keeps all the log files. Only applies to the node, not to the other
+	/// commands.
+	pub fn set_log_retention(&mut self, retention: Option<LogRetention>) {
+		self.log_retention = retention;
+	}
+
+	/// Call `hook` once the process receives a signal stopping the node or command, before its
+	/// tasks are terminated, e.g. to flush external state or notify a sidecar.
+	///
+	/// It isn't called when the node or command stops on its own, successfully or not.
+	pub fn set_on_shutdown(&mut self, hook: impl FnOnce() + Send + 'static) {
+		self.on_shutdown = Some(Box::new(hook));
+	}
+
+	/// Whether the previous run of the node with this database didn't shut down cleanly, e.g. it
+	/// crashed or was killed, as told by the [`RUNNING_MARKER_FILE`] it left in the directory of
+	/// its chain.
+	///
+	/// The marker is created once `run_node_until_exit` initialized the node and removed once its
+	/// tasks are done at shutdown. Always `false` for a database that isn't on disk.
+	pub fn was_unclean_shutdown(&self) -> bool {
+		self.unclean_shutdown
+	}
+
+	/// Set which of the signals stopping the node or command are handled, e.g. to leave `SIGTERM`
+	/// to the default disposition of the OS when running under a debugger.
+	///
+	/// The node or command is still driven to completion when a signal isn't handled, it just
+	/// doesn't stop gracefully on it. Both `SIGINT` and `SIGTERM` are handled by default.
+	pub fn set_signal_set(&mut self, signal_set: SignalSet) {
+		self.signal_set = signal_set;
+	}
+
+	/// Call `callback` whenever the process receives `SIGHUP` while the node runs, instead of
+	/// terminating, e.g. to reopen the log files after they have been rotated.
+	///
+	/// Only `SIGINT` and `SIGTERM` stop the node then. Without a callback, the default,
+	/// `SIGHUP` isn't handled. Only applies to [`Runner::run_node_until_exit`], on unix.
+	pub fn set_on_sighup(&mut self, callback: impl Fn() + Send + Sync + 'static) {
+		self.on_sighup = Some(Box::new(callback));
+	}
+
+	/// Call `callback` whenever the process receives `SIGUSR1` while the node runs, e.g. to dump
+	/// diagnostics of a node that seems stuck with `kill -USR1 <pid>`.
+	///
+	/// The node keeps running. The default callback logs the tasks of the node that are running.
+	/// Only applies to [`Runner::run_node_until_exit`], on unix.
+	pub fn set_on_sigusr1(&mut self, callback: impl Fn() + Send + Sync + 'static) {
+		self.on_sigusr1 = Some(Box::new(callback));
+	}
+
+	/// Run the node, or the command, against an empty in-memory database instead of the one
+	/// selected by the configuration.
+	///
+	/// Everything written to the database is lost when the node shuts down, which is mostly
+	/// useful for tests. This is the same as passing `--database memory`.
+	pub fn use_in_memory_database(&mut self) {
+		self.config.database = sc_service::DatabaseConfig::in_memory();
+	}
+
+	/// Load the genesis state of the chain spec from a file written by the `cache-genesis`
+	/// subcommand, instead of building it when the node starts.
+	///
+	/// Fails if the cache wasn't built from the same chain spec, or if its content doesn't match
+	/// the genesis hash recorded in it.
+	pub fn use_genesis_cache<B: BlockT>(&mut self, path: &Path) -> Result<()> {
+		let file = std::io::BufReader::new(std::fs::File::open(path)?);
+		let storage = read_genesis_cache::<B>(&*self.config.chain_spec, file)?;
+		self.config.chain_spec.set_storage(storage);
+		Ok(())
+	}
+
+	/// Read from the database of the replica node whose base path is `base_path`, instead of the
+	/// one of this node. The replica is opened read-only and can keep running meanwhile.
+	///
+	/// Fails if the replica doesn't have a RocksDB database for this chain, or if its genesis
+	/// block differs from the one of this chain.
+	pub fn use_read_replica<B: BlockT>(&mut self, base_path: &Path) -> Result<()> {
+		self.config.database = read_replica_database::<B>(
+			&self.config.database,
+			&*self.config.chain_spec,
+			base_path,
+		)?;
+		Ok(())
+	}
+
+	/// Print the blocks in the logs of the informant with `formatter`, instead of their number
+	/// and hash.
+	///
+	/// It is used for the `Imported` lines and for the best and finalized blocks of the periodic
+	/// status line, e.g. to add fields like the number of extrinsics of the block.
+	pub fn set_informant_block_formatter<B: BlockT>(
+		&mut self,
+		formatter: impl Fn(&InformantBlock<B>) -> String + Send + Sync + 'static,
+	) {
+		self.config.informant_output_format.set_block_formatter(formatter);
+	}
+
+	/// Print at most one line per `interval` for the blocks imported by the node, summarizing
+	/// the blocks imported since the previous line, instead of one line per block.
+	///
+	/// `None`, the default, prints a line for every imported block.
+	pub fn set_informant_import_summary_interval(&mut self, interval: Option<Duration>) {
+		self.config.informant_output_format.import_summary_interval = interval;
+	}
+
+	/// Only spawn the tasks of the node allowed by `filter`, e.g. to run a node without its
+	/// offchain workers while debugging.
+	///
+	/// Tasks are matched by the name they are spawned with, not by group: leaving out a part of the
+	/// node, e.g. block authoring, means listing the names of all of its tasks, such as
+	/// `babe-proposer`. `filter` is logged as a warning once the node is built, and each task that
+	/// isn't spawned at the debug level.
+	pub fn set_task_filter(&mut self, filter: TaskFilter) {
+		self.config.task_filter = filter;
+	}
+
+	/// Reject the calls to the RPC servers with a "syncing" error until the node has caught up
+	/// with the network, e.g. to keep load balancers from routing traffic to a read replica that
+	/// would serve stale data.
+	///
+	/// The node is considered caught up the first time it knows the best block of its peers and
+	/// isn't major syncing anymore. It is disabled by default.
+	pub fn set_rpc_wait_for_sync(&mut self, wait: bool) {
+		self.config.rpc_wait_for_sync = wait;
+	}
+
+	/// Delay the start of the authoring task until the node is connected to at least
+	/// `min_peers` peers, so that it doesn't author blocks in isolation after a fresh start or
+	/// a network partition. Authoring starts anyway once `timeout` has elapsed.
+	///
+	/// Only applies to nodes whose service honors [`Configuration::min_peers_before_authoring`],
+	/// e.g. through [`sc_service::wait_for_authoring_peers`]. It is disabled by default.
+	pub fn set_min_peers_before_authoring(&mut self, min_peers: usize, timeout: Duration) {
+		self.config.min_peers_before_authoring = Some(MinPeersBeforeAuthoring { min_peers, timeout });
+	}
+
+	/// Import at most `max_blocks_per_sec` blocks per second until the node has caught up with
+	/// the network, so that a node catching up doesn't starve the other services running on the
+	/// same machine.
+	///
+	/// Only applies to nodes whose service honors [`Configuration::max_catch_up_import_rate`],
+	/// e.g. through [`sc_service::throttle_import_until_synced`]. It is disabled by default.
+	pub fn set_max_catch_up_import_rate(&mut self, max_blocks_per_sec: Option<u32>) {
+		self.config.max_catch_up_import_rate = max_blocks_per_sec;
+	}
+
+	/// Shift the clock used by the timestamp inherent and the slots of the node by `offset_ms`
+	/// milliseconds, to test how consensus behaves on a node whose clock is ahead of, or
+	/// behind, the other ones.
+	///
+	/// The offset applies process-wide, see [`sp_timestamp::set_clock_offset`]. Fails unless
+	/// the chain is a development or local one.
+	#[cfg(feature = "clock-offset")]
+	pub fn set_clock_offset(&mut self, offset_ms: i64) -> Result<()> {
+		match self.config.chain_spec.chain_type() {
+			sc_service::ChainType::Development | sc_service::ChainType::Local => {},
+			_ => return Err(
+				"The clock offset is only available on development and local chains".into(),
+			),
+		}
+
+		warn!("⏰ Clock shifted by {}ms for consensus. This is meant for testing only.", offset_ms);
+		sp_timestamp::set_clock_offset(offset_ms);
+		Ok(())
+	}
+
+	/// Log the information about the node as a single JSON line when it starts, instead of the
+	/// human-readable lines, so that log pipelines can index it.
+	///
+	/// It is disabled by default.
+	pub fn set_node_infos_json(&mut self, enable: bool) {
+		self.node_infos_json = enable;
+	}
+
+	/// Log a single line starting with `NODE_READY`, with the [`NODE_READY_TARGET`] target at the
+	/// info level, once the node is initialized, for the tools waiting for it to be ready.
+	///
+	/// The line gives the name, role and chain of the node, and its best block when the node was
+	/// built with [`sc_service::spawn_tasks`]. It is logged once, even if the node is restarted.
+	/// It is enabled by default.
+	pub fn set_node_ready_line(&mut self, enable: bool) {
+		self.node_ready_line = enable;
+	}
+
+	/// Get the Prometheus registry of the node, if Prometheus is enabled.
+	///
+	/// Custom collectors registered on it are exposed on the node's own metrics endpoint, which
+	/// only starts listening once the node is being initialized by
+	/// [`Runner::run_node_until_exit`]. The same registry is available from the `initialize`
+	/// closure through [`Configuration::prometheus_registry`].
+	pub fn prometheus_registry(&self) -> Option<&Registry> {
+		self.config.prometheus_registry()
 	}
 
 	/// Get an immutable reference to the node Configuration
@@ -210,3 +2174,1315 @@ impl<C: SubstrateCli> Runner<C> {
 		&mut self.config
 	}
 }
+
+/// A Substrate CLI runner that spawns the tasks of the node on a tokio runtime owned by the
+/// caller, for embedding the node into an existing async application.
+///
+/// Unlike [`Runner`], it never creates nor drops a runtime and doesn't listen to any signal: the
+/// caller is responsible for driving the runtime, handling the signals and shutting down. The
+/// settings of [`Runner`] about these, the shutdown timeouts, the disk usage limit, the log
+/// retention and the service manager notifications, thus don't apply to it. The node information
+/// lines, the `NODE_READY` line and the [`RUNNING_MARKER_FILE`] are handled the same way.
+pub struct EmbeddedRunner<C: SubstrateCli> {
+	config: Configuration,
+	node_infos_json: bool,
+	node_ready_line: bool,
+	unclean_shutdown: bool,
+	phantom: PhantomData<C>,
+}
+
+impl<C: SubstrateCli> EmbeddedRunner<C> {
+	/// Create a new runner with the command provided in argument, spawning its tasks on the
+	/// runtime of `runtime_handle`.
+	pub fn new<T: CliConfiguration>(
+		cli: &C,
+		command: &T,
+		runtime_handle: tokio::runtime::Handle,
+	) -> Result<EmbeddedRunner<C>> {
+		set_process_start_time();
+		let config = command.create_configuration(cli, task_executor(runtime_handle, None))?;
+		let unclean_shutdown = marker_dir(&config).map_or(false, |dir| was_running(&dir));
+
+		Ok(EmbeddedRunner {
+			config,
+			node_infos_json: false,
+			node_ready_line: true,
+			unclean_shutdown,
+			phantom: PhantomData,
+		})
+	}
+
+	/// Run a node until one of its essential tasks fails or it is terminated.
+	///
+	/// This must be awaited on the runtime given to [`EmbeddedRunner::new`]. Dropping the
+	/// returned future stops waiting for the node without shutting it down; to stop a node
+	/// cleanly the caller should terminate its `TaskManager` and await this future instead.
+	pub async fn run_node_until_exit<F: Future<Output = sc_service::error::Result<TaskManager>>>(
+		self,
+		initialize: impl FnOnce(Configuration) -> F,
+	) -> Result<()> {
+		ensure_database_unlocked(rocksdb_path(&self.config).as_deref())?;
+		if self.node_infos_json {
+			print_node_infos_json::<C>(&self.config);
+		} else {
+			print_node_infos::<C>(&self.config);
+		}
+		let marker_dir = marker_dir(&self.config);
+		let mut ready = Some(node_ready_line(&self.config)).filter(|_| self.node_ready_line);
+		let mut task_manager = initialize(self.config).await?;
+
+		let marker = marker_dir.as_deref().map(mark_running);
+		log_node_ready(&mut ready, &task_manager);
+		let res = task_manager.future().await;
+		task_manager.clean_shutdown().await;
+		if let Some(marker) = marker {
+			clear_running(&marker);
+		}
+		res.map_err(|e| e.to_string().into())
+	}
+
+	/// Log the information about the node as a single JSON line when it starts, see
+	/// [`Runner::set_node_infos_json`].
+	pub fn set_node_infos_json(&mut self, enable: bool) {
+		self.node_infos_json = enable;
+	}
+
+	/// Log the `NODE_READY` line once the node is initialized, see
+	/// [`Runner::set_node_ready_line`].
+	pub fn set_node_ready_line(&mut self, enable: bool) {
+		self.node_ready_line = enable;
+	}
+
+	/// Whether the previous run of the node with this database didn't shut down cleanly, see
+	/// [`Runner::was_unclean_shutdown`].
+	pub fn was_unclean_shutdown(&self) -> bool {
+		self.unclean_shutdown
+	}
+
+	/// Get an immutable reference to the node Configuration
+	pub fn config(&self) -> &Configuration {
+		&self.config
+	}
+
+	/// Get a mutable reference to the node Configuration
+	pub fn config_mut(&mut self) -> &mut Configuration {
+		&mut self.config
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::log_retention::prune_logs;
+	use std::{env, io, process::Command, thread, time::SystemTime};
+
+	#[test]
+	fn shutdown_timeout_abandons_stuck_tasks() {
+		let tokio_runtime = build_runtime().unwrap();
+		let stuck = tokio_runtime.handle().spawn_blocking(|| loop {
+			thread::sleep(Duration::from_millis(10));
+		});
+
+		let started = Instant::now();
+		let res = run_until_exit(
+			tokio_runtime,
+			future::ready(Ok::<(), io::Error>(())),
+			None,
+			move || stuck.map(drop),
+			ShutdownSettings {
+				shutdown_timeout: Some(Duration::from_millis(100)),
+				..Default::default()
+			},
+		);
+
+		assert!(res.is_ok());
+		assert!(started.elapsed() < Duration::from_secs(5));
+	}
+
+	#[test]
+	fn node_runs_under_each_runtime_flavor() {
+		for &flavor in &[RuntimeFlavor::MultiThread, RuntimeFlavor::CurrentThread] {
+			let tokio_runtime = build_runtime_with_flavor(
+				flavor,
+				None,
+				None,
+				DEFAULT_TOKIO_THREAD_NAME,
+				None,
+			).unwrap();
+			let task_executor = task_executor(tokio_runtime.handle().clone(), None);
+
+			let (async_tx, async_rx) = futures::channel::oneshot::channel();
+			let (blocking_tx, blocking_rx) = futures::channel::oneshot::channel();
+			let _ = task_executor.spawn(Box::pin(async move {
+				let _ = async_tx.send(());
+			}), TaskType::Async);
+			let _ = task_executor.spawn(Box::pin(async move {
+				let _ = blocking_tx.send(thread::current().id());
+			}), TaskType::Blocking);
+
+			let node = async move {
+				async_rx.await.map_err(|_| io::Error::new(io::ErrorKind::Other, "async task lost"))?;
+				let blocking_thread = blocking_rx.await
+					.map_err(|_| io::Error::new(io::ErrorKind::Other, "blocking task lost"))?;
+				assert_ne!(blocking_thread, thread::current().id());
+				Ok::<(), io::Error>(())
+			};
+
+			let res = run_until_exit(
+				tokio_runtime,
+				node,
+				None,
+				|| future::ready(()),
+				ShutdownSettings::default(),
+			);
+			assert!(res.is_ok(), "{:?}: {:?}", flavor, res);
+		}
+	}
+
+	#[cfg(feature = "task-gauges")]
+	#[test]
+	fn spawned_tasks_are_counted_until_they_finish() {
+		let executable = env::current_exe().unwrap();
+		let output = Command::new(executable)
+			.env("COUNT_TASKS", "1")
+			.args(&["--nocapture", "spawned_tasks_are_counted_until_they_finish_entrypoint"])
+			.output()
+			.unwrap();
+
+		let stdout = String::from_utf8_lossy(&output.stdout);
+		assert!(output.status.success(), "{}{}", stdout, String::from_utf8_lossy(&output.stderr));
+		assert!(stdout.contains("TASKS_COUNTED"), "{}", stdout);
+	}
+
+	/// This is no actual test, it will be used by the `spawned_tasks_are_counted_until_they_finish`
+	/// test. It runs in a process of its own, so that no task of another test changes the gauge.
+	#[cfg(feature = "task-gauges")]
+	#[test]
+	fn spawned_tasks_are_counted_until_they_finish_entrypoint() {
+		if env::var("COUNT_TASKS").is_err() {
+			return;
+		}
+
+		let mut tokio_runtime = build_runtime().unwrap();
+		let task_executor = task_executor(tokio_runtime.handle().clone(), None);
+		let baseline = TOKIO_TASKS_ACTIVE.get();
+
+		let (started_tx, started_rx) = futures::channel::oneshot::channel();
+		let (finish_tx, finish_rx) = futures::channel::oneshot::channel::<()>();
+		let _ = task_executor.spawn(Box::pin(async move {
+			let _ = started_tx.send(());
+			let _ = finish_rx.await;
+		}), TaskType::Async);
+
+		tokio_runtime.block_on(started_rx).unwrap();
+		assert_eq!(TOKIO_TASKS_ACTIVE.get(), baseline + 1);
+
+		// The task is uncounted once it finished, on a thread of the runtime.
+		let _ = finish_tx.send(());
+		let started = Instant::now();
+		while TOKIO_TASKS_ACTIVE.get() != baseline {
+			assert!(started.elapsed() < Duration::from_secs(5), "the task is still counted");
+			thread::sleep(Duration::from_millis(10));
+		}
+		drop(tokio_runtime);
+		println!("TASKS_COUNTED");
+	}
+
+	#[cfg(feature = "tokio-console")]
+	#[test]
+	fn spawned_tasks_are_traced() {
+		let executable = env::current_exe().unwrap();
+		let output = Command::new(executable)
+			.env(TASK_TRACES_ENV, "1")
+			.args(&["--nocapture", "spawned_tasks_are_traced_entrypoint"])
+			.output()
+			.unwrap();
+
+		let stdout = String::from_utf8_lossy(&output.stdout);
+		assert!(output.status.success(), "{}{}", stdout, String::from_utf8_lossy(&output.stderr));
+		assert!(stdout.contains("TASKS_TRACED"), "{}", stdout);
+	}
+
+	/// This is no actual test, it will be used by the `spawned_tasks_are_traced` test. It runs in
+	/// a process of its own, which is the only one with the task traces turned on.
+	#[cfg(feature = "tokio-console")]
+	#[test]
+	fn spawned_tasks_are_traced_entrypoint() {
+		use std::sync::{Arc, atomic::AtomicUsize};
+		use tracing_subscriber::layer::{Context, SubscriberExt};
+
+		/// Counts the `runtime.spawn` spans, by kind.
+		#[derive(Clone, Default)]
+		struct SpawnSpans(Arc<(AtomicUsize, AtomicUsize)>);
+
+		impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for SpawnSpans {
+			fn new_span(
+				&self,
+				attrs: &tracing::span::Attributes<'_>,
+				_: &tracing::span::Id,
+				_: Context<'_, S>,
+			) {
+				let metadata = attrs.metadata();
+				if metadata.target() != "tokio::task" || metadata.name() != "runtime.spawn" {
+					return;
+				}
+				let blocking = format!("{:?}", attrs.values()).contains("blocking");
+				let count = if blocking { &(self.0).1 } else { &(self.0).0 };
+				count.fetch_add(1, Ordering::SeqCst);
+			}
+		}
+
+		if env::var(TASK_TRACES_ENV).is_err() {
+			return;
+		}
+
+		let spans = SpawnSpans::default();
+		let subscriber = tracing_subscriber::registry().with(spans.clone());
+		let mut tokio_runtime = build_runtime().unwrap();
+		let task_executor = task_executor(tokio_runtime.handle().clone(), None);
+
+		let (async_tx, async_rx) = futures::channel::oneshot::channel();
+		let (blocking_tx, blocking_rx) = futures::channel::oneshot::channel();
+		tracing::subscriber::with_default(subscriber, || {
+			let _ = task_executor.spawn(Box::pin(async move {
+				let _ = async_tx.send(());
+			}), TaskType::Async);
+			let _ = task_executor.spawn(Box::pin(async move {
+				let _ = blocking_tx.send(());
+			}), TaskType::Blocking);
+		});
+
+		tokio_runtime.block_on(future::join(async_rx, blocking_rx));
+		assert_eq!((spans.0).0.load(Ordering::SeqCst), 1);
+		assert_eq!((spans.0).1.load(Ordering::SeqCst), 1);
+		drop(tokio_runtime);
+		println!("TASKS_TRACED");
+	}
+
+	#[test]
+	fn runtime_threads_get_the_stack_size() {
+		/// Use about `depth` KiB of stack.
+		fn recurse(depth: usize) -> u8 {
+			let frame = [depth as u8; 1024];
+			if depth == 0 {
+				return 0;
+			}
+			// Reading the frame keeps it from being optimized away.
+			recurse(depth - 1).wrapping_add(unsafe { std::ptr::read_volatile(&frame[depth % 1024]) })
+		}
+
+		// Far more than the 2 MiB tokio gives by default.
+		const DEPTH: usize = 8 * 1024;
+		let tokio_runtime = build_runtime_with_flavor(
+			RuntimeFlavor::MultiThread,
+			None,
+			None,
+			DEFAULT_TOKIO_THREAD_NAME,
+			Some(64 << 20),
+		).unwrap();
+		let task_executor = task_executor(tokio_runtime.handle().clone(), None);
+
+		let (async_tx, async_rx) = futures::channel::oneshot::channel();
+		let (blocking_tx, blocking_rx) = futures::channel::oneshot::channel();
+		let _ = task_executor.spawn(Box::pin(async move {
+			let _ = async_tx.send(recurse(DEPTH));
+		}), TaskType::Async);
+		let _ = task_executor.spawn(Box::pin(async move {
+			let _ = blocking_tx.send(recurse(DEPTH));
+		}), TaskType::Blocking);
+
+		let node = async move {
+			async_rx.await.map_err(|_| io::Error::new(io::ErrorKind::Other, "async task lost"))?;
+			blocking_rx.await
+				.map_err(|_| io::Error::new(io::ErrorKind::Other, "blocking task lost"))?;
+			Ok::<(), io::Error>(())
+		};
+
+		let res = run_until_exit(
+			tokio_runtime,
+			node,
+			None,
+			|| future::ready(()),
+			ShutdownSettings::default(),
+		);
+		assert!(res.is_ok(), "{:?}", res);
+	}
+
+	#[test]
+	fn runtime_threads_are_named() {
+		let mut tokio_runtime = build_runtime_with_flavor(
+			RuntimeFlavor::MultiThread,
+			Some(2),
+			None,
+			"test-worker",
+			None,
+		).unwrap();
+
+		let (worker, blocking) = tokio_runtime.block_on(async {
+			let worker = tokio::spawn(async { thread::current().name().map(String::from) });
+			let blocking = tokio::task::spawn_blocking(|| {
+				thread::current().name().map(String::from)
+			});
+			(worker.await.unwrap(), blocking.await.unwrap())
+		});
+
+		for name in &[worker, blocking] {
+			assert!(
+				name.as_ref().map_or(false, |name| name.starts_with("test-worker-")),
+				"{:?}",
+				name,
+			);
+		}
+		// The thread hooks still run along with the naming.
+		assert!(TOKIO_THREADS_TOTAL.get() > 0);
+	}
+
+	#[cfg(target_os = "linux")]
+	#[test]
+	fn process_start_time_is_when_the_process_started() {
+		let since_epoch = || {
+			std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs()
+		};
+
+		let spawned = since_epoch();
+		let output = Command::new(env::current_exe().unwrap())
+			.env("READ_PROCESS_START_TIME", "1")
+			.args(&[
+				"--nocapture",
+				"--exact",
+				"runner::tests::process_start_time_is_when_the_process_started_entrypoint",
+			])
+			.output()
+			.unwrap();
+
+		let stdout = String::from_utf8_lossy(&output.stdout);
+		assert!(output.status.success(), "{}", stdout);
+		let start: u64 = stdout
+			.lines()
+			.find_map(|line| line.strip_prefix("PROCESS_START_TIME="))
+			.unwrap()
+			.parse()
+			.unwrap();
+		// Not when the runtime was built, seconds later. The boot time is given in whole seconds.
+		assert!(spawned <= start + 1 && start <= spawned + 1, "{} for {}", start, spawned);
+	}
+
+	/// This is no actual test, it will be used by the
+	/// `process_start_time_is_when_the_process_started` test. It builds its first runtime seconds
+	/// after it started.
+	#[cfg(target_os = "linux")]
+	#[test]
+	fn process_start_time_is_when_the_process_started_entrypoint() {
+		if env::var("READ_PROCESS_START_TIME").is_err() {
+			return;
+		}
+
+		thread::sleep(Duration::from_secs(3));
+		let _tokio_runtime = build_runtime().unwrap();
+		println!("PROCESS_START_TIME={}", PROCESS_START_TIME_SECONDS.get());
+	}
+
+	/// Run the `entrypoint` test in a process of its own, so that the signals it sends itself
+	/// don't reach the other tests, and check that it completed, returning its logs.
+	#[cfg(unix)]
+	fn run_signal_test(entrypoint: &str) -> String {
+		let executable = env::current_exe().unwrap();
+		let output = Command::new(executable)
+			.env("RUN_SIGNAL_TEST", "1")
+			.args(&["--nocapture", "--exact", &format!("runner::tests::{}", entrypoint)])
+			.output()
+			.unwrap();
+
+		let stdout = String::from_utf8_lossy(&output.stdout);
+		let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+		assert!(output.status.success(), "{}{}", stdout, stderr);
+		assert!(stdout.contains("SIGNAL_TEST_DONE"), "{}", stdout);
+		stderr
+	}
+
+	struct TestCli;
+
+	impl SubstrateCli for TestCli {
+		fn impl_name() -> String { "Test node".into() }
+		fn impl_version() -> String { "0.0.0".into() }
+		fn description() -> String { String::new() }
+		fn author() -> String { String::new() }
+		fn support_url() -> String { String::new() }
+		fn copyright_start_year() -> i32 { 2020 }
+		fn load_spec(&self, _: &str) -> std::result::Result<Box<dyn ChainSpec>, String> {
+			Ok(Box::new(sc_service::GenericChainSpec::<()>::from_genesis(
+				"Test",
+				"test",
+				sc_service::ChainType::Development,
+				|| (),
+				Vec::new(),
+				None,
+				None,
+				None,
+				None,
+			)))
+		}
+		fn native_runtime_version(_: &dyn ChainSpec) -> Option<&'static crate::RuntimeVersion> {
+			None
+		}
+	}
+
+	/// A runner of a node named `test`, whose data is kept in `base_path`.
+	fn test_runner(base_path: &Path) -> Runner<TestCli> {
+		use structopt::StructOpt;
+
+		let base_path = base_path.to_str().unwrap();
+		let cmd = crate::RunCmd::from_iter(&["node", "--base-path", base_path, "--name", "test"]);
+		Runner::new(&TestCli, &cmd).unwrap()
+	}
+
+	/// Send `signal`, e.g. `-TERM`, to the current process.
+	#[cfg(unix)]
+	fn kill_self(signal: &str) -> io::Result<()> {
+		Command::new("kill").args(&[signal, &std::process::id().to_string()]).status().map(drop)
+	}
+
+	/// Resolves once the runner reports the node of `task_manager` as ready, with the best block
+	/// `#0 (0x2a)`, which is after it installed its signal handlers.
+	#[cfg(unix)]
+	fn once_ready(task_manager: &mut TaskManager) -> futures::channel::oneshot::Receiver<()> {
+		let (ready_tx, ready_rx) = futures::channel::oneshot::channel();
+		let ready_tx = std::sync::Mutex::new(Some(ready_tx));
+		task_manager.set_best_block(move || {
+			if let Some(ready_tx) = ready_tx.lock().unwrap().take() {
+				let _ = ready_tx.send(());
+			}
+			"#0 (0x2a)".to_owned()
+		});
+		ready_rx
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn trigger_leaves_the_signals_alone() {
+		use std::os::unix::process::ExitStatusExt;
+
+		let entrypoint = "runner::tests::trigger_leaves_the_signals_alone_entrypoint";
+		let output = Command::new(env::current_exe().unwrap())
+			.env("RUN_SIGNAL_TEST", "1")
+			.args(&["--nocapture", "--exact", entrypoint])
+			.output()
+			.unwrap();
+
+		// `SIGUSR1` kills the process, as it does by default, instead of logging the tasks.
+		let stdout = String::from_utf8_lossy(&output.stdout);
+		assert_eq!(output.status.signal(), Some(libc::SIGUSR1), "{}", stdout);
+		assert!(!stdout.contains("SIGNAL_TEST_DONE"), "{}", stdout);
+	}
+
+	/// This is no actual test, it will be used by the `trigger_leaves_the_signals_alone` test. It
+	/// sends itself `SIGUSR1` while running a node until a trigger stops it.
+	#[cfg(unix)]
+	#[test]
+	fn trigger_leaves_the_signals_alone_entrypoint() {
+		if env::var("RUN_SIGNAL_TEST").is_err() {
+			return;
+		}
+
+		let base_path = tempfile::tempdir().unwrap();
+		let (stop_tx, stop_rx) = futures::channel::oneshot::channel();
+		let res = test_runner(base_path.path()).run_node_until_signal(|config| async move {
+			let mut task_manager = TaskManager::new(config.task_executor.clone(), None)?;
+			let ready = once_ready(&mut task_manager);
+			task_manager.spawn_handle().spawn("signal", async move {
+				if ready.await.is_ok() {
+					let _ = kill_self("-USR1");
+					tokio::time::delay_for(Duration::from_millis(500)).await;
+					let _ = stop_tx.send(());
+				}
+			});
+			Ok(task_manager)
+		}, stop_rx.map(drop));
+
+		assert!(res.is_ok(), "{:?}", res);
+		println!("SIGNAL_TEST_DONE");
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn running_marker_tells_an_unclean_shutdown() {
+		let base_path = tempfile::tempdir().unwrap();
+		let run = |stop: &str| {
+			let entrypoint = "runner::tests::running_marker_tells_an_unclean_shutdown_entrypoint";
+			let output = Command::new(env::current_exe().unwrap())
+				.env("STOP_NODE", stop)
+				.env("NODE_BASE_PATH", base_path.path())
+				.args(&["--nocapture", "--exact", entrypoint])
+				.output()
+				.unwrap();
+			(output.status.success(), String::from_utf8_lossy(&output.stdout).into_owned())
+		};
+		let chain_path = base_path.path().join("chains").join("test");
+
+		let (success, stdout) = run("crash");
+		assert!(!success, "{}", stdout);
+		assert!(stdout.contains("UNCLEAN_SHUTDOWN=false"), "{}", stdout);
+		assert!(chain_path.join(RUNNING_MARKER_FILE).exists());
+		assert!(!chain_path.join("db").join(RUNNING_MARKER_FILE).exists());
+
+		let (success, stdout) = run("terminate");
+		assert!(success, "{}", stdout);
+		assert!(stdout.contains("UNCLEAN_SHUTDOWN=true"), "{}", stdout);
+		assert!(stdout.contains("NODE_STOPPED"), "{}", stdout);
+		assert!(!chain_path.join(RUNNING_MARKER_FILE).exists());
+		assert!(!test_runner(base_path.path()).was_unclean_shutdown());
+	}
+
+	/// This is no actual test, it will be used by the `running_marker_tells_an_unclean_shutdown`
+	/// test. It runs a node, on the base path of the test, until it aborts or terminates itself
+	/// once ready.
+	#[cfg(unix)]
+	#[test]
+	fn running_marker_tells_an_unclean_shutdown_entrypoint() {
+		let (stop, base_path) = match (env::var("STOP_NODE"), env::var("NODE_BASE_PATH")) {
+			(Ok(stop), Ok(base_path)) => (stop, base_path),
+			_ => return,
+		};
+
+		let runner = test_runner(Path::new(&base_path));
+		println!("UNCLEAN_SHUTDOWN={}", runner.was_unclean_shutdown());
+		let res = runner.run_node_until_exit(|config| async move {
+			let mut task_manager = TaskManager::new(config.task_executor.clone(), None)?;
+			let ready = once_ready(&mut task_manager);
+			task_manager.spawn_handle().spawn("stop", async move {
+				if ready.await.is_ok() {
+					match stop.as_str() {
+						"crash" => std::process::abort(),
+						_ => drop(kill_self("-TERM")),
+					}
+				}
+			});
+			Ok(task_manager)
+		});
+
+		assert!(res.is_ok(), "{:?}", res);
+		println!("NODE_STOPPED");
+	}
+
+	#[test]
+	fn embedded_runner_marks_the_node_running() {
+		use std::sync::{Arc, atomic::AtomicBool};
+		use structopt::StructOpt;
+
+		let base_path = tempfile::tempdir().unwrap();
+		let marker = base_path.path().join("chains").join("test").join(RUNNING_MARKER_FILE);
+		let cmd = crate::RunCmd::from_iter(&[
+			"node", "--base-path", base_path.path().to_str().unwrap(), "--name", "test",
+		]);
+		let mut tokio_runtime = build_runtime().unwrap();
+		let runner = EmbeddedRunner::new(&TestCli, &cmd, tokio_runtime.handle().clone()).unwrap();
+		assert!(!runner.was_unclean_shutdown());
+
+		// Checked when the ready line is logged, then an essential task ends to stop the node.
+		let (ready_tx, ready_rx) = futures::channel::oneshot::channel();
+		let ready_tx = std::sync::Mutex::new(Some(ready_tx));
+		let running = Arc::new(AtomicBool::new(false));
+		let (running_marker, was_running) = (marker.clone(), running.clone());
+		let res = tokio_runtime.block_on(runner.run_node_until_exit(|config| async move {
+			let mut task_manager = TaskManager::new(config.task_executor.clone(), None)?;
+			task_manager.set_best_block(move || {
+				if let Some(ready_tx) = ready_tx.lock().unwrap().take() {
+					was_running.store(running_marker.exists(), Ordering::SeqCst);
+					let _ = ready_tx.send(());
+				}
+				"#0 (0x2a)".into()
+			});
+			task_manager.spawn_essential_handle().spawn("stop", ready_rx.map(drop));
+			Ok(task_manager)
+		}));
+
+		assert!(res.is_err(), "{:?}", res);
+		assert!(running.load(Ordering::SeqCst));
+		assert!(!marker.exists());
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn shutdown_hook_runs_on_interrupt() {
+		run_signal_test("shutdown_hook_runs_on_interrupt_entrypoint");
+	}
+
+	/// This is no actual test, it will be used by the `shutdown_hook_runs_on_interrupt` test.
+	/// It interrupts its own process.
+	#[cfg(unix)]
+	#[test]
+	fn shutdown_hook_runs_on_interrupt_entrypoint() {
+		use std::sync::{Arc, atomic::AtomicBool};
+
+		if env::var("RUN_SIGNAL_TEST").is_err() {
+			return;
+		}
+
+		let tokio_runtime = build_runtime().unwrap();
+		let hook_called = Arc::new(AtomicBool::new(false));
+		let on_shutdown = {
+			let hook_called = hook_called.clone();
+			Box::new(move || hook_called.store(true, Ordering::SeqCst)) as ShutdownHook
+		};
+
+		// The signal handlers are installed by the time the node is polled.
+		let node = async {
+			std::process::Command::new("kill")
+				.args(&["-INT", &std::process::id().to_string()])
+				.status()?;
+			future::pending::<std::result::Result<(), io::Error>>().await
+		};
+		let res = run_until_exit(
+			tokio_runtime,
+			node,
+			None,
+			|| future::ready(()),
+			ShutdownSettings {
+				on_shutdown: Some(on_shutdown),
+				..Default::default()
+			},
+		);
+
+		assert!(res.is_ok(), "{:?}", res);
+		assert!(hook_called.load(Ordering::SeqCst));
+		println!("SIGNAL_TEST_DONE");
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn node_completes_without_sigterm_handling() {
+		run_signal_test("node_completes_without_sigterm_handling_entrypoint");
+	}
+
+	/// This is no actual test, it will be used by the `node_completes_without_sigterm_handling`
+	/// test. It terminates its own process, which ignores `SIGTERM` like under some debuggers,
+	/// a disposition that handling `SIGTERM` would replace.
+	#[cfg(unix)]
+	#[test]
+	fn node_completes_without_sigterm_handling_entrypoint() {
+		if env::var("RUN_SIGNAL_TEST").is_err() {
+			return;
+		}
+
+		unsafe {
+			libc::signal(libc::SIGTERM, libc::SIG_IGN);
+		}
+		let tokio_runtime = build_runtime().unwrap();
+		let signal_set = SignalSet { handle_sigint: true, handle_sigterm: false };
+
+		// The signal handlers are installed by the time the node is polled.
+		let node = async {
+			kill_self("-TERM")?;
+			tokio::time::delay_for(Duration::from_millis(100)).await;
+			Ok::<_, io::Error>(42)
+		};
+		let res = run_until_exit(
+			tokio_runtime,
+			node,
+			None,
+			|| future::ready(()),
+			ShutdownSettings {
+				signal_set,
+				..Default::default()
+			},
+		);
+
+		// Not stopped by the signal, which would have given `None`.
+		assert_eq!(res.unwrap(), Some(42));
+		println!("SIGNAL_TEST_DONE");
+	}
+
+	/// Write the log files `names` to `dir`, from the oldest to the newest, each `size` bytes.
+	fn write_logs(dir: &Path, names: &[&str], size: usize) {
+		for name in names {
+			std::fs::write(dir.join(name), vec![b'x'; size]).unwrap();
+			thread::sleep(Duration::from_millis(20));
+		}
+	}
+
+	fn retention(
+		dir: &Path,
+		max_total_size: Option<u64>,
+		max_age: Option<Duration>,
+	) -> LogRetention {
+		LogRetention {
+			dir: dir.to_path_buf(),
+			max_total_size,
+			max_age,
+			interval: Duration::from_secs(60),
+		}
+	}
+
+	#[test]
+	fn log_retention_prunes_the_oldest_files_beyond_the_size() {
+		let dir = tempfile::tempdir().unwrap();
+		write_logs(dir.path(), &["1.log", "2.log", "3.log", "4.log"], 100);
+		std::fs::create_dir(dir.path().join("archive")).unwrap();
+
+		let retention = retention(dir.path(), Some(250), None);
+		let pruned = prune_logs(&retention, SystemTime::now()).unwrap();
+		assert_eq!(pruned, vec![dir.path().join("2.log"), dir.path().join("1.log")]);
+		assert!(dir.path().join("3.log").exists() && dir.path().join("4.log").exists());
+		assert!(dir.path().join("archive").is_dir());
+
+		// Within the policy, nothing more is pruned.
+		assert!(prune_logs(&retention, SystemTime::now()).unwrap().is_empty());
+	}
+
+	#[test]
+	fn log_retention_prunes_the_files_beyond_the_age_but_the_newest() {
+		let dir = tempfile::tempdir().unwrap();
+		write_logs(dir.path(), &["1.log", "2.log"], 10);
+
+		let retention = retention(dir.path(), None, Some(Duration::from_secs(3600)));
+		let now = SystemTime::now();
+		assert!(prune_logs(&retention, now).unwrap().is_empty());
+
+		let later = now + Duration::from_secs(7200);
+		let pruned = prune_logs(&retention, later).unwrap();
+		assert_eq!(pruned, vec![dir.path().join("1.log")]);
+		assert!(dir.path().join("2.log").exists());
+	}
+
+	#[test]
+	fn unfused_futures_can_be_run() {
+		let mut tokio_runtime = build_runtime().unwrap();
+
+		// An `async` block isn't a `FusedFuture`.
+		let node = async { Ok::<(), io::Error>(()) };
+		let terminations = Terminations::signals(&tokio_runtime, SignalSet::default()).unwrap();
+		let stopped = block_on_main(
+			&mut tokio_runtime,
+			node,
+			terminations,
+			None,
+			None,
+			None,
+			&mut ShutdownSettings::default(),
+		);
+		assert_eq!(stopped.ok(), Some(Stopped::Completed));
+
+		let res = run_until_exit(
+			tokio_runtime,
+			async { Ok::<(), io::Error>(()) },
+			None,
+			|| future::ready(()),
+			ShutdownSettings::default(),
+		);
+		assert!(res.is_ok(), "{:?}", res);
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn sigusr1_runs_diagnostics_without_stopping_the_node() {
+		run_signal_test("sigusr1_runs_diagnostics_without_stopping_the_node_entrypoint");
+	}
+
+	/// This is no actual test, it will be used by the
+	/// `sigusr1_runs_diagnostics_without_stopping_the_node` test. It sends `SIGUSR1` to its own
+	/// process.
+	#[cfg(unix)]
+	#[test]
+	fn sigusr1_runs_diagnostics_without_stopping_the_node_entrypoint() {
+		use std::sync::{Arc, atomic::AtomicBool};
+
+		if env::var("RUN_SIGNAL_TEST").is_err() {
+			return;
+		}
+
+		let mut tokio_runtime = build_runtime().unwrap();
+		let called = Arc::new(AtomicBool::new(false));
+		let diagnostics = {
+			let called = called.clone();
+			Box::new(move || called.store(true, Ordering::SeqCst)) as DiagnosticsCallback
+		};
+
+		// The node only completes once the callback ran, so it wasn't stopped by the signal.
+		let node = async {
+			std::process::Command::new("kill")
+				.args(&["-USR1", &std::process::id().to_string()])
+				.status()?;
+			while !called.load(Ordering::SeqCst) {
+				tokio::time::delay_for(Duration::from_millis(10)).await;
+			}
+			Ok::<(), io::Error>(())
+		};
+		let terminations = Terminations::signals(&tokio_runtime, SignalSet::default()).unwrap();
+		let stopped = block_on_main(
+			&mut tokio_runtime,
+			node,
+			terminations,
+			None,
+			Some(&diagnostics),
+			None,
+			&mut ShutdownSettings::default(),
+		);
+
+		assert_eq!(stopped.ok(), Some(Stopped::Completed));
+		println!("SIGNAL_TEST_DONE");
+	}
+
+	#[test]
+	fn database_open_in_another_process_fails_with_a_clear_error() {
+		use std::io::{BufRead, BufReader};
+		use std::process::Stdio;
+
+		let base_path = tempfile::tempdir().unwrap();
+		let database_path = rocksdb_path(test_runner(base_path.path()).config()).unwrap();
+		let mut holder = Command::new(env::current_exe().unwrap())
+			.env("HOLD_DATABASE", &database_path)
+			.args(&["--nocapture", "--exact", "runner::tests::hold_database_entrypoint"])
+			.stdin(Stdio::piped())
+			.stdout(Stdio::piped())
+			.spawn()
+			.unwrap();
+		let mut output = BufReader::new(holder.stdout.take().unwrap()).lines();
+		assert!(output.by_ref().any(|line| line.unwrap().contains("DATABASE_HELD")));
+
+		let locked = |res: Result<()>| {
+			matches!(res, Err(crate::Error::DatabaseLocked(ref locked)) if locked == &database_path)
+		};
+		assert!(locked(ensure_database_unlocked(Some(&database_path))));
+		let opened = "The database is opened while another process holds it";
+		assert!(locked(test_runner(base_path.path()).async_run(|_| {
+			Err::<(future::Ready<Result<()>>, TaskManager), _>(opened.into())
+		})));
+		assert!(locked(test_runner(base_path.path()).run_node_until_exit(|_| {
+			future::ready(Err(sc_service::Error::Other(opened.into())))
+		})));
+
+		drop(holder.stdin.take());
+		output.for_each(drop);
+		assert!(holder.wait().unwrap().success());
+		assert!(ensure_database_unlocked(Some(&database_path)).is_ok());
+	}
+
+	/// This is no actual test, it will be used by the
+	/// `database_open_in_another_process_fails_with_a_clear_error` test. It holds the database
+	/// open until its stdin is closed.
+	#[test]
+	fn hold_database_entrypoint() {
+		use std::io::Read;
+
+		let path = match env::var("HOLD_DATABASE") {
+			Ok(path) => path,
+			Err(_) => return,
+		};
+		let config = kvdb_rocksdb::DatabaseConfig::with_columns(1);
+		let _db = kvdb_rocksdb::Database::open(&config, &path).unwrap();
+		println!("DATABASE_HELD");
+		let _ = io::stdin().read_to_end(&mut Vec::new());
+	}
+
+	#[test]
+	fn requested_restart_is_returned_to_the_caller() {
+		let base_path = tempfile::tempdir().unwrap();
+		let res = test_runner(base_path.path()).run_node_until_exit(|config| async move {
+			let task_manager = TaskManager::new(config.task_executor.clone(), None)?;
+			task_manager.restart_handle().request_restart();
+			Ok(task_manager)
+		});
+
+		match res {
+			Err(crate::Error::Service(ref e)) =>
+				assert_eq!(default_exit_code(e), ExitCode(RESTART_EXIT_CODE)),
+			res => panic!("The node didn't stop for a restart: {:?}", res),
+		}
+	}
+
+	#[test]
+	fn full_disk_is_returned_to_the_caller() {
+		let base_path = tempfile::tempdir().unwrap();
+		let mut runner = test_runner(base_path.path());
+		runner.set_disk_usage_limit(Some(DiskUsageLimit {
+			high_water: 0.0,
+			low_water: None,
+			interval: Duration::from_millis(10),
+		}));
+		let res = runner.run_node_until_exit(|config| async move {
+			TaskManager::new(config.task_executor.clone(), None).map_err(Into::into)
+		});
+
+		match res {
+			Err(crate::Error::Service(ref e)) =>
+				assert_eq!(default_exit_code(e), ExitCode(DISK_USAGE_EXIT_CODE)),
+			res => panic!("The node didn't stop for its disk usage: {:?}", res),
+		}
+	}
+
+	#[test]
+	fn node_ready_line_is_logged_once() {
+		let executable = env::current_exe().unwrap();
+		let output = Command::new(executable)
+			.env("ENABLE_LOGGING", "1")
+			.args(&["--nocapture", "node_ready_line_is_logged_once_entrypoint"])
+			.output()
+			.unwrap();
+
+		let output = String::from_utf8(output.stderr).unwrap();
+		assert_eq!(output.matches("NODE_READY name=\"test\"").count(), 1, "{}", output);
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn node_ready_line_gives_the_best_block() {
+		let logs = run_signal_test("node_ready_line_gives_the_best_block_entrypoint");
+
+		let lines = logs.lines().filter(|line| line.contains("NODE_READY")).collect::<Vec<_>>();
+		assert_eq!(lines.len(), 1, "{}", logs);
+		assert!(
+			lines[0].contains(
+				"NODE_READY name=\"test\" role=\"FULL\" chain=\"Test\" best=\"#0 (0x2a)\"",
+			),
+			"{}",
+			logs,
+		);
+	}
+
+	/// This is no actual test, it will be used by the `node_ready_line_gives_the_best_block` test.
+	/// It runs a node until it terminates itself, once ready.
+	#[cfg(unix)]
+	#[test]
+	fn node_ready_line_gives_the_best_block_entrypoint() {
+		if env::var("RUN_SIGNAL_TEST").is_err() {
+			return;
+		}
+
+		let pattern = format!("{}=info", NODE_READY_TARGET);
+		crate::init_logger(
+			&pattern,
+			Default::default(),
+			Default::default(),
+			false,
+			None,
+			None,
+		).unwrap();
+		let base_path = tempfile::tempdir().unwrap();
+		let res = test_runner(base_path.path()).run_node_until_exit(|config| async move {
+			let mut task_manager = TaskManager::new(config.task_executor.clone(), None)?;
+			let ready = once_ready(&mut task_manager);
+			task_manager.spawn_handle().spawn("terminate", async {
+				if ready.await.is_ok() {
+					let _ = kill_self("-TERM");
+				}
+			});
+			Ok(task_manager)
+		});
+
+		assert!(res.is_ok(), "{:?}", res);
+		println!("SIGNAL_TEST_DONE");
+	}
+
+	/// This is no actual test, it will be used by the `node_ready_line_is_logged_once` test.
+	/// It logs the line of a node that is then restarted.
+	#[test]
+	fn node_ready_line_is_logged_once_entrypoint() {
+		if env::var("ENABLE_LOGGING").is_ok() {
+			let pattern = format!("{}=info", NODE_READY_TARGET);
+			crate::init_logger(&pattern, Default::default(), Default::default(), false, None, None)
+				.unwrap();
+
+			let tokio_runtime = build_runtime().unwrap();
+			let task_executor = task_executor(tokio_runtime.handle().clone(), None);
+			let task_manager = TaskManager::new(task_executor, None).unwrap();
+			let mut ready = Some("NODE_READY name=\"test\"".to_owned());
+			log_node_ready(&mut ready, &task_manager);
+			log_node_ready(&mut ready, &task_manager);
+		}
+	}
+
+	#[test]
+	fn wasm_only_node_has_no_native_runtime() {
+		struct WasmOnlyCli;
+
+		impl SubstrateCli for WasmOnlyCli {
+			fn impl_name() -> String { "Wasm only".into() }
+			fn impl_version() -> String { "0.0.0".into() }
+			fn description() -> String { String::new() }
+			fn author() -> String { String::new() }
+			fn support_url() -> String { String::new() }
+			fn copyright_start_year() -> i32 { 2020 }
+			fn load_spec(&self, _: &str) -> std::result::Result<Box<dyn ChainSpec>, String> {
+				Err("No chain spec".into())
+			}
+			fn native_runtime_version(_: &dyn ChainSpec) -> Option<&'static crate::RuntimeVersion> {
+				None
+			}
+		}
+
+		let chain_spec = sc_service::GenericChainSpec::<()>::from_genesis(
+			"Test",
+			"test",
+			sc_service::ChainType::Development,
+			|| (),
+			Vec::new(),
+			None,
+			None,
+			None,
+			None,
+		);
+
+		assert_eq!(native_runtime::<WasmOnlyCli>(&chain_spec), "<none, wasm-only>");
+	}
+
+	#[test]
+	fn output_of_the_command_is_returned() {
+		let res = run_until_exit(
+			build_runtime().unwrap(),
+			async { Ok::<_, io::Error>(6 * 7) },
+			None,
+			|| future::ready(()),
+			ShutdownSettings::default(),
+		);
+		assert_eq!(res.unwrap(), Some(42));
+
+		let terminations = Terminations::Trigger(Some(Box::pin(future::ready(()))));
+		let res = run_cancellable(
+			&mut build_runtime().unwrap(),
+			future::pending::<std::result::Result<u32, io::Error>>(),
+			terminations,
+			None,
+			&mut ShutdownSettings::default(),
+		);
+		assert_eq!(res, Ok(None));
+	}
+
+	#[test]
+	fn cancelled_command_returns_within_the_grace_period() {
+		use std::sync::{Arc, atomic::AtomicBool};
+
+		let mut tokio_runtime = build_runtime().unwrap();
+		let (cancellation, token) = Cancellation::new(Duration::from_secs(5));
+		let checkpointed = Arc::new(AtomicBool::new(false));
+		let command = {
+			let checkpointed = checkpointed.clone();
+			async move {
+				token.cancelled().await;
+				assert!(token.is_cancelled());
+				tokio::time::delay_for(Duration::from_millis(50)).await;
+				checkpointed.store(true, Ordering::SeqCst);
+				Ok::<(), io::Error>(())
+			}
+		};
+
+		let terminations = Terminations::Trigger(Some(Box::pin(future::ready(()))));
+		let res = run_cancellable(
+			&mut tokio_runtime,
+			command,
+			terminations,
+			None,
+			&mut ShutdownSettings {
+				cancellation: Some(cancellation),
+				..Default::default()
+			},
+		);
+
+		assert!(res.is_ok());
+		assert!(checkpointed.load(Ordering::SeqCst));
+	}
+
+	#[test]
+	fn trigger_stops_the_node_gracefully() {
+		use std::sync::{Arc, atomic::AtomicBool};
+
+		struct SetOnDrop(Arc<AtomicBool>);
+
+		impl Drop for SetOnDrop {
+			fn drop(&mut self) {
+				self.0.store(true, Ordering::SeqCst);
+			}
+		}
+
+		let mut tokio_runtime = build_runtime().unwrap();
+		let task_executor = task_executor(tokio_runtime.handle().clone(), None);
+		let mut task_manager = TaskManager::new(task_executor, None).unwrap();
+		let dropped = Arc::new(AtomicBool::new(false));
+		let state = SetOnDrop(dropped.clone());
+		task_manager.spawn_essential_handle().spawn("dummy", async move {
+			let _state = state;
+			future::pending::<()>().await
+		});
+
+		let (trigger_tx, trigger_rx) = futures::channel::oneshot::channel::<()>();
+		tokio_runtime.spawn(async move {
+			tokio::time::delay_for(Duration::from_millis(50)).await;
+			let _ = trigger_tx.send(());
+		});
+		let terminations = Terminations::Trigger(Some(Box::pin(trigger_rx.map(drop))));
+		let stopped = block_on_main(
+			&mut tokio_runtime,
+			task_manager.future(),
+			terminations,
+			None,
+			None,
+			None,
+			&mut ShutdownSettings::default(),
+		);
+		assert_eq!(stopped.ok(), Some(Stopped::Signal));
+		assert!(!dropped.load(Ordering::SeqCst));
+
+		let running_tasks = task_manager.running_tasks();
+		assert!(shutdown_within(
+			tokio_runtime,
+			task_manager.clean_shutdown(),
+			Some(running_tasks),
+			None,
+			None,
+		));
+		assert!(dropped.load(Ordering::SeqCst));
+	}
+
+	#[test]
+	fn tasks_are_dropped_before_the_runtime() {
+		use std::sync::{Arc, atomic::AtomicBool};
+
+		/// Spawns a task through the task manager when dropped, which must not panic.
+		struct SpawnOnDrop(SpawnTaskHandle, Arc<AtomicBool>);
+
+		impl Drop for SpawnOnDrop {
+			fn drop(&mut self) {
+				self.0.spawn("late", async {});
+				self.1.store(tokio::runtime::Handle::try_current().is_ok(), Ordering::SeqCst);
+			}
+		}
+
+		let tokio_runtime = build_runtime().unwrap();
+		let (task_panics_tx, mut task_panics) = tracing_unbounded("mpsc_task_panics");
+		let task_executor = task_executor(tokio_runtime.handle().clone(), Some(task_panics_tx));
+		let task_manager = TaskManager::new(task_executor, None).unwrap();
+		let dropped_on_runtime = Arc::new(AtomicBool::new(false));
+		let state = SpawnOnDrop(task_manager.spawn_handle(), dropped_on_runtime.clone());
+		task_manager.spawn_handle().spawn("holder", async move {
+			let _state = state;
+			future::pending::<()>().await
+		});
+
+		let running_tasks = task_manager.running_tasks();
+		let res = run_until_exit(
+			tokio_runtime,
+			future::ready(Ok::<(), io::Error>(())),
+			None,
+			|| task_manager.clean_shutdown(),
+			ShutdownSettings {
+				running_tasks: Some(running_tasks),
+				..Default::default()
+			},
+		);
+
+		assert!(res.is_ok(), "{:?}", res);
+		// The task was dropped by the task manager, while the runtime was still there.
+		assert!(dropped_on_runtime.load(Ordering::SeqCst));
+		assert!(task_panics.try_next().ok().flatten().is_none());
+	}
+
+	#[test]
+	fn node_completing_on_its_own_is_an_error() {
+		let mut tokio_runtime = build_runtime().unwrap();
+
+		let node = future::ready(Ok::<(), io::Error>(()));
+		let terminations = Terminations::signals(&tokio_runtime, SignalSet::default()).unwrap();
+		let stopped = block_on_main(
+			&mut tokio_runtime,
+			node,
+			terminations,
+			None,
+			None,
+			None,
+			&mut ShutdownSettings::default(),
+		);
+		assert_eq!(stopped.as_ref().ok(), Some(&Stopped::Completed));
+		match node_result(stopped) {
+			Err(e) => assert!(
+				matches!(
+					e.downcast_ref::<sc_service::Error>(),
+					Some(sc_service::Error::EssentialTaskFailed),
+				),
+				"{}",
+				e,
+			),
+			Ok(()) => panic!("the node completing on its own isn't an error"),
+		}
+
+		assert!(node_result(Ok(Stopped::Signal)).is_ok());
+	}
+
+	/// Run the `entrypoint` test in a process of its own with the panic handler of
+	/// [`CliConfiguration::init`] set, which can't be unset once set, and check that it completed.
+	fn run_panic_handler_test(entrypoint: &str) {
+		let executable = env::current_exe().unwrap();
+		let output = Command::new(executable)
+			.env("RUN_PANIC_HANDLER_TEST", "1")
+			.args(&["--nocapture", "--exact", &format!("runner::tests::{}", entrypoint)])
+			.output()
+			.unwrap();
+
+		let stdout = String::from_utf8_lossy(&output.stdout);
+		assert!(output.status.success(), "{}{}", stdout, String::from_utf8_lossy(&output.stderr));
+		assert!(stdout.contains("PANIC_HANDLER_TEST_DONE"), "{}", stdout);
+	}
+
+	#[test]
+	fn task_panic_stops_the_node_with_the_panic_handler() {
+		run_panic_handler_test("task_panic_stops_the_node_with_the_panic_handler_entrypoint");
+	}
+
+	/// This is no actual test, it will be used by the
+	/// `task_panic_stops_the_node_with_the_panic_handler` test.
+	#[test]
+	fn task_panic_stops_the_node_with_the_panic_handler_entrypoint() {
+		if env::var("RUN_PANIC_HANDLER_TEST").is_err() {
+			return;
+		}
+
+		sp_panic_handler::set("https://example.com/issues", "0.0.0");
+		task_panic_stops_the_node();
+		println!("PANIC_HANDLER_TEST_DONE");
+	}
+
+	#[test]
+	fn node_panic_is_caught_with_the_panic_handler() {
+		run_panic_handler_test("node_panic_is_caught_with_the_panic_handler_entrypoint");
+	}
+
+	/// This is no actual test, it will be used by the `node_panic_is_caught_with_the_panic_handler`
+	/// test.
+	#[test]
+	fn node_panic_is_caught_with_the_panic_handler_entrypoint() {
+		if env::var("RUN_PANIC_HANDLER_TEST").is_err() {
+			return;
+		}
+
+		sp_panic_handler::set("https://example.com/issues", "0.0.0");
+		let node = Box::pin(async { panic!("node failure") });
+		match futures::executor::block_on(catch_node_panic(node)) {
+			Err(e) => assert!(e.to_string().contains("node failure"), "{}", e),
+			Ok(()) => panic!("the panic of the node wasn't caught"),
+		}
+		println!("PANIC_HANDLER_TEST_DONE");
+	}
+
+	#[test]
+	fn task_panic_stops_the_node() {
+		let tokio_runtime = build_runtime().unwrap();
+		let (task_panics_tx, mut task_panics) = tracing_unbounded("mpsc_task_panics");
+		let task_executor = task_executor(tokio_runtime.handle().clone(), Some(task_panics_tx));
+
+		let _ = task_executor.spawn(Box::pin(async {
+			panic!("task failure");
+		}), TaskType::Async);
+
+		let node = future::pending::<std::result::Result<(), io::Error>>();
+		let res = run_until_exit(
+			tokio_runtime,
+			node,
+			Some(&mut task_panics),
+			|| future::ready(()),
+			ShutdownSettings::default(),
+		);
+
+		match res {
+			Err(e) => assert!(e.to_string().contains("task failure"), "{}", e),
+			Ok(_) => panic!("the node kept running after a task panicked"),
+		}
+	}
+}
@@ -0,0 +1,89 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2020 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Notifications of the service manager, for the nodes run as systemd units of `Type=notify`.
+//!
+//! Only sent with the `sd-notify` feature on unix, when systemd passes a `NOTIFY_SOCKET` to the
+//! node; this is a no-op otherwise.
+
+use std::time::Duration;
+
+/// The node is ready, i.e. it has been initialized.
+pub(crate) const READY: &str = "READY=1";
+/// The node is shutting down.
+pub(crate) const STOPPING: &str = "STOPPING=1";
+/// The node is alive, see [`watchdog_interval`].
+const WATCHDOG: &str = "WATCHDOG=1";
+
+/// Send `state` to the service manager.
+#[cfg(all(unix, feature = "sd-notify"))]
+pub(crate) fn notify(state: &str) {
+	use std::os::unix::net::UnixDatagram;
+
+	let socket = match std::env::var_os("NOTIFY_SOCKET") {
+		Some(socket) => socket,
+		None => return,
+	};
+	// Abstract sockets, starting with `@`, can't be addressed by the standard library.
+	if socket.to_string_lossy().starts_with('@') {
+		log::debug!("Abstract NOTIFY_SOCKET {:?} isn't supported", socket);
+		return;
+	}
+
+	let sent = UnixDatagram::unbound().and_then(|sender| sender.send_to(state.as_bytes(), &socket));
+	if let Err(e) = sent {
+		log::warn!("Failed to notify {} to {:?}: {}", state, socket, e);
+	}
+}
+
+#[cfg(not(all(unix, feature = "sd-notify")))]
+pub(crate) fn notify(_state: &str) {}
+
+/// How often the service manager expects to be notified that the node is alive, if it does.
+///
+/// This is half of the `WATCHDOG_USEC` set by systemd, leaving room for the node being late.
+#[cfg(all(unix, feature = "sd-notify"))]
+pub(crate) fn watchdog_interval() -> Option<Duration> {
+	std::env::var_os("NOTIFY_SOCKET")?;
+	// The watchdog isn't meant for this process when it was set up for another one.
+	if let Ok(pid) = std::env::var("WATCHDOG_PID") {
+		if pid.parse::<u32>().ok() != Some(std::process::id()) {
+			return None;
+		}
+	}
+
+	let usec = std::env::var("WATCHDOG_USEC").ok()?.parse::<u64>().ok()?;
+	match usec {
+		0 => None,
+		usec => Some(Duration::from_micros(usec) / 2),
+	}
+}
+
+#[cfg(not(all(unix, feature = "sd-notify")))]
+pub(crate) fn watchdog_interval() -> Option<Duration> {
+	None
+}
+
+/// Notify the service manager that the node is alive every `interval`, forever.
+pub(crate) async fn watchdog(interval: Duration) {
+	let mut interval = tokio::time::interval(interval);
+	loop {
+		interval.tick().await;
+		notify(WATCHDOG);
+	}
+}
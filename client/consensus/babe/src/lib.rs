@@ -98,7 +98,9 @@ use sp_consensus::{
 };
 use sp_consensus_babe::inherents::BabeInherentData;
 use sp_timestamp::{TimestampInherentData, InherentType as TimestampInherent};
-use sp_consensus::import_queue::{Verifier, BasicQueue, DefaultImportQueue, CacheKeyId};
+use sp_consensus::import_queue::{
+	Verifier, BasicQueue, DefaultImportQueue, CacheKeyId, FutureBlockPolicy,
+};
 use sc_client_api::{
 	backend::AuxStore,
 	BlockchainEvents, ProvideUncles,
@@ -112,7 +114,7 @@ use log::{debug, info, log, trace, warn};
 use prometheus_endpoint::Registry;
 use sc_consensus_slots::{
 	SlotInfo, SlotCompatible, StorageChanges, CheckedHeader, check_equivocation,
-	BackoffAuthoringBlocksStrategy,
+	BackoffAuthoringBlocksStrategy, FutureBlocks,
 };
 use sc_consensus_epochs::{
 	descendent_query, SharedEpochChanges, EpochChangesFor, Epoch as EpochT, ViableEpochDescriptor,
@@ -835,6 +837,7 @@ pub struct BabeVerifier<Block: BlockT, Client, SelectChain, CAW> {
 	epoch_changes: SharedEpochChanges<Block, Epoch>,
 	time_source: TimeSource,
 	can_author_with: CAW,
+	future_blocks: FutureBlocks,
 }
 
 impl<Block, Client, SelectChain, CAW> BabeVerifier<Block, Client, SelectChain, CAW>
@@ -1016,16 +1019,29 @@ where
 			|slot| Epoch::genesis(&self.config, slot)
 		).ok_or_else(|| Error::<Block>::FetchEpoch(parent_hash))?;
 
-		// We add one to the current slot to allow for some small drift.
-		// FIXME #1019 in the future, alter this queue to allow deferring of headers
+		// The future block policy allows for some drift of the clocks, by default one slot.
 		let v_params = verification::VerificationParams {
 			header: header.clone(),
-			pre_digest: Some(pre_digest),
-			slot_now: slot_now + 1,
+			pre_digest: Some(pre_digest.clone()),
+			slot_now: self.future_blocks.max_slot(slot_now),
 			epoch: viable_epoch.as_ref(),
 		};
 
-		match verification::check_header::<Block>(v_params)? {
+		let mut checked_header = verification::check_header::<Block>(v_params)?;
+		if let CheckedHeader::Deferred(_, slot_number) = checked_header {
+			if self.future_blocks.wait_for(slot_number, slot_now, self.config.slot_duration) {
+				let v_params = verification::VerificationParams {
+					header: header.clone(),
+					pre_digest: Some(pre_digest),
+					slot_now: slot_number,
+					epoch: viable_epoch.as_ref(),
+				};
+
+				checked_header = verification::check_header::<Block>(v_params)?;
+			}
+		}
+
+		match checked_header {
 			CheckedHeader::Checked(pre_header, verified_info) => {
 				let babe_pre_digest = verified_info.pre_digest.as_babe_pre_digest()
 					.expect("check_header always returns a pre-digest digest item; qed");
@@ -1488,6 +1504,7 @@ pub fn import_queue<Block: BlockT, Client, SelectChain, Inner, CAW>(
 	spawner: &impl sp_core::traits::SpawnNamed,
 	registry: Option<&Registry>,
 	can_author_with: CAW,
+	future_block_policy: FutureBlockPolicy,
 ) -> ClientResult<DefaultImportQueue<Block, Client>> where
 	Inner: BlockImport<Block, Error = ConsensusError, Transaction = sp_api::TransactionFor<Client, Block>>
 		+ Send + Sync + 'static,
@@ -1507,6 +1524,7 @@ pub fn import_queue<Block: BlockT, Client, SelectChain, Inner, CAW>(
 		epoch_changes: babe_link.epoch_changes,
 		time_source: babe_link.time_source,
 		can_author_with,
+		future_blocks: FutureBlocks::new(future_block_policy, registry),
 	};
 
 	Ok(BasicQueue::new(
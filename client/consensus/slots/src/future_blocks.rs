@@ -0,0 +1,112 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Handling of the blocks whose slot is ahead of the local clock during verification.
+
+use std::time::Duration;
+use log::debug;
+use prometheus_endpoint::{register, CounterVec, Opts, PrometheusError, Registry, U64};
+use sp_consensus::import_queue::{FutureBlockAction, FutureBlockPolicy};
+
+/// Applies a [`FutureBlockPolicy`] in a slot-based verifier.
+pub struct FutureBlocks {
+	policy: FutureBlockPolicy,
+	metrics: Option<Metrics>,
+}
+
+impl FutureBlocks {
+	/// Create a new `FutureBlocks`, registering its metrics in `registry` if any.
+	pub fn new(policy: FutureBlockPolicy, registry: Option<&Registry>) -> Self {
+		let metrics = registry.and_then(|registry| match Metrics::register(registry) {
+			Ok(metrics) => Some(metrics),
+			Err(err) => {
+				debug!(target: "slots", "Failed to register future blocks metrics: {:?}", err);
+				None
+			}
+		});
+
+		FutureBlocks { policy, metrics }
+	}
+
+	/// The highest slot of the blocks that are verified right away when the current slot is
+	/// `slot_now`.
+	pub fn max_slot(&self, slot_now: u64) -> u64 {
+		slot_now.saturating_add(self.policy.tolerance)
+	}
+
+	/// Handle a block at `slot`, which is beyond [`FutureBlocks::max_slot`] of `slot_now`.
+	///
+	/// Blocks the current thread until the block is within the tolerance if the policy queues it.
+	/// Returns whether the block can now be verified with `slot` as the current slot, or must be
+	/// rejected.
+	pub fn wait_for(&self, slot: u64, slot_now: u64, slot_duration: u64) -> bool {
+		let ahead = slot - self.max_slot(slot_now);
+
+		match self.policy.action {
+			FutureBlockAction::Queue { max_wait } if ahead <= max_wait => {
+				self.report("queued");
+
+				let within_tolerance_at = Duration::from_millis(
+					slot.saturating_sub(self.policy.tolerance).saturating_mul(slot_duration),
+				);
+				let now = crate::slots::duration_now();
+				if within_tolerance_at > now {
+					debug!(
+						target: "slots",
+						"Waiting {:?} for block at slot {} from the future.",
+						within_tolerance_at - now,
+						slot,
+					);
+					std::thread::sleep(within_tolerance_at - now);
+				}
+
+				true
+			},
+			FutureBlockAction::Queue { .. } | FutureBlockAction::Reject => {
+				self.report("rejected");
+				false
+			},
+		}
+	}
+
+	fn report(&self, action: &str) {
+		if let Some(metrics) = &self.metrics {
+			metrics.future_blocks.with_label_values(&[action]).inc();
+		}
+	}
+}
+
+/// Prometheus metrics for [`FutureBlocks`].
+struct Metrics {
+	future_blocks: CounterVec<U64>,
+}
+
+impl Metrics {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			future_blocks: register(
+				CounterVec::new(
+					Opts::new(
+						"import_queue_future_blocks_total",
+						"Number of blocks from the future received by the import queue.",
+					),
+					&["action"],
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
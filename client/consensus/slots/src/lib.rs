@@ -25,10 +25,12 @@
 
 mod slots;
 mod aux_schema;
+mod future_blocks;
 
 pub use slots::SlotInfo;
 use slots::Slots;
 pub use aux_schema::{check_equivocation, MAX_SLOT_CAPACITY, PRUNING_BOUND};
+pub use future_blocks::FutureBlocks;
 
 use std::{fmt::Debug, ops::Deref, pin::Pin, sync::Arc, time::{Instant, Duration}};
 use codec::{Decode, Encode};
@@ -438,6 +440,10 @@ where
 			};
 
 			if let Err(err) = can_author_with.can_author_with(&BlockId::Hash(chain_head.hash())) {
+				if err == sp_consensus::AUTHORING_PAUSED {
+					debug!(target: "slots", "Skipping slot {}: {}", slot_num, err);
+					return Either::Right(future::ready(Ok(())));
+				}
 				warn!(
 					target: "slots",
 					"Unable to author block in slot {},. `can_author_with` returned: {} \
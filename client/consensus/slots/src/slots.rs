@@ -30,11 +30,12 @@ use futures_timer::Delay;
 pub fn duration_now() -> Duration {
 	use std::time::SystemTime;
 	let now = SystemTime::now();
-	now.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_else(|e| panic!(
+	let since_epoch = now.duration_since(SystemTime::UNIX_EPOCH).unwrap_or_else(|e| panic!(
 		"Current time {:?} is before unix epoch. Something is wrong: {:?}",
 		now,
 		e,
-	))
+	));
+	sp_timestamp::with_clock_offset(since_epoch)
 }
 
 /// Returns the duration until the next slot, based on current duration since
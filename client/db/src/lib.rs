@@ -281,6 +281,17 @@ pub enum DatabaseSettingsSrc {
 		cache_size: usize,
 	},
 
+	/// Load a RocksDB database from a given path as a secondary instance, which never writes to
+	/// it. Meant to read the database of another running node.
+	RocksDbSecondary {
+		/// Path to the database.
+		path: PathBuf,
+		/// Path where the secondary instance keeps its own logs.
+		secondary_path: PathBuf,
+		/// Cache size in MiB.
+		cache_size: usize,
+	},
+
 	/// Load a ParityDb database from a given path.
 	ParityDb {
 		/// Path to the database.
@@ -292,10 +303,17 @@ pub enum DatabaseSettingsSrc {
 }
 
 impl DatabaseSettingsSrc {
+	/// Create a new empty in-memory database, whose content is lost once it is dropped.
+	pub fn in_memory() -> Self {
+		let db = kvdb_memorydb::create(crate::utils::NUM_COLUMNS);
+		DatabaseSettingsSrc::Custom(sp_database::as_database(db))
+	}
+
 	/// Return dabase path for databases that are on the disk.
 	pub fn path(&self) -> Option<&Path> {
 		match self {
 			DatabaseSettingsSrc::RocksDb { path, .. } => Some(path.as_path()),
+			DatabaseSettingsSrc::RocksDbSecondary { path, .. } => Some(path.as_path()),
 			DatabaseSettingsSrc::ParityDb { path, .. } => Some(path.as_path()),
 			DatabaseSettingsSrc::Custom(_) => None,
 		}
@@ -313,6 +331,7 @@ impl std::fmt::Display for DatabaseSettingsSrc {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		let name = match self {
 			DatabaseSettingsSrc::RocksDb { .. } => "RocksDb",
+			DatabaseSettingsSrc::RocksDbSecondary { .. } => "RocksDb (secondary)",
 			DatabaseSettingsSrc::ParityDb { .. } => "ParityDb",
 			DatabaseSettingsSrc::Custom(_) => "Custom",
 		};
@@ -320,6 +339,23 @@ impl std::fmt::Display for DatabaseSettingsSrc {
 	}
 }
 
+/// Read the hash of the genesis block stored in the full node database found at `source`,
+/// without opening a backend on it.
+///
+/// Returns `None` if the database doesn't contain any block yet.
+pub fn read_genesis_hash<Block: BlockT>(
+	source: DatabaseSettingsSrc,
+) -> ClientResult<Option<Block::Hash>> {
+	let settings = DatabaseSettings {
+		state_cache_size: 0,
+		state_cache_child_ratio: None,
+		pruning: PruningMode::ArchiveAll,
+		source,
+	};
+	let db = utils::open_database::<Block>(&settings, DatabaseType::Full)?;
+	utils::read_genesis_hash(&*db)
+}
+
 pub(crate) mod columns {
 	pub const META: u32 = crate::utils::COLUMN_META;
 	pub const STATE: u32 = 1;
@@ -33,17 +33,24 @@ const CURRENT_VERSION: u32 = 1;
 pub fn upgrade_db<Block: BlockT>(db_path: &Path, _db_type: DatabaseType) -> sp_blockchain::Result<()> {
 	let is_empty = db_path.read_dir().map_or(true, |mut d| d.next().is_none());
 	if !is_empty {
-		let db_version = current_version(db_path)?;
-		match db_version {
-			0 => Err(sp_blockchain::Error::Backend(format!("Unsupported database version: {}", db_version)))?,
-			1 => (),
-			_ => Err(sp_blockchain::Error::Backend(format!("Future database version: {}", db_version)))?,
-		}
+		check_db_version(db_path)?;
 	}
 
 	update_version(db_path)
 }
 
+/// Check that the database at given path has the current version, without modifying it.
+pub fn check_db_version(db_path: &Path) -> sp_blockchain::Result<()> {
+	let db_version = current_version(db_path)?;
+	match db_version {
+		0 => Err(sp_blockchain::Error::Backend(format!("Unsupported database version: {}", db_version)))?,
+		1 => (),
+		_ => Err(sp_blockchain::Error::Backend(format!("Future database version: {}", db_version)))?,
+	}
+
+	Ok(())
+}
+
 
 /// Reads current database version from the file at given path.
 /// If the file does not exist returns 0.
@@ -226,53 +226,17 @@ pub fn open_database<Block: BlockT>(
 			crate::upgrade::upgrade_db::<Block>(&path, db_type)?;
 
 			// and now open database assuming that it has the latest version
-			let mut db_config = kvdb_rocksdb::DatabaseConfig::with_columns(NUM_COLUMNS);
-			let path = path.to_str()
-				.ok_or_else(|| sp_blockchain::Error::Backend("Invalid database path".into()))?;
-
-			let mut memory_budget = std::collections::HashMap::new();
-			match db_type {
-				DatabaseType::Full => {
-					let state_col_budget = (*cache_size as f64 * 0.9) as usize;
-					let other_col_budget = (cache_size - state_col_budget) / (NUM_COLUMNS as usize - 1);
-
-					for i in 0..NUM_COLUMNS {
-						if i == crate::columns::STATE {
-							memory_budget.insert(i, state_col_budget);
-						} else {
-							memory_budget.insert(i, other_col_budget);
-						}
-					}
-					log::trace!(
-						target: "db",
-						"Open RocksDB database at {}, state column budget: {} MiB, others({}) column cache: {} MiB",
-						path,
-						state_col_budget,
-						NUM_COLUMNS,
-						other_col_budget,
-					);
-				},
-				DatabaseType::Light => {
-					let col_budget = cache_size / (NUM_COLUMNS as usize);
-					for i in 0..NUM_COLUMNS {
-						memory_budget.insert(i, col_budget);
-					}
-					log::trace!(
-						target: "db",
-						"Open RocksDB light database at {}, column cache: {} MiB",
-						path,
-						col_budget,
-					);
-				}
-			}
-			db_config.memory_budget = memory_budget;
+			open_kvdb_rocksdb(path, *cache_size, None, db_type)?
+		},
+		#[cfg(any(feature = "with-kvdb-rocksdb", test))]
+		DatabaseSettingsSrc::RocksDbSecondary { path, secondary_path, cache_size } => {
+			// the database is upgraded by the node owning it, never by a secondary instance
+			crate::upgrade::check_db_version(&path)?;
 
-			let db = kvdb_rocksdb::Database::open(&db_config, &path)
-				.map_err(|err| sp_blockchain::Error::Backend(format!("{}", err)))?;
-			sp_database::as_database(db)
+			open_kvdb_rocksdb(path, *cache_size, Some(secondary_path.as_path()), db_type)?
 		},
 		#[cfg(not(any(feature = "with-kvdb-rocksdb", test)))]
-		DatabaseSettingsSrc::RocksDb { .. } => {
+		DatabaseSettingsSrc::RocksDb { .. } | DatabaseSettingsSrc::RocksDbSecondary { .. } => {
 			return Err(db_open_error("with-kvdb-rocksdb"));
 		},
 		#[cfg(feature = "with-parity-db")]
@@ -292,6 +256,67 @@ pub fn open_database<Block: BlockT>(
 	Ok(db)
 }
 
+/// Open the RocksDB database at `path`, as a secondary instance keeping its logs in
+/// `secondary_path` if any.
+#[cfg(any(feature = "with-kvdb-rocksdb", test))]
+fn open_kvdb_rocksdb(
+	path: &std::path::Path,
+	cache_size: usize,
+	secondary_path: Option<&std::path::Path>,
+	db_type: DatabaseType,
+) -> sp_blockchain::Result<Arc<dyn Database<DbHash>>> {
+	let invalid_path = || sp_blockchain::Error::Backend("Invalid database path".into());
+
+	let mut db_config = kvdb_rocksdb::DatabaseConfig::with_columns(NUM_COLUMNS);
+	let path = path.to_str().ok_or_else(invalid_path)?;
+
+	let mut memory_budget = std::collections::HashMap::new();
+	match db_type {
+		DatabaseType::Full => {
+			let state_col_budget = (cache_size as f64 * 0.9) as usize;
+			let other_col_budget = (cache_size - state_col_budget) / (NUM_COLUMNS as usize - 1);
+
+			for i in 0..NUM_COLUMNS {
+				if i == crate::columns::STATE {
+					memory_budget.insert(i, state_col_budget);
+				} else {
+					memory_budget.insert(i, other_col_budget);
+				}
+			}
+			log::trace!(
+				target: "db",
+				"Open RocksDB database at {}, state column budget: {} MiB, others({}) column cache: {} MiB",
+				path,
+				state_col_budget,
+				NUM_COLUMNS,
+				other_col_budget,
+			);
+		},
+		DatabaseType::Light => {
+			let col_budget = cache_size / (NUM_COLUMNS as usize);
+			for i in 0..NUM_COLUMNS {
+				memory_budget.insert(i, col_budget);
+			}
+			log::trace!(
+				target: "db",
+				"Open RocksDB light database at {}, column cache: {} MiB",
+				path,
+				col_budget,
+			);
+		}
+	}
+	db_config.memory_budget = memory_budget;
+
+	if let Some(secondary_path) = secondary_path {
+		let secondary_path = secondary_path.to_str().ok_or_else(invalid_path)?;
+		db_config.secondary = Some(secondary_path.into());
+	}
+
+	let db = kvdb_rocksdb::Database::open(&db_config, &path)
+		.map_err(|err| sp_blockchain::Error::Backend(format!("{}", err)))?;
+	Ok(sp_database::as_database(db))
+}
+
 /// Check database type.
 pub fn check_database_type(db: &dyn Database<DbHash>, db_type: DatabaseType) -> sp_blockchain::Result<()> {
 	match db.get(COLUMN_META, meta_keys::TYPE) {
@@ -87,6 +87,16 @@ impl<Block: BlockT> GrandpaJustification<Block> {
 		Ok(GrandpaJustification { round, commit, votes_ancestries })
 	}
 
+	/// Round in which the commit was made.
+	pub fn round(&self) -> u64 {
+		self.round
+	}
+
+	/// The precommits of the commit, as `(voter, target)` pairs.
+	pub fn precommits(&self) -> impl Iterator<Item = (&AuthorityId, &Block::Hash)> {
+		self.commit.precommits.iter().map(|signed| (&signed.id, &signed.precommit.target_hash))
+	}
+
 	/// Decode a GRANDPA justification and validate the commit and the votes'
 	/// ancestry proofs finalize the given block.
 	pub fn decode_and_verify_finalizes(
@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::OutputFormat;
+use crate::{BlockFormatter, InformantBlock, InformantBlockKind, OutputFormat};
 use ansi_term::Colour;
 use log::info;
 use sc_client_api::ClientInfo;
@@ -51,17 +51,31 @@ pub struct InformantDisplay<B: BlockT> {
 	last_total_bytes_outbound: u64,
 	/// The format to print output in.
 	format: OutputFormat,
+	/// Formatter of the best and finalized blocks, if not the default one.
+	block_formatter: Option<BlockFormatter<B>>,
 }
 
 impl<B: BlockT> InformantDisplay<B> {
 	/// Builds a new informant display system.
-	pub fn new(format: OutputFormat) -> InformantDisplay<B> {
+	pub fn new(
+		format: OutputFormat,
+		block_formatter: Option<BlockFormatter<B>>,
+	) -> InformantDisplay<B> {
 		InformantDisplay {
 			last_number: None,
 			last_update: Instant::now(),
 			last_total_bytes_inbound: 0,
 			last_total_bytes_outbound: 0,
 			format,
+			block_formatter,
+		}
+	}
+
+	/// Formats a block of the status line.
+	fn format_block(&self, kind: InformantBlockKind, number: &NumberFor<B>, hash: &B::Hash) -> String {
+		match &self.block_formatter {
+			Some(formatter) => formatter(&InformantBlock { kind, number, hash }),
+			None => format!("#{} ({})", self.format.bold(Colour::White, number), hash),
 		}
 	}
 
@@ -92,46 +106,43 @@ impl<B: BlockT> InformantDisplay<B> {
 			};
 
 		let (level, status, target) = match (net_status.sync_state, net_status.best_seen_block) {
-			(SyncState::Idle, _) => ("💤", "Idle".into(), "".into()),
-			(SyncState::Downloading, None) => ("⚙️ ", format!("Preparing{}", speed), "".into()),
+			(SyncState::Idle, _) => ("💤 ", "Idle".into(), "".into()),
+			(SyncState::Downloading, None) => ("⚙️  ", format!("Preparing{}", speed), "".into()),
 			(SyncState::Downloading, Some(n)) => (
-				"⚙️ ",
+				"⚙️  ",
 				format!("Syncing{}", speed),
 				format!(", target=#{}", n),
 			),
 		};
 
-		if self.format.enable_color {
-			info!(
-				target: "substrate",
-				"{} {}{} ({} peers), best: #{} ({}), finalized #{} ({}), {} {}",
-				level,
-				Colour::White.bold().paint(&status),
-				target,
-				Colour::White.bold().paint(format!("{}", num_connected_peers)),
-				Colour::White.bold().paint(format!("{}", best_number)),
-				best_hash,
-				Colour::White.bold().paint(format!("{}", finalized_number)),
-				info.chain.finalized_hash,
-				Colour::Green.paint(format!("⬇ {}", TransferRateFormat(avg_bytes_per_sec_inbound))),
-				Colour::Red.paint(format!("⬆ {}", TransferRateFormat(avg_bytes_per_sec_outbound))),
-			)
+		let best = self.format_block(InformantBlockKind::Best, &best_number, &best_hash);
+		let finalized = self.format_block(
+			InformantBlockKind::Finalized,
+			&finalized_number,
+			&info.chain.finalized_hash,
+		);
+
+		let (inbound, outbound) = if self.format.enable_emoji { ("⬇", "⬆") } else { ("in", "out") };
+		let inbound = format!("{} {}", inbound, TransferRateFormat(avg_bytes_per_sec_inbound));
+		let outbound = format!("{} {}", outbound, TransferRateFormat(avg_bytes_per_sec_outbound));
+		let (inbound, outbound) = if self.format.enable_color {
+			(Colour::Green.paint(inbound).to_string(), Colour::Red.paint(outbound).to_string())
 		} else {
-			info!(
-				target: "substrate",
-				"{} {}{} ({} peers), best: #{} ({}), finalized #{} ({}), ⬇ {} ⬆ {}",
-				level,
-				status,
-				target,
-				num_connected_peers,
-				best_number,
-				best_hash,
-				finalized_number,
-				info.chain.finalized_hash,
-				TransferRateFormat(avg_bytes_per_sec_inbound),
-				TransferRateFormat(avg_bytes_per_sec_outbound),
-			)
-		}
+			(inbound, outbound)
+		};
+
+		info!(
+			target: "substrate",
+			"{}{}{} ({} peers), best: {}, finalized {}, {} {}",
+			self.format.emoji(level),
+			self.format.bold(Colour::White, &status),
+			target,
+			self.format.bold(Colour::White, num_connected_peers),
+			best,
+			finalized,
+			inbound,
+			outbound,
+		)
 	}
 }
 
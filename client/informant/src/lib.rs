@@ -25,10 +25,11 @@ use parity_util_mem::MallocSizeOf;
 use sc_client_api::{BlockchainEvents, UsageProvider};
 use sc_network::NetworkStatus;
 use sp_blockchain::HeaderMetadata;
-use sp_runtime::traits::{Block as BlockT, Header};
+use sp_runtime::traits::{Block as BlockT, Header, NumberFor};
 use sp_transaction_pool::TransactionPool;
 use sp_utils::{status_sinks, mpsc::tracing_unbounded};
-use std::{fmt::Display, sync::Arc, time::Duration, collections::VecDeque};
+use std::{any::Any, fmt::Display, sync::Arc, time::Duration, collections::VecDeque};
+use wasm_timer::Instant;
 
 mod display;
 
@@ -39,16 +40,110 @@ pub struct OutputFormat {
 	///
 	/// Is enabled by default.
 	pub enable_color: bool,
+
+	/// Prefix the lines with emoji.
+	///
+	/// Is enabled by default.
+	pub enable_emoji: bool,
+
+	/// The [`BlockFormatter`] used to print the blocks, set with
+	/// [`OutputFormat::set_block_formatter`].
+	///
+	/// By default blocks are printed as their number and hash.
+	pub block_formatter: Option<Arc<dyn Any + Send + Sync>>,
+
+	/// Print at most one line per interval for the imported blocks, summarizing the blocks
+	/// imported since the previous line, instead of one line per block.
+	///
+	/// The summary is printed when the first block is imported after the interval elapsed.
+	/// Disabled by default.
+	pub import_summary_interval: Option<Duration>,
 }
 
 impl Default for OutputFormat {
 	fn default() -> Self {
 		Self {
 			enable_color: true,
+			enable_emoji: true,
+			block_formatter: None,
+			import_summary_interval: None,
 		}
 	}
 }
 
+impl OutputFormat {
+	/// `emoji` if emoji are enabled, nothing otherwise.
+	fn emoji<'a>(&self, emoji: &'a str) -> &'a str {
+		if self.enable_emoji { emoji } else { "" }
+	}
+
+	/// `value` painted in bold with `colour` if colors are enabled.
+	fn bold(&self, colour: Colour, value: impl Display) -> String {
+		if self.enable_color {
+			colour.bold().paint(value.to_string()).to_string()
+		} else {
+			value.to_string()
+		}
+	}
+
+	/// The default format for a terminal, or plain output without colors nor emoji otherwise,
+	/// e.g. when the logs are piped or written to a file.
+	pub fn for_terminal(is_terminal: bool) -> Self {
+		Self {
+			enable_color: is_terminal,
+			enable_emoji: is_terminal,
+			..Default::default()
+		}
+	}
+
+	/// Print the blocks with `formatter` instead of their number and hash, e.g. to add fields
+	/// like the number of extrinsics of the block.
+	///
+	/// The formatter is ignored, with a warning, if the informant is built for a block type other
+	/// than `B`.
+	pub fn set_block_formatter<B: BlockT>(
+		&mut self,
+		formatter: impl Fn(&InformantBlock<B>) -> String + Send + Sync + 'static,
+	) {
+		let formatter: BlockFormatter<B> = Arc::new(formatter);
+		self.block_formatter = Some(Arc::new(formatter));
+	}
+
+	fn block_formatter<B: BlockT>(&self) -> Option<BlockFormatter<B>> {
+		let formatter = self.block_formatter.as_ref()?;
+		let formatter = formatter.downcast_ref::<BlockFormatter<B>>().cloned();
+		if formatter.is_none() {
+			warn!("Ignoring the informant block formatter, which is for another block type");
+		}
+		formatter
+	}
+}
+
+/// Why a block is printed by the informant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InformantBlockKind {
+	/// The block has just been imported.
+	Imported,
+	/// The block is the best block, in the periodic status line.
+	Best,
+	/// The block is the last finalized block, in the periodic status line.
+	Finalized,
+}
+
+/// A block printed by the informant.
+#[derive(Debug)]
+pub struct InformantBlock<'a, B: BlockT> {
+	/// Why the block is printed.
+	pub kind: InformantBlockKind,
+	/// Number of the block.
+	pub number: &'a NumberFor<B>,
+	/// Hash of the block.
+	pub hash: &'a B::Hash,
+}
+
+/// Function formatting a block printed by the informant, by default into `#<number> (<hash>)`.
+pub type BlockFormatter<B> = Arc<dyn Fn(&InformantBlock<B>) -> String + Send + Sync>;
+
 /// Marker trait for a type that implements `TransactionPool` and `MallocSizeOf` on `not(target_os = "unknown")`.
 #[cfg(target_os = "unknown")]
 pub trait TransactionPoolAndMaybeMallogSizeOf: TransactionPool {}
@@ -74,7 +169,8 @@ where
 	C: UsageProvider<B> + HeaderMetadata<B> + BlockchainEvents<B>,
 	<C as HeaderMetadata<B>>::Error: Display,
 {
-	let mut display = display::InformantDisplay::new(format.clone());
+	let block_formatter = format.block_formatter::<B>();
+	let mut display = display::InformantDisplay::new(format.clone(), block_formatter.clone());
 
 	let client_1 = client.clone();
 	let (network_status_sink, network_status_stream) = tracing_unbounded("mpsc_network_status");
@@ -103,11 +199,15 @@ where
 
 	future::join(
 		display_notifications,
-		display_block_import(client),
+		display_block_import(client, block_formatter, format),
 	).map(|_| ())
 }
 
-fn display_block_import<B: BlockT, C>(client: Arc<C>) -> impl Future<Output = ()>
+fn display_block_import<B: BlockT, C>(
+	client: Arc<C>,
+	block_formatter: Option<BlockFormatter<B>>,
+	format: OutputFormat,
+) -> impl Future<Output = ()>
 where
 	C: UsageProvider<B> + HeaderMetadata<B> + BlockchainEvents<B>,
 	<C as HeaderMetadata<B>>::Error: Display,
@@ -121,6 +221,10 @@ where
 	let mut last_blocks = VecDeque::new();
 	let max_blocks_to_track = 100;
 
+	// When the last summary was printed and the number of blocks imported since then.
+	let mut last_summary = (Instant::now(), 0u64);
+	let summary_interval = format.import_summary_interval;
+
 	client.import_notification_stream().for_each(move |n| {
 		// detect and log reorganizations.
 		if let Some((ref last_num, ref last_hash)) = last_best {
@@ -133,10 +237,11 @@ where
 
 				match maybe_ancestor {
 					Ok(ref ancestor) if ancestor.hash != *last_hash => info!(
-						"♻️  Reorg on #{},{} to #{},{}, common ancestor #{},{}",
-						Colour::Red.bold().paint(format!("{}", last_num)), last_hash,
-						Colour::Green.bold().paint(format!("{}", n.header.number())), n.hash,
-						Colour::White.bold().paint(format!("{}", ancestor.number)), ancestor.hash,
+						"{}Reorg on #{},{} to #{},{}, common ancestor #{},{}",
+						format.emoji("♻️  "),
+						format.bold(Colour::Red, last_num), last_hash,
+						format.bold(Colour::Green, n.header.number()), n.hash,
+						format.bold(Colour::White, ancestor.number), ancestor.hash,
 					),
 					Ok(_) => {},
 					Err(e) => warn!("Error computing tree route: {}", e),
@@ -158,12 +263,58 @@ where
 				last_blocks.pop_front();
 			}
 
-			info!(
-				target: "substrate",
-				"✨ Imported #{} ({})",
-				Colour::White.bold().paint(format!("{}", n.header.number())),
-				n.hash,
-			);
+			if let Some(interval) = summary_interval {
+				last_summary.1 += 1;
+				let elapsed = last_summary.0.elapsed();
+				if elapsed >= interval {
+					let info = client.usage_info();
+					let format_block = |kind, number: &NumberFor<B>, hash: &B::Hash| {
+						match &block_formatter {
+							Some(formatter) => formatter(&InformantBlock { kind, number, hash }),
+							None => format!("#{} ({})", format.bold(Colour::White, number), hash),
+						}
+					};
+					info!(
+						target: "substrate",
+						"{}Imported {} block(s) in the last {}s, best: {}, finalized {}",
+						format.emoji("✨ "),
+						last_summary.1,
+						elapsed.as_secs(),
+						format_block(
+							InformantBlockKind::Best,
+							&info.chain.best_number,
+							&info.chain.best_hash,
+						),
+						format_block(
+							InformantBlockKind::Finalized,
+							&info.chain.finalized_number,
+							&info.chain.finalized_hash,
+						),
+					);
+					last_summary = (Instant::now(), 0);
+				}
+				return future::ready(());
+			}
+
+			match &block_formatter {
+				Some(formatter) => info!(
+					target: "substrate",
+					"{}Imported {}",
+					format.emoji("✨ "),
+					formatter(&InformantBlock {
+						kind: InformantBlockKind::Imported,
+						number: n.header.number(),
+						hash: &n.hash,
+					}),
+				),
+				None => info!(
+					target: "substrate",
+					"{}Imported #{} ({})",
+					format.emoji("✨ "),
+					format.bold(Colour::White, n.header.number()),
+					n.hash,
+				),
+			}
 		}
 
 		future::ready(())
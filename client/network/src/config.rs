@@ -390,6 +390,12 @@ pub struct NetworkConfiguration {
 	pub transport: TransportConfig,
 	/// Maximum number of peers to ask the same blocks in parallel.
 	pub max_parallel_downloads: u32,
+	/// Maximum number of block requests in flight at once, across all peers, or `None` for no
+	/// limit.
+	///
+	/// A lower limit bounds the bandwidth and memory that syncing uses, at the cost of a slower
+	/// sync and of a higher latency to fetch newly announced blocks.
+	pub max_block_requests: Option<u32>,
 	/// Should we insert non-global addresses into the DHT?
 	pub allow_non_globals_in_dht: bool,
 	/// Require iterative Kademlia DHT queries to use disjoint paths for increased resiliency in the
@@ -425,6 +431,7 @@ impl NetworkConfiguration {
 				wasm_external_transport: None,
 			},
 			max_parallel_downloads: 5,
+			max_block_requests: None,
 			allow_non_globals_in_dht: false,
 			kademlia_disjoint_query_paths: false,
 		}
@@ -606,6 +613,33 @@ impl NodeKeyConfig {
 				.map(Keypair::Ed25519),
 		}
 	}
+
+	/// Replace the secret key stored in the file of this configuration by a newly generated one,
+	/// and return the corresponding identity `Keypair`.
+	///
+	/// The new key is first written to a temporary file which is then renamed over the previous
+	/// one, so that the file always holds a valid key. Only keys stored in a file can be rotated.
+	pub fn rotate(&self) -> io::Result<Keypair> {
+		match self {
+			NodeKeyConfig::Ed25519(Secret::File(path)) => {
+				let mut tmp = path.clone().into_os_string();
+				tmp.push(".new");
+				let tmp = PathBuf::from(tmp);
+				if tmp.exists() {
+					fs::remove_file(&tmp)?;
+				}
+
+				let sk = ed25519::SecretKey::generate();
+				write_secret_file(&tmp, sk.as_ref())?;
+				fs::rename(&tmp, path)?;
+				Ok(Keypair::Ed25519(sk.into()))
+			},
+			NodeKeyConfig::Ed25519(_) => Err(io::Error::new(
+				io::ErrorKind::Other,
+				"The node key isn't stored in a file",
+			)),
+		}
+	}
 }
 
 /// Load a secret key from a file, if it exists, or generate a
@@ -699,6 +733,19 @@ mod tests {
 		assert!(file.is_file() && secret_bytes(&kp1) == secret_bytes(&kp2))
 	}
 
+	#[test]
+	fn test_rotate_secret_file() {
+		let tmp = tempdir_with_prefix("x");
+		let file = tmp.path().join("x").to_path_buf();
+		let config = NodeKeyConfig::Ed25519(Secret::File(file.clone()));
+		let kp1 = config.clone().into_keypair().unwrap();
+		let kp2 = config.rotate().unwrap();
+		let kp3 = config.into_keypair().unwrap();
+		assert!(secret_bytes(&kp1) != secret_bytes(&kp2));
+		assert!(secret_bytes(&kp2) == secret_bytes(&kp3));
+		assert!(NodeKeyConfig::Ed25519(Secret::New).rotate().is_err());
+	}
+
 	#[test]
 	fn test_secret_input() {
 		let sk = ed25519::SecretKey::generate();
@@ -129,6 +129,7 @@ struct Metrics {
 	peers: Gauge<U64>,
 	queued_blocks: Gauge<U64>,
 	fork_targets: Gauge<U64>,
+	block_requests: Gauge<U64>,
 	justifications: GaugeVec<U64>,
 	propagated_transactions: Counter<U64>,
 }
@@ -152,6 +153,13 @@ impl Metrics {
 				let g = Gauge::new("sync_fork_targets", "Number of fork sync targets")?;
 				register(g, r)?
 			},
+			block_requests: {
+				let g = Gauge::new(
+					"sync_block_requests_in_flight",
+					"Number of block requests waiting for a response",
+				)?;
+				register(g, r)?
+			},
 			justifications: {
 				let g = GaugeVec::new(
 					Opts::new(
@@ -282,6 +290,8 @@ pub struct ProtocolConfig {
 	pub roles: Roles,
 	/// Maximum number of peers to ask the same blocks in parallel.
 	pub max_parallel_downloads: u32,
+	/// Maximum number of block requests in flight at once, or `None` for no limit.
+	pub max_block_requests: Option<u32>,
 }
 
 impl Default for ProtocolConfig {
@@ -289,6 +299,7 @@ impl Default for ProtocolConfig {
 		ProtocolConfig {
 			roles: Roles::FULL,
 			max_parallel_downloads: 5,
+			max_block_requests: None,
 		}
 	}
 }
@@ -365,6 +376,7 @@ impl<B: BlockT, H: ExHashT> Protocol<B, H> {
 			&info,
 			block_announce_validator,
 			config.max_parallel_downloads,
+			config.max_block_requests,
 		);
 
 		let important_peers = {
@@ -1300,6 +1312,7 @@ impl<B: BlockT, H: ExHashT> Protocol<B, H> {
 
 			metrics.fork_targets.set(m.fork_targets.into());
 			metrics.queued_blocks.set(m.queued_blocks.into());
+			metrics.block_requests.set(m.block_requests.into());
 
 			metrics.justifications.with_label_values(&["pending"])
 				.set(m.justifications.pending_requests.into());
@@ -198,6 +198,8 @@ pub struct ChainSync<B: BlockT> {
 	block_announce_validator: Box<dyn BlockAnnounceValidator<B> + Send>,
 	/// Maximum number of peers to ask the same blocks in parallel.
 	max_parallel_downloads: u32,
+	/// Maximum number of block requests in flight at once, across all peers.
+	max_block_requests: Option<u32>,
 	/// Total number of downloaded blocks.
 	downloaded_blocks: usize,
 	/// All block announcement that are currently being validated.
@@ -408,6 +410,7 @@ impl<B: BlockT> ChainSync<B> {
 		info: &BlockchainInfo<B>,
 		block_announce_validator: Box<dyn BlockAnnounceValidator<B> + Send>,
 		max_parallel_downloads: u32,
+		max_block_requests: Option<u32>,
 	) -> Self {
 		let mut required_block_attributes = BlockAttributes::HEADER | BlockAttributes::JUSTIFICATION;
 
@@ -430,6 +433,7 @@ impl<B: BlockT> ChainSync<B> {
 			pending_requests: Default::default(),
 			block_announce_validator,
 			max_parallel_downloads,
+			max_block_requests,
 			downloaded_blocks: 0,
 			block_announce_validation: Default::default(),
 			block_announce_validation_per_peer_stats: Default::default(),
@@ -472,6 +476,16 @@ impl<B: BlockT> ChainSync<B> {
 		self.fork_targets.len()
 	}
 
+	/// Number of block requests in flight, not counting ancestry searches and justifications.
+	pub fn num_block_requests(&self) -> usize {
+		self.peers.values()
+			.filter(|p| matches!(
+				p.state,
+				PeerSyncState::DownloadingNew(_) | PeerSyncState::DownloadingStale(_)
+			))
+			.count()
+	}
+
 	/// Number of downloaded blocks.
 	pub fn num_downloaded_blocks(&self) -> usize {
 		self.downloaded_blocks
@@ -660,6 +674,9 @@ impl<B: BlockT> ChainSync<B> {
 	}
 
 	/// Get an iterator over all block requests of all peers.
+	///
+	/// No more requests are returned once `max_block_requests` block requests are in flight. The
+	/// peers left without a request are asked again once some of the requests have completed.
 	pub fn block_requests(&mut self) -> impl Iterator<Item = (&PeerId, BlockRequest<B>)> + '_ {
 		if self.pending_requests.is_empty() {
 			return Either::Left(std::iter::empty())
@@ -668,6 +685,12 @@ impl<B: BlockT> ChainSync<B> {
 			trace!(target: "sync", "Too many blocks in the queue.");
 			return Either::Left(std::iter::empty())
 		}
+		let max_requests = self.max_block_requests.map_or(usize::MAX, |max| max as usize);
+		let mut in_flight = self.num_block_requests();
+		if in_flight >= max_requests {
+			trace!(target: "sync", "Too many block requests in flight.");
+			return Either::Left(std::iter::empty())
+		}
 		let major_sync = self.status().state == SyncState::Downloading;
 		let blocks = &mut self.blocks;
 		let attrs = &self.required_block_attributes;
@@ -677,11 +700,16 @@ impl<B: BlockT> ChainSync<B> {
 		let client = &self.client;
 		let queue = &self.queue_blocks;
 		let pending_requests = self.pending_requests.take();
+		let deferred_requests = &mut self.pending_requests;
 		let max_parallel = if major_sync { 1 } else { self.max_parallel_downloads };
 		let iter = self.peers.iter_mut().filter_map(move |(id, peer)| {
 			if !peer.state.is_available() || !pending_requests.contains(id) {
 				return None
 			}
+			if in_flight >= max_requests {
+				deferred_requests.add(id);
+				return None
+			}
 
 			if let Some((range, req)) = peer_block_request(
 				id,
@@ -701,6 +729,7 @@ impl<B: BlockT> ChainSync<B> {
 					peer.common_number,
 					req,
 				);
+				in_flight += 1;
 				Some((id, req))
 			} else if let Some((hash, req)) = fork_sync_request(
 				id,
@@ -716,6 +745,7 @@ impl<B: BlockT> ChainSync<B> {
 			) {
 				trace!(target: "sync", "Downloading fork {:?} from {}", hash, id);
 				peer.state = PeerSyncState::DownloadingStale(hash);
+				in_flight += 1;
 				Some((id, req))
 			} else {
 				None
@@ -1432,6 +1462,7 @@ impl<B: BlockT> ChainSync<B> {
 		Metrics {
 			queued_blocks: self.queue_blocks.len().try_into().unwrap_or(std::u32::MAX),
 			fork_targets: self.fork_targets.len().try_into().unwrap_or(std::u32::MAX),
+			block_requests: self.num_block_requests().try_into().unwrap_or(std::u32::MAX),
 			justifications: self.extra_justifications.metrics(),
 			_priv: ()
 		}
@@ -1442,6 +1473,7 @@ impl<B: BlockT> ChainSync<B> {
 pub(crate) struct Metrics {
 	pub(crate) queued_blocks: u32,
 	pub(crate) fork_targets: u32,
+	pub(crate) block_requests: u32,
 	pub(crate) justifications: extra_requests::Metrics,
 	_priv: ()
 }
@@ -1767,6 +1799,7 @@ mod test {
 			&info,
 			block_announce_validator,
 			1,
+			None,
 		);
 
 		let (a1_hash, a1_number) = {
@@ -1838,6 +1871,7 @@ mod test {
 			&info,
 			Box::new(DefaultBlockAnnounceValidator),
 			1,
+			None,
 		);
 
 		let peer_id1 = PeerId::random();
@@ -1897,6 +1931,40 @@ mod test {
 		);
 	}
 
+	#[test]
+	fn block_requests_are_capped() {
+		let client = Arc::new(TestClientBuilder::new().build());
+		let info = client.info();
+
+		let mut sync = ChainSync::new(
+			Roles::AUTHORITY,
+			client.clone(),
+			&info,
+			Box::new(DefaultBlockAnnounceValidator),
+			5,
+			Some(2),
+		);
+
+		let peers = vec![PeerId::random(), PeerId::random(), PeerId::random()];
+		for peer in &peers {
+			sync.new_peer(peer.clone(), Hash::random(), 5).unwrap();
+		}
+
+		// only two of the three peers are asked for blocks
+		let requested = sync.block_requests().map(|(p, _)| p.clone()).collect::<Vec<_>>();
+		assert_eq!(requested.len(), 2);
+		assert_eq!(sync.num_block_requests(), 2);
+		assert_eq!(sync.metrics().block_requests, 2);
+		let deferred = peers.iter().find(|p| !requested.contains(p)).unwrap().clone();
+		assert!(sync.pending_requests.contains(&deferred));
+		assert_eq!(sync.block_requests().count(), 0);
+
+		// the remaining peer is asked once a request completes
+		sync.peer_disconnected(&requested[0]);
+		let requested = sync.block_requests().map(|(p, _)| p.clone()).collect::<Vec<_>>();
+		assert_eq!(requested, vec![deferred]);
+	}
+
 	/// Send a block annoucnement for the given `header`.
 	fn send_block_announce(
 		header: Header,
@@ -1981,6 +2049,7 @@ mod test {
 			&info,
 			Box::new(DefaultBlockAnnounceValidator),
 			5,
+			None,
 		);
 
 		let peer_id1 = PeerId::random();
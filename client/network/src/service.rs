@@ -80,6 +80,7 @@ use sc_peerset::PeersetHandle;
 use sp_consensus::import_queue::{BlockImportError, BlockImportResult, ImportQueue, Link};
 use sp_runtime::traits::{Block as BlockT, NumberFor};
 use sp_utils::mpsc::{tracing_unbounded, TracingUnboundedReceiver, TracingUnboundedSender};
+use sp_utils::metrics::PEER_DISCONNECTS_TOTAL;
 use std::{
 	borrow::Cow,
 	collections::{HashMap, HashSet},
@@ -269,6 +270,7 @@ impl<B: BlockT + 'static, H: ExHashT> NetworkWorker<B, H> {
 			protocol::ProtocolConfig {
 				roles: From::from(&params.role),
 				max_parallel_downloads: params.network_config.max_parallel_downloads,
+				max_block_requests: params.network_config.max_block_requests,
 			},
 			local_peer_id.clone(),
 			params.chain.clone(),
@@ -1558,23 +1560,24 @@ impl<B: BlockT + 'static, H: ExHashT> Future for NetworkWorker<B, H> {
 				},
 				Poll::Ready(SwarmEvent::ConnectionClosed { peer_id, cause, endpoint, num_established }) => {
 					trace!(target: "sub-libp2p", "Libp2p => Disconnected({:?}, {:?})", peer_id, cause);
+					let reason = match cause {
+						Some(ConnectionError::IO(_)) => "transport-error",
+						Some(ConnectionError::Handler(NodeHandlerWrapperError::Handler(EitherError::A(EitherError::A(
+							EitherError::A(EitherError::A(EitherError::B(
+							EitherError::A(PingFailure::Timeout))))))))) => "ping-timeout",
+						Some(ConnectionError::Handler(NodeHandlerWrapperError::Handler(EitherError::A(EitherError::A(
+							EitherError::A(EitherError::A(EitherError::A(
+							NotifsHandlerError::SyncNotificationsClogged)))))))) => "sync-notifications-clogged",
+						Some(ConnectionError::Handler(NodeHandlerWrapperError::Handler(_))) => "protocol-error",
+						Some(ConnectionError::Handler(NodeHandlerWrapperError::KeepAliveTimeout)) => "keep-alive-timeout",
+						None => "actively-closed",
+					};
+					PEER_DISCONNECTS_TOTAL.with_label_values(&[reason]).inc();
 					if let Some(metrics) = this.metrics.as_ref() {
 						let direction = match endpoint {
 							ConnectedPoint::Dialer { .. } => "out",
 							ConnectedPoint::Listener { .. } => "in",
 						};
-						let reason = match cause {
-							Some(ConnectionError::IO(_)) => "transport-error",
-							Some(ConnectionError::Handler(NodeHandlerWrapperError::Handler(EitherError::A(EitherError::A(
-								EitherError::A(EitherError::A(EitherError::B(
-								EitherError::A(PingFailure::Timeout))))))))) => "ping-timeout",
-							Some(ConnectionError::Handler(NodeHandlerWrapperError::Handler(EitherError::A(EitherError::A(
-								EitherError::A(EitherError::A(EitherError::A(
-								NotifsHandlerError::SyncNotificationsClogged)))))))) => "sync-notifications-clogged",
-							Some(ConnectionError::Handler(NodeHandlerWrapperError::Handler(_))) => "protocol-error",
-							Some(ConnectionError::Handler(NodeHandlerWrapperError::KeepAliveTimeout)) => "keep-alive-timeout",
-							None => "actively-closed",
-						};
 						metrics.connections_closed_total.with_label_values(&[direction, reason]).inc();
 
 						// `num_established` represents the number of *remaining* connections.
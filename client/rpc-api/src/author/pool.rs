@@ -0,0 +1,46 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Transaction pool contents for author RPC module.
+
+use sp_core::Bytes;
+use serde::{Serialize, Deserialize};
+
+/// A transaction of the pool, as seen by the pool.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolTransaction<Hash> {
+	/// The hash of the transaction.
+	pub hash: Hash,
+	/// The priority of the transaction.
+	pub priority: u64,
+	/// The number of blocks the transaction stays valid for.
+	pub longevity: u64,
+	/// The tags the transaction requires.
+	pub requires: Vec<Bytes>,
+	/// The tags the transaction provides.
+	pub provides: Vec<Bytes>,
+}
+
+/// The contents of the transaction pool.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolContents<Hash> {
+	/// The transactions ready to be included, ordered by priority.
+	pub ready: Vec<PoolTransaction<Hash>>,
+	/// The transactions waiting for some of their requirements to be satisfied.
+	pub future: Vec<PoolTransaction<Hash>>,
+}
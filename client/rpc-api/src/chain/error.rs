@@ -33,6 +33,10 @@ pub enum Error {
 	/// Client error.
 	#[display(fmt="Client error: {}", _0)]
 	Client(Box<dyn std::error::Error + Send>),
+	/// The subscription was dropped for buffering too many notifications.
+	#[display(fmt="Subscription dropped after buffering {} notifications", _0)]
+	#[from(ignore)]
+	SubscriptionDropped(usize),
 	/// Other error type.
 	Other(String),
 }
@@ -57,6 +61,11 @@ impl From<Error> for rpc::Error {
 				message,
 				data: None,
 			},
+			e @ Error::SubscriptionDropped(_) => rpc::Error {
+				code: rpc::ErrorCode::ServerError(BASE_ERROR + 2),
+				message: e.to_string(),
+				data: None,
+			},
 			e => errors::internal(e),
 		}
 	}
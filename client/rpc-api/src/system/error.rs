@@ -32,6 +32,13 @@ pub enum Error {
 	NotHealthy(Health),
 	/// Peer argument is malformatted.
 	MalformattedPeerArg(String),
+	/// The node key couldn't be rotated.
+	#[display(fmt = "Node key not rotated: {}", _0)]
+	#[from(ignore)]
+	NodeKeyNotRotated(String),
+	/// The node can't stop or start authoring at runtime.
+	#[display(fmt = "Authoring can't be stopped or started on this node")]
+	AuthoringNotSwitchable,
 }
 
 impl std::error::Error for Error {}
@@ -51,7 +58,17 @@ impl From<Error> for rpc::Error {
 				code :rpc::ErrorCode::ServerError(BASE_ERROR + 2),
 				message: e.clone(),
 				data: None,
-			}
+			},
+			Error::NodeKeyNotRotated(_) => rpc::Error {
+				code: rpc::ErrorCode::ServerError(BASE_ERROR + 3),
+				message: format!("{}", e),
+				data: None,
+			},
+			Error::AuthoringNotSwitchable => rpc::Error {
+				code: rpc::ErrorCode::ServerError(BASE_ERROR + 4),
+				message: format!("{}", e),
+				data: None,
+			},
 		}
 	}
 }
@@ -122,4 +122,39 @@ pub trait SystemApi<Hash, Number> {
 	#[rpc(name = "system_resetLogFilter", returns = "()")]
 	fn system_reset_log_filter(&self)
 		-> Result<(), jsonrpc_core::Error>;
+
+	/// Prepares the node for a restart: the node stops all of its work, flushes its database and
+	/// exits with a code telling its supervisor to restart it.
+	///
+	/// Returns once the shutdown has been initiated.
+	#[rpc(name = "system_prepareRestart", returns = "()")]
+	fn system_prepare_restart(&self)
+		-> Compat<BoxFuture<'static, Result<(), jsonrpc_core::Error>>>;
+
+	/// Replaces the node key by a newly generated one. Only node keys stored in a file can be
+	/// rotated.
+	///
+	/// The running node can't change its network identity, so it keeps its current peer id and
+	/// only announces the new one after it has been restarted. This doesn't restart the node.
+	///
+	/// Since this changes the network identity of the node, `confirm` must be the current peer
+	/// id of the node. Returns the peer id the node will have after its next restart.
+	#[rpc(name = "system_rotateNodeKey", returns = "String")]
+	fn system_rotate_node_key(&self, confirm: String)
+		-> Compat<BoxFuture<'static, Result<String, jsonrpc_core::Error>>>;
+
+	/// Stops the authoring of blocks, e.g. before the maintenance of a validator. The node keeps
+	/// importing blocks and answering requests in the meantime.
+	///
+	/// Returns `false` if authoring was already stopped.
+	#[rpc(name = "system_stopAuthoring", returns = "bool")]
+	fn system_stop_authoring(&self)
+		-> Compat<BoxFuture<'static, Result<bool, jsonrpc_core::Error>>>;
+
+	/// Starts the authoring of blocks again after `system_stopAuthoring`.
+	///
+	/// Returns `false` if authoring wasn't stopped.
+	#[rpc(name = "system_startAuthoring", returns = "bool")]
+	fn system_start_authoring(&self)
+		-> Compat<BoxFuture<'static, Result<bool, jsonrpc_core::Error>>>;
 }
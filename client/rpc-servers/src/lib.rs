@@ -22,9 +22,9 @@
 
 mod middleware;
 
-use std::io;
-use jsonrpc_core::{IoHandlerExtension, MetaIoHandler};
-use log::error;
+use std::{collections::HashMap, io, sync::{Arc, Mutex}, time::Instant};
+use jsonrpc_core::{IoHandlerExtension, MetaIoHandler, Params};
+use log::{error, warn};
 use pubsub::PubSubMetadata;
 
 /// Maximal payload accepted by RPC servers.
@@ -37,7 +37,7 @@ const WS_MAX_CONNECTIONS: usize = 100;
 pub type RpcHandler<T> = pubsub::PubSubHandler<T, RpcMiddleware>;
 
 pub use self::inner::*;
-pub use middleware::{RpcMiddleware, RpcMetrics};
+pub use middleware::{MethodFilter, RpcMiddleware, RpcMetrics, SYNCING_ERROR_CODE};
 
 /// Construct rpc `IoHandler`
 pub fn rpc_handler<M: PubSubMetadata>(
@@ -63,9 +63,81 @@ pub fn rpc_handler<M: PubSubMetadata>(
 	io
 }
 
+/// The open WS sessions, listed by the `rpc_sessions` method and closed by the
+/// `rpc_killSession` one, see [`add_session_methods`].
+///
+/// Clones share the same sessions.
+#[derive(Clone, Default)]
+pub struct WsSessions(Arc<Mutex<HashMap<u64, WsSession>>>);
+
+struct WsSession {
+	opened: Instant,
+	origin: Option<String>,
+	close: Box<dyn Fn() + Send>,
+}
+
+impl WsSessions {
+	#[cfg(not(target_os = "unknown"))]
+	fn open(&self, id: u64, origin: Option<String>, close: Box<dyn Fn() + Send>) {
+		let session = WsSession { opened: Instant::now(), origin, close };
+		self.lock().insert(id, session);
+	}
+
+	#[cfg(not(target_os = "unknown"))]
+	fn remove(&self, id: u64) {
+		self.lock().remove(&id);
+	}
+
+	/// The id, age in seconds and origin of every open session, oldest first, as JSON.
+	pub fn list(&self) -> serde_json::Value {
+		let sessions = self.lock();
+		let mut sessions = sessions.iter().collect::<Vec<_>>();
+		sessions.sort_by_key(|(_, session)| session.opened);
+		sessions.into_iter()
+			.map(|(id, session)| serde_json::json!({
+				"id": id,
+				"ageSecs": session.opened.elapsed().as_secs(),
+				"origin": session.origin,
+			}))
+			.collect()
+	}
+
+	/// Close the session with the given id. Returns `false` if there is no such session.
+	pub fn kill(&self, id: u64) -> bool {
+		match self.lock().get(&id) {
+			Some(session) => {
+				warn!("Closing WS RPC session {}", id);
+				(session.close)();
+				true
+			},
+			None => false,
+		}
+	}
+
+	fn lock(&self) -> std::sync::MutexGuard<HashMap<u64, WsSession>> {
+		// A panic while holding the lock leaves the sessions consistent.
+		self.0.lock().unwrap_or_else(|e| e.into_inner())
+	}
+}
+
+/// Add the `rpc_sessions` and `rpc_killSession` methods, listing and closing the sessions of
+/// the WS servers started with `sessions`.
+///
+/// Killing a session frees the resources held by a misbehaving client, so these methods are
+/// meant for handlers with the unsafe methods allowed only.
+pub fn add_session_methods<M: PubSubMetadata>(io: &mut RpcHandler<M>, sessions: WsSessions) {
+	let listed = sessions.clone();
+	io.add_method("rpc_sessions", move |_| Ok(listed.list()));
+	io.add_method("rpc_killSession", move |params: Params| {
+		let (id,): (u64,) = params.parse()?;
+		Ok(serde_json::Value::Bool(sessions.kill(id)))
+	});
+}
+
 #[cfg(not(target_os = "unknown"))]
 mod inner {
 	use super::*;
+	use std::sync::atomic::{AtomicUsize, Ordering};
 
 	/// Type alias for ipc server
 	pub type IpcServer = ipc::Server;
@@ -115,16 +187,43 @@ mod inner {
 
 	/// Start WS server listening on given address.
 	///
+	/// At most `max_connections` (or [`WS_MAX_CONNECTIONS`] if unspecified) connections are
+	/// served concurrently. Connections above that limit get an HTTP `503` response to their
+	/// handshake and are reported through `metrics`. The open sessions are tracked in
+	/// `sessions`.
+	///
 	/// **Note**: Only available if `not(target_os = "unknown")`.
 	pub fn start_ws<M: pubsub::PubSubMetadata + From<jsonrpc_core::futures::sync::mpsc::Sender<String>>> (
 		addr: &std::net::SocketAddr,
 		max_connections: Option<usize>,
 		cors: Option<&Vec<String>>,
 		io: RpcHandler<M>,
+		metrics: RpcMetrics,
+		sessions: WsSessions,
 	) -> io::Result<ws::Server> {
-		ws::ServerBuilder::with_meta_extractor(io, |context: &ws::RequestContext| context.sender().into())
+		let limiter = ConnectionLimiter::new(
+			max_connections.unwrap_or(WS_MAX_CONNECTIONS),
+			metrics,
+			sessions.clone(),
+		);
+
+		let meta_extractor = move |context: &ws::RequestContext| {
+			let out = context.out.clone();
+			sessions.open(
+				context.session_id,
+				context.origin.as_ref().map(|origin| String::from(&**origin)),
+				Box::new(move || { let _ = out.close(ws::ws::CloseCode::Normal); }),
+			);
+			context.sender().into()
+		};
+
+		ws::ServerBuilder::with_meta_extractor(io, meta_extractor)
 			.max_payload(MAX_PAYLOAD)
-			.max_connections(max_connections.unwrap_or(WS_MAX_CONNECTIONS))
+			// Leave some room above the limit so that excess connections reach the handshake and
+			// get a proper response instead of being dropped by the socket layer.
+			.max_connections(limiter.max_connections + WS_REJECTION_HEADROOM)
+			.session_stats(limiter.clone())
+			.request_middleware(limiter)
 			.allowed_origins(map_cors(cors))
 			.allowed_hosts(hosts_filtering(cors.is_some()))
 			.start(addr)
@@ -138,6 +237,61 @@ mod inner {
 			})
 	}
 
+	/// Number of connections accepted above the limit only to be rejected during the handshake.
+	const WS_REJECTION_HEADROOM: usize = 16;
+
+	/// Keeps track of the open WS sessions and rejects the handshake of the ones above the limit.
+	#[derive(Clone)]
+	struct ConnectionLimiter {
+		max_connections: usize,
+		open: Arc<AtomicUsize>,
+		metrics: RpcMetrics,
+		sessions: WsSessions,
+	}
+
+	impl ConnectionLimiter {
+		fn new(max_connections: usize, metrics: RpcMetrics, sessions: WsSessions) -> Self {
+			ConnectionLimiter {
+				max_connections,
+				open: Arc::new(AtomicUsize::new(0)),
+				metrics,
+				sessions,
+			}
+		}
+	}
+
+	impl ws::SessionStats for ConnectionLimiter {
+		fn open_session(&self, _id: ws::SessionId) {
+			self.open.fetch_add(1, Ordering::SeqCst);
+		}
+
+		fn close_session(&self, id: ws::SessionId) {
+			self.open.fetch_sub(1, Ordering::SeqCst);
+			self.sessions.remove(id);
+		}
+	}
+
+	impl ws::RequestMiddleware for ConnectionLimiter {
+		fn process(&self, _req: &ws::ws::Request) -> ws::MiddlewareAction {
+			// The session of the connection being processed is already accounted for.
+			if self.open.load(Ordering::SeqCst) <= self.max_connections {
+				return ws::MiddlewareAction::Proceed;
+			}
+
+			warn!("Rejecting WS RPC connection: limit of {} connections reached", self.max_connections);
+			self.metrics.on_connection_rejected("ws");
+			ws::MiddlewareAction::Respond {
+				response: ws::ws::Response::new(
+					503,
+					"Service Unavailable",
+					b"Too many connections".to_vec(),
+				),
+				validate_origin: false,
+				validate_hosts: false,
+			}
+		}
+	}
+
 	fn map_cors<T: for<'a> From<&'a str>>(
 		cors: Option<&Vec<String>>
 	) -> http::DomainsValidation<T> {
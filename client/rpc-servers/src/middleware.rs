@@ -18,9 +18,11 @@
 
 //! Middleware for RPC requests.
 
+use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
 use jsonrpc_core::{
 	Middleware as RequestMiddleware, Metadata,
-	Request, Response, FutureResponse, FutureOutput
+	Request, Response, FutureResponse, FutureOutput,
+	Call, Error, ErrorCode, Output,
 };
 use prometheus_endpoint::{
 	Registry, CounterVec, PrometheusError,
@@ -33,6 +35,7 @@ use futures::{future::Either, Future};
 #[derive(Debug, Clone)]
 pub struct RpcMetrics {
 	rpc_calls: Option<CounterVec<U64>>,
+	rejected_connections: Option<CounterVec<U64>>,
 }
 
 impl RpcMetrics {
@@ -51,14 +54,67 @@ impl RpcMetrics {
 					r,
 				)
 			).transpose()?,
+			rejected_connections: metrics_registry.map(|r|
+				register(
+					CounterVec::new(
+						Opts::new(
+							"rpc_connections_rejected_total",
+							"Number of rpc connections rejected because the connection limit was reached",
+						),
+						&["protocol"]
+					)?,
+					r,
+				)
+			).transpose()?,
 		})
 	}
+
+	/// Report a connection that was rejected by the server for the given transport.
+	pub fn on_connection_rejected(&self, transport_label: &str) {
+		if let Some(ref rejected_connections) = self.rejected_connections {
+			rejected_connections.with_label_values(&[transport_label]).inc();
+		}
+	}
+}
+
+/// Error code of the calls rejected while the node is syncing.
+pub const SYNCING_ERROR_CODE: i64 = -32050;
+
+/// Restricts the methods served by an RPC endpoint.
+///
+/// A pattern is either a full method name, or a prefix followed by `*` (e.g. `author_*`).
+#[derive(Debug, Clone, Default)]
+pub struct MethodFilter {
+	allow: Option<Vec<String>>,
+	deny: Vec<String>,
+}
+
+impl MethodFilter {
+	/// Create a filter serving the methods matching `allow` (or all of them if `None`),
+	/// except those matching `deny`.
+	pub fn new(allow: Option<Vec<String>>, deny: Vec<String>) -> Self {
+		MethodFilter { allow, deny }
+	}
+
+	/// Whether `method` is served.
+	pub fn is_allowed(&self, method: &str) -> bool {
+		let matches = |pattern: &String| if pattern.ends_with('*') {
+			method.starts_with(&pattern[..pattern.len() - 1])
+		} else {
+			method == pattern
+		};
+
+		self.allow.as_ref().map_or(true, |allow| allow.iter().any(matches))
+			&& !self.deny.iter().any(matches)
+	}
 }
 
 /// Middleware for RPC calls
 pub struct RpcMiddleware {
 	metrics: RpcMetrics,
 	transport_label: String,
+	syncing: Option<Arc<AtomicBool>>,
+	filter: Option<MethodFilter>,
 }
 
 impl RpcMiddleware {
@@ -70,7 +126,52 @@ impl RpcMiddleware {
 		RpcMiddleware {
 			metrics,
 			transport_label: String::from(transport_label),
+			syncing: None,
+			filter: None,
+		}
+	}
+
+	/// Reject every call with a [`SYNCING_ERROR_CODE`] error for as long as `syncing` is set.
+	///
+	/// Since the health endpoint of the HTTP server goes through the middleware as well, it
+	/// reports the node as unhealthy meanwhile.
+	pub fn reject_while_syncing(mut self, syncing: Arc<AtomicBool>) -> Self {
+		self.syncing = Some(syncing);
+		self
+	}
+
+	/// Only serve the methods allowed by `filter`, the others are reported as not found.
+	pub fn with_method_filter(mut self, filter: MethodFilter) -> Self {
+		self.filter = Some(filter);
+		self
+	}
+}
+
+/// Build the response rejecting all the calls of `request` because the node is syncing.
+fn syncing_response(request: &Request) -> Option<Response> {
+	let reject = |call: &Call| {
+		let error = Error {
+			code: ErrorCode::ServerError(SYNCING_ERROR_CODE),
+			message: "Node is syncing".into(),
+			data: None,
+		};
+		match call {
+			Call::MethodCall(call) => Some(Output::from(Err(error), call.id.clone(), call.jsonrpc)),
+			Call::Notification(_) => None,
+			Call::Invalid { id } => Some(Output::from(Err(error), id.clone(), None)),
 		}
+	};
+
+	match request {
+		Request::Single(call) => reject(call).map(Response::Single),
+		Request::Batch(calls) => {
+			let outputs = calls.iter().filter_map(reject).collect::<Vec<_>>();
+			if outputs.is_empty() {
+				None
+			} else {
+				Some(Response::Batch(outputs))
+			}
+		},
 	}
 }
 
@@ -87,6 +188,48 @@ impl<M: Metadata> RequestMiddleware<M> for RpcMiddleware {
 			rpc_calls.with_label_values(&[self.transport_label.as_str()]).inc();
 		}
 
+		if self.syncing.as_ref().map_or(false, |syncing| syncing.load(Ordering::Relaxed)) {
+			return Either::A(Box::new(futures::future::ok(syncing_response(&request))));
+		}
+
 		Either::B(next(request, meta))
 	}
+
+	fn on_call<F, X>(&self, call: Call, meta: M, next: F) -> Either<FutureOutput, X>
+	where
+		F: Fn(Call, M) -> X + Send + Sync,
+		X: Future<Item = Option<Output>, Error = ()> + Send + 'static,
+	{
+		let filtered = match (&self.filter, &call) {
+			(Some(filter), Call::MethodCall(c)) if !filter.is_allowed(&c.method) =>
+				Some(Some(Output::from(Err(Error::method_not_found()), c.id.clone(), c.jsonrpc))),
+			(Some(filter), Call::Notification(n)) if !filter.is_allowed(&n.method) => Some(None),
+			_ => None,
+		};
+
+		match filtered {
+			Some(output) => Either::A(Box::new(futures::future::ok(output))),
+			None => Either::B(next(call, meta)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn method_filter_matches_names_and_prefixes() {
+		let filter = MethodFilter::new(
+			Some(vec!["system_*".into(), "chain_getBlock".into()]),
+			vec!["system_addReservedPeer".into()],
+		);
+
+		assert!(filter.is_allowed("system_health"));
+		assert!(filter.is_allowed("chain_getBlock"));
+		assert!(!filter.is_allowed("chain_getBlockHash"));
+		assert!(!filter.is_allowed("system_addReservedPeer"));
+		assert!(!filter.is_allowed("author_submitExtrinsic"));
+		assert!(MethodFilter::default().is_allowed("author_submitExtrinsic"));
+	}
 }
@@ -160,6 +160,23 @@ impl<P, Client> AuthorApi<TxHash<P>, BlockHash<P>> for Author<P, Client>
 		Ok(self.pool.ready().map(|tx| tx.data().encode().into()).collect())
 	}
 
+	fn pool_contents(&self) -> Result<pool::PoolContents<TxHash<P>>> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let transaction = |tx: Arc<P::InPoolTransaction>| pool::PoolTransaction {
+			hash: tx.hash().clone(),
+			priority: *tx.priority(),
+			longevity: *tx.longevity(),
+			requires: tx.requires().iter().cloned().map(Into::into).collect(),
+			provides: tx.provides().iter().cloned().map(Into::into).collect(),
+		};
+
+		Ok(pool::PoolContents {
+			ready: self.pool.ready().map(transaction).collect(),
+			future: self.pool.futures().into_iter().map(transaction).collect(),
+		})
+	}
+
 	fn remove_extrinsic(
 		&self,
 		bytes_or_hash: Vec<hash::ExtrinsicOrHash<TxHash<P>>>,
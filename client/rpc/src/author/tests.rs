@@ -200,6 +200,21 @@ fn should_return_pending_extrinsics() {
 	);
 }
 
+#[test]
+fn should_return_pool_contents() {
+	let p = TestSetup::default().author();
+
+	let ready = uxt(AccountKeyring::Alice, 0);
+	let ready_hash = p.submit_extrinsic(ready.encode().into()).wait().unwrap();
+	let future = uxt(AccountKeyring::Alice, 2);
+	let future_hash = p.submit_extrinsic(future.encode().into()).wait().unwrap();
+
+	let contents = p.pool_contents().unwrap();
+	assert_eq!(contents.ready.iter().map(|tx| tx.hash).collect::<Vec<_>>(), vec![ready_hash]);
+	assert_eq!(contents.future.iter().map(|tx| tx.hash).collect::<Vec<_>>(), vec![future_hash]);
+	assert!(!contents.future[0].requires.is_empty());
+}
+
 #[test]
 fn should_remove_extrinsics() {
 	let setup = TestSetup::default();
@@ -24,12 +24,12 @@ mod chain_light;
 #[cfg(test)]
 mod tests;
 
-use std::sync::Arc;
+use std::{collections::VecDeque, sync::Arc};
 use futures::{future, StreamExt, TryStreamExt};
 use log::warn;
 use rpc::{
 	Result as RpcResult,
-	futures::{stream, Future, Sink, Stream},
+	futures::{future::Either, stream, Async, AsyncSink, Future, Poll, Sink, Stream},
 };
 
 use sc_client_api::{BlockchainEvents, light::{Fetcher, RemoteBlockchain}};
@@ -44,6 +44,7 @@ use self::error::{Result, Error, FutureResult};
 
 pub use sc_rpc_api::chain::*;
 use sp_blockchain::HeaderBackend;
+use sp_utils::metrics::RPC_SUBSCRIPTIONS_DROPPED_TOTAL;
 use sc_client_api::BlockBackend;
 
 /// Blockchain backend API
@@ -108,11 +109,13 @@ trait ChainBackend<Client, Block: BlockT>: Send + Sync + 'static
 		&self,
 		_metadata: crate::Metadata,
 		subscriber: Subscriber<Block::Header>,
+		max_buffered: Option<usize>,
 	) {
 		subscribe_headers(
 			self.client(),
 			self.subscriptions(),
 			subscriber,
+			max_buffered,
 			|| self.client().info().best_hash,
 			|| self.client().import_notification_stream()
 				.map(|notification| Ok::<_, ()>(notification.header))
@@ -134,11 +137,13 @@ trait ChainBackend<Client, Block: BlockT>: Send + Sync + 'static
 		&self,
 		_metadata: crate::Metadata,
 		subscriber: Subscriber<Block::Header>,
+		max_buffered: Option<usize>,
 	) {
 		subscribe_headers(
 			self.client(),
 			self.subscriptions(),
 			subscriber,
+			max_buffered,
 			|| self.client().info().best_hash,
 			|| self.client().import_notification_stream()
 				.filter(|notification| future::ready(notification.is_new_best))
@@ -161,11 +166,13 @@ trait ChainBackend<Client, Block: BlockT>: Send + Sync + 'static
 		&self,
 		_metadata: crate::Metadata,
 		subscriber: Subscriber<Block::Header>,
+		max_buffered: Option<usize>,
 	) {
 		subscribe_headers(
 			self.client(),
 			self.subscriptions(),
 			subscriber,
+			max_buffered,
 			|| self.client().info().finalized_hash,
 			|| self.client().finality_notification_stream()
 				.map(|notification| Ok::<_, ()>(notification.header))
@@ -194,6 +201,7 @@ pub fn new_full<Block: BlockT, Client>(
 {
 	Chain {
 		backend: Box::new(self::chain_full::FullChain::new(client, subscriptions)),
+		max_buffered_notifications: None,
 	}
 }
 
@@ -216,12 +224,26 @@ pub fn new_light<Block: BlockT, Client, F: Fetcher<Block>>(
 			remote_blockchain,
 			fetcher,
 		)),
+		max_buffered_notifications: None,
 	}
 }
 
 /// Chain API with subscriptions support.
 pub struct Chain<Block: BlockT, Client> {
 	backend: Box<dyn ChainBackend<Client, Block>>,
+	max_buffered_notifications: Option<usize>,
+}
+
+impl<Block: BlockT, Client> Chain<Block, Client> {
+	/// Drop the head subscriptions having more than `max` notifications waiting to be read by
+	/// the subscriber, instead of buffering them indefinitely.
+	///
+	/// The dropped subscribers get an error notification, and are counted by the
+	/// `rpc_subscriptions_dropped_total` metric. `None`, the default, sets no limit.
+	pub fn with_max_buffered_notifications(mut self, max: Option<usize>) -> Self {
+		self.max_buffered_notifications = max;
+		self
+	}
 }
 
 impl<Block, Client> ChainApi<NumberFor<Block>, Block::Hash, Block::Header, SignedBlock<Block>> for
@@ -261,7 +283,7 @@ impl<Block, Client> ChainApi<NumberFor<Block>, Block::Hash, Block::Header, Signe
 	}
 
 	fn subscribe_all_heads(&self, metadata: Self::Metadata, subscriber: Subscriber<Block::Header>) {
-		self.backend.subscribe_all_heads(metadata, subscriber)
+		self.backend.subscribe_all_heads(metadata, subscriber, self.max_buffered_notifications)
 	}
 
 	fn unsubscribe_all_heads(&self, metadata: Option<Self::Metadata>, id: SubscriptionId) -> RpcResult<bool> {
@@ -269,7 +291,7 @@ impl<Block, Client> ChainApi<NumberFor<Block>, Block::Hash, Block::Header, Signe
 	}
 
 	fn subscribe_new_heads(&self, metadata: Self::Metadata, subscriber: Subscriber<Block::Header>) {
-		self.backend.subscribe_new_heads(metadata, subscriber)
+		self.backend.subscribe_new_heads(metadata, subscriber, self.max_buffered_notifications)
 	}
 
 	fn unsubscribe_new_heads(&self, metadata: Option<Self::Metadata>, id: SubscriptionId) -> RpcResult<bool> {
@@ -277,7 +299,7 @@ impl<Block, Client> ChainApi<NumberFor<Block>, Block::Hash, Block::Header, Signe
 	}
 
 	fn subscribe_finalized_heads(&self, metadata: Self::Metadata, subscriber: Subscriber<Block::Header>) {
-		self.backend.subscribe_finalized_heads(metadata, subscriber)
+		self.backend.subscribe_finalized_heads(metadata, subscriber, self.max_buffered_notifications)
 	}
 
 	fn unsubscribe_finalized_heads(&self, metadata: Option<Self::Metadata>, id: SubscriptionId) -> RpcResult<bool> {
@@ -290,6 +312,7 @@ fn subscribe_headers<Block, Client, F, G, S, ERR>(
 	client: &Arc<Client>,
 	subscriptions: &SubscriptionManager,
 	subscriber: Subscriber<Block::Header>,
+	max_buffered: Option<usize>,
 	best_block_hash: G,
 	stream: F,
 ) where
@@ -314,17 +337,97 @@ fn subscribe_headers<Block, Client, F, G, S, ERR>(
 			.map(|res| Ok(res))
 			.map_err(|e| warn!("Block notification stream error: {:?}", e));
 
-		sink
-			.sink_map_err(|e| warn!("Error sending notifications: {:?}", e))
-			.send_all(
-				stream::iter_result(vec![Ok(header)])
-					.chain(stream)
-			)
-			// we ignore the resulting Stream (if the first stream is over we are unsubscribed)
-			.map(|_| ())
+		let stream = stream::iter_result(vec![Ok(header)]).chain(stream);
+		match max_buffered {
+			Some(max_buffered) => Either::A(BoundedForward {
+				sink,
+				stream: Some(stream),
+				buffer: VecDeque::new(),
+				max_buffered,
+			}),
+			None => Either::B(sink
+				.sink_map_err(|e| warn!("Error sending notifications: {:?}", e))
+				.send_all(stream)
+				// we ignore the resulting Stream (if the first stream is over we are unsubscribed)
+				.map(|_| ())
+			),
+		}
 	});
 }
 
+/// Sends the notifications of `stream` to `sink`, buffering at most `max_buffered` of them while
+/// the subscriber is slower than the stream.
+///
+/// Once the buffer is full, the pending notifications are discarded, and the subscriber is sent
+/// an error with the reason before the subscription ends.
+struct BoundedForward<K: Sink, S> {
+	sink: K,
+	/// `None` once the stream is over or the subscriber is dropped.
+	stream: Option<S>,
+	buffer: VecDeque<K::SinkItem>,
+	max_buffered: usize,
+}
+
+impl<K: Sink, S> BoundedForward<K, S> {
+	/// Send the buffered notifications until the subscriber can't take more.
+	fn send_buffered(&mut self) -> std::result::Result<(), K::SinkError> {
+		while let Some(item) = self.buffer.pop_front() {
+			if let AsyncSink::NotReady(item) = self.sink.start_send(item)? {
+				self.buffer.push_front(item);
+				break;
+			}
+		}
+		Ok(())
+	}
+}
+
+impl<K, S, T> Future for BoundedForward<K, S> where
+	K: Sink<SinkItem = std::result::Result<T, rpc::Error>>,
+	K::SinkError: std::fmt::Debug,
+	S: Stream<Item = std::result::Result<T, rpc::Error>, Error = ()>,
+{
+	type Item = ();
+	type Error = ();
+
+	fn poll(&mut self) -> Poll<(), ()> {
+		// Take the notifications as they come, whether the subscriber can receive them or not.
+		while let Some(stream) = self.stream.as_mut() {
+			match stream.poll() {
+				Ok(Async::Ready(Some(item))) => self.buffer.push_back(item),
+				Ok(Async::Ready(None)) | Err(()) => self.stream = None,
+				Ok(Async::NotReady) => break,
+			}
+
+			if let Err(e) = self.send_buffered() {
+				warn!("Error sending notifications: {:?}", e);
+				return Ok(Async::Ready(()));
+			}
+			if self.buffer.len() > self.max_buffered {
+				warn!("Dropping a subscriber too slow to read its notifications");
+				RPC_SUBSCRIPTIONS_DROPPED_TOTAL.inc();
+				self.buffer.clear();
+				self.buffer.push_back(Err(Error::SubscriptionDropped(self.max_buffered).into()));
+				self.stream = None;
+			}
+		}
+
+		if let Err(e) = self.send_buffered() {
+			warn!("Error sending notifications: {:?}", e);
+			return Ok(Async::Ready(()));
+		}
+
+		match self.sink.poll_complete() {
+			Ok(Async::Ready(())) if self.stream.is_none() && self.buffer.is_empty() =>
+				Ok(Async::Ready(())),
+			Ok(_) => Ok(Async::NotReady),
+			Err(e) => {
+				warn!("Error sending notifications: {:?}", e);
+				Ok(Async::Ready(()))
+			},
+		}
+	}
+}
+
 fn client_err(err: sp_blockchain::Error) -> Error {
 	Error::Client(Box::new(err))
 }
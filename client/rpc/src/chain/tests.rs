@@ -272,3 +272,30 @@ fn should_notify_about_finalized_block() {
 	// no more notifications on this channel
 	assert_eq!(executor::block_on(next.into_future().compat()).unwrap().0, None);
 }
+
+#[test]
+fn should_drop_slow_subscriber() {
+	let (subscriber, id, transport) = Subscriber::new_test("test");
+	let mut client = Arc::new(substrate_test_runtime_client::new());
+	let api = new_full(client.clone(), SubscriptionManager::new(Arc::new(TaskExecutor)))
+		.with_max_buffered_notifications(Some(1));
+
+	api.subscribe_all_heads(Default::default(), subscriber);
+
+	// assert id assigned
+	assert!(matches!(
+		executor::block_on(id.compat()),
+		Ok(Ok(SubscriptionId::String(_)))
+	));
+
+	// import more blocks than the transport and the buffer can hold, without reading them
+	for _ in 0..64 {
+		let block = client.new_block(Default::default()).unwrap().build().unwrap().block;
+		client.import(BlockOrigin::Own, block).unwrap();
+	}
+
+	// the subscription ends with the reason it was dropped
+	let notifications = executor::block_on(Stream01CompatExt::compat(transport).collect::<Vec<_>>());
+	assert!(notifications.len() < 65);
+	assert!(notifications.last().unwrap().as_ref().unwrap().contains("Subscription dropped"));
+}
@@ -69,6 +69,15 @@ pub enum Request<B: traits::Block> {
 	NodeRoles(oneshot::Sender<Vec<NodeRole>>),
 	/// Must return the state of the node syncing.
 	SyncState(oneshot::Sender<SyncState<<B::Header as HeaderT>::Number>>),
+	/// Must initiate the shutdown of the node before a restart, and answer once it has.
+	PrepareRestart(oneshot::Sender<()>),
+	/// Must replace the node key by a new one, provided the given confirmation is the current
+	/// base58-encoded `PeerId`. Must return the `PeerId` of the new key.
+	RotateNodeKey(String, oneshot::Sender<Result<String>>),
+	/// Must stop the authoring of blocks. Must return `false` if it was already stopped.
+	StopAuthoring(oneshot::Sender<Result<bool>>),
+	/// Must start the authoring of blocks again. Must return `false` if it wasn't stopped.
+	StartAuthoring(oneshot::Sender<Result<bool>>),
 }
 
 impl<B: traits::Block> System<B> {
@@ -208,4 +217,64 @@ impl<B: traits::Block> SystemApi<B::Hash, <B::Header as HeaderT>::Number> for Sy
 		self.deny_unsafe.check_if_safe()?;
 		sc_tracing::reset_log_filter().map_err(|_e| rpc::Error::internal_error())
 	}
+
+	fn system_prepare_restart(&self)
+		-> Compat<BoxFuture<'static, std::result::Result<(), rpc::Error>>>
+	{
+		bail_if_unsafe!(self.deny_unsafe);
+
+		let (tx, rx) = oneshot::channel();
+		let _ = self.send_back.unbounded_send(Request::PrepareRestart(tx));
+		async move {
+			rx.await.map_err(|_| rpc::Error::internal_error())
+		}.boxed().compat()
+	}
+
+	fn system_rotate_node_key(&self, confirm: String)
+		-> Compat<BoxFuture<'static, std::result::Result<String, rpc::Error>>>
+	{
+		bail_if_unsafe!(self.deny_unsafe);
+
+		let (tx, rx) = oneshot::channel();
+		let _ = self.send_back.unbounded_send(Request::RotateNodeKey(confirm, tx));
+		async move {
+			match rx.await {
+				Ok(Ok(peer_id)) => Ok(peer_id),
+				Ok(Err(e)) => Err(rpc::Error::from(e)),
+				Err(_) => Err(rpc::Error::internal_error()),
+			}
+		}.boxed().compat()
+	}
+
+	fn system_stop_authoring(&self)
+		-> Compat<BoxFuture<'static, std::result::Result<bool, rpc::Error>>>
+	{
+		bail_if_unsafe!(self.deny_unsafe);
+
+		let (tx, rx) = oneshot::channel();
+		let _ = self.send_back.unbounded_send(Request::StopAuthoring(tx));
+		authoring_switched(rx)
+	}
+
+	fn system_start_authoring(&self)
+		-> Compat<BoxFuture<'static, std::result::Result<bool, rpc::Error>>>
+	{
+		bail_if_unsafe!(self.deny_unsafe);
+
+		let (tx, rx) = oneshot::channel();
+		let _ = self.send_back.unbounded_send(Request::StartAuthoring(tx));
+		authoring_switched(rx)
+	}
+}
+
+fn authoring_switched(
+	rx: oneshot::Receiver<Result<bool>>,
+) -> Compat<BoxFuture<'static, std::result::Result<bool, rpc::Error>>> {
+	async move {
+		match rx.await {
+			Ok(Ok(switched)) => Ok(switched),
+			Ok(Err(e)) => Err(rpc::Error::from(e)),
+			Err(_) => Err(rpc::Error::internal_error()),
+		}
+	}.boxed().compat()
 }
@@ -114,6 +114,22 @@ fn api<T: Into<Option<Status>>>(sync: T) -> System<Block> {
 						highest_block: Some(3),
 					});
 				}
+				Request::PrepareRestart(sender) => {
+					let _ = sender.send(());
+				}
+				Request::RotateNodeKey(confirm, sender) => {
+					let _ = sender.send(match confirm.as_str() {
+						"QmSk5HQbn6LhUwDiNMseVUjuRYhEtYj4aUZ6WfWoGURpdV" =>
+							Ok("QmXuczLg6MXs3gW4jxyLjFtqH7E8s5mBXx6UFriH6H8YEz".to_string()),
+						_ => Err(error::Error::NodeKeyNotRotated("wrong confirmation".into())),
+					});
+				}
+				Request::StopAuthoring(sender) => {
+					let _ = sender.send(Ok(true));
+				}
+				Request::StartAuthoring(sender) => {
+					let _ = sender.send(Err(error::Error::AuthoringNotSwitchable));
+				}
 			};
 
 			future::ready(())
@@ -325,6 +341,27 @@ fn system_network_add_reserved() {
 	assert!(runtime.block_on(bad_fut).is_err());
 }
 
+#[test]
+fn system_rotate_node_key() {
+	let mut runtime = tokio::runtime::current_thread::Runtime::new().unwrap();
+
+	let good_fut = api(None).system_rotate_node_key("QmSk5HQbn6LhUwDiNMseVUjuRYhEtYj4aUZ6WfWoGURpdV".into());
+	let bad_fut = api(None).system_rotate_node_key("QmXuczLg6MXs3gW4jxyLjFtqH7E8s5mBXx6UFriH6H8YEz".into());
+	assert_eq!(
+		runtime.block_on(good_fut),
+		Ok("QmXuczLg6MXs3gW4jxyLjFtqH7E8s5mBXx6UFriH6H8YEz".to_string()),
+	);
+	assert!(runtime.block_on(bad_fut).is_err());
+}
+
+#[test]
+fn system_stop_and_start_authoring() {
+	let mut runtime = tokio::runtime::current_thread::Runtime::new().unwrap();
+
+	assert_eq!(runtime.block_on(api(None).system_stop_authoring()), Ok(true));
+	assert!(runtime.block_on(api(None).system_start_authoring()).is_err());
+}
+
 #[test]
 fn system_network_remove_reserved() {
 	let good_peer_id = "QmSk5HQbn6LhUwDiNMseVUjuRYhEtYj4aUZ6WfWoGURpdV";
@@ -344,7 +381,14 @@ fn test_add_reset_log_filter() {
 
 	// Enter log generation / filter reload
 	if std::env::var("TEST_LOG_FILTER").is_ok() {
-		sc_cli::init_logger("test_before_add=debug", Default::default(), Default::default(), false).unwrap();
+		sc_cli::init_logger(
+			"test_before_add=debug",
+			Default::default(),
+			Default::default(),
+			false,
+			None,
+			None,
+		).unwrap();
 		for line in std::io::stdin().lock().lines() {
 			let line = line.expect("Failed to read bytes");
 			if line.contains("add_reload") {
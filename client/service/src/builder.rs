@@ -20,6 +20,7 @@ use crate::{
 	error::Error, DEFAULT_PROTOCOL_ID, MallocSizeOfWasm,
 	TelemetryConnectionSinks, RpcHandlers, NetworkStatusSinks,
 	start_rpc_servers, build_network_future, TransactionPoolAdapter, TaskManager, SpawnTaskHandle,
+	RestartHandle,
 	metrics::MetricsService,
 	client::{light, Client, ClientConfig},
 	config::{Configuration, KeystoreConfig, PrometheusConfig},
@@ -35,21 +36,21 @@ use sp_consensus::{
 };
 use jsonrpc_pubsub::manager::SubscriptionManager;
 use futures::{
-	FutureExt, StreamExt,
+	FutureExt, Stream, StreamExt,
 	future::ready,
 	channel::oneshot,
 };
 use sc_keystore::LocalKeystore;
 use log::{info, warn};
-use sc_network::config::{Role, OnDemand};
-use sc_network::NetworkService;
+use sc_network::config::{NodeKeyConfig, Role, OnDemand};
+use sc_network::{NetworkService, NetworkStatus, PeerId, SyncState};
 use sp_runtime::generic::BlockId;
 use sp_runtime::traits::{
 	Block as BlockT, SaturatedConversion, HashFor, Zero, BlockIdTo,
 };
 use sp_api::{ProvideRuntimeApi, CallApiAt};
 use sc_executor::{NativeExecutor, NativeExecutionDispatch, RuntimeInfo};
-use std::sync::Arc;
+use std::{sync::{Arc, atomic::{AtomicBool, Ordering}}, time::Duration};
 use wasm_timer::SystemTime;
 use sc_telemetry::{telemetry, SUBSTRATE_INFO};
 use sp_transaction_pool::MaintainedTransactionPool;
@@ -65,7 +66,7 @@ use sc_client_api::{
 	BlockBackend, BlockchainEvents,
 	backend::StorageProvider,
 	proof_provider::ProofProvider,
-	execution_extensions::ExecutionExtensions
+	execution_extensions::{ExecutionExtensions, ExtensionsFactory},
 };
 use sp_blockchain::{HeaderMetadata, HeaderBackend};
 
@@ -265,18 +266,52 @@ pub fn new_full_client<TBl, TRtApi, TExecDisp>(
 	new_full_parts(config).map(|parts| parts.0)
 }
 
+/// Creates a new full client for the given config, registering the extensions produced by
+/// `extensions_factory` on every runtime call.
+///
+/// See [`new_full_parts_with_extensions`] for the requirements on the factory.
+pub fn new_full_client_with_extensions<TBl, TRtApi, TExecDisp>(
+	config: &Configuration,
+	extensions_factory: Box<dyn ExtensionsFactory>,
+) -> Result<TFullClient<TBl, TRtApi, TExecDisp>, Error> where
+	TBl: BlockT,
+	TExecDisp: NativeExecutionDispatch + 'static,
+{
+	new_full_parts_with_extensions(config, extensions_factory).map(|parts| parts.0)
+}
+
 /// Create the initial parts of a full node.
 pub fn new_full_parts<TBl, TRtApi, TExecDisp>(
 	config: &Configuration,
 ) -> Result<TFullParts<TBl, TRtApi, TExecDisp>,	Error> where
 	TBl: BlockT,
 	TExecDisp: NativeExecutionDispatch + 'static,
+{
+	new_full_parts_with_extensions(config, Box::new(()))
+}
+
+/// Create the initial parts of a full node, registering the extensions produced by
+/// `extensions_factory` on every runtime call.
+///
+/// This lets subcommands re-executing blocks, such as tracing or metering tools, provide their
+/// own host extensions. The factory is owned by the client and lives as long as it does, and it
+/// must be `Send + Sync` because it is called concurrently from every thread calling into the
+/// runtime. It is called once per call, so it should be cheap and return fresh extensions each
+/// time; state shared between calls has to be synchronized by the extensions themselves. The
+/// keystore and transaction pool extensions are still registered on top of the ones it returns.
+pub fn new_full_parts_with_extensions<TBl, TRtApi, TExecDisp>(
+	config: &Configuration,
+	extensions_factory: Box<dyn ExtensionsFactory>,
+) -> Result<TFullParts<TBl, TRtApi, TExecDisp>,	Error> where
+	TBl: BlockT,
+	TExecDisp: NativeExecutionDispatch + 'static,
 {
 	let keystore_container = KeystoreContainer::new(&config.keystore)?;
 
 	let task_manager = {
 		let registry = config.prometheus_config.as_ref().map(|cfg| &cfg.registry);
 		TaskManager::new(config.task_executor.clone(), registry)?
+			.with_task_filter(config.task_filter.clone())
 	};
 
 	let executor = NativeExecutor::<TExecDisp>::new(
@@ -307,6 +342,7 @@ pub fn new_full_parts<TBl, TRtApi, TExecDisp>(
 			config.execution_strategies.clone(),
 			Some(keystore_container.sync_keystore()),
 		);
+		extensions.set_extensions_factory(extensions_factory);
 
 		new_client(
 			db_config,
@@ -344,6 +380,7 @@ pub fn new_light_parts<TBl, TRtApi, TExecDisp>(
 	let task_manager = {
 		let registry = config.prometheus_config.as_ref().map(|cfg| &cfg.registry);
 		TaskManager::new(config.task_executor.clone(), registry)?
+			.with_task_filter(config.task_filter.clone())
 	};
 
 	let executor = NativeExecutor::<TExecDisp>::new(
@@ -457,6 +494,9 @@ pub struct SpawnTasksParams<'a, TBl: BlockT, TCl, TExPool, TRpc, Backend> {
 	pub system_rpc_tx: TracingUnboundedSender<sc_rpc::system::Request<TBl>>,
 	/// Shared Telemetry connection sinks,
 	pub telemetry_connection_sinks: TelemetryConnectionSinks,
+	/// An optional switch pausing the authoring of the node, turned by the
+	/// `system_stopAuthoring` and `system_startAuthoring` RPCs.
+	pub authoring_switch: Option<sp_consensus::AuthoringSwitch>,
 }
 
 /// Build a shared offchain workers instance.
@@ -537,6 +577,7 @@ pub fn spawn_tasks<TBl, TBackend, TExPool, TRpc, TCl>(
 		network_status_sinks,
 		system_rpc_tx,
 		telemetry_connection_sinks,
+		authoring_switch,
 	} = params;
 
 	let chain_info = client.usage_info().chain;
@@ -556,6 +597,13 @@ pub fn spawn_tasks<TBl, TBackend, TExPool, TRpc, TCl>(
 	);
 
 	let spawn_handle = task_manager.spawn_handle();
+	task_manager.set_best_block({
+		let client = client.clone();
+		move || {
+			let info = client.usage_info().chain;
+			format!("#{} ({:?})", info.best_number, info.best_hash)
+		}
+	});
 
 	// Inform the tx pool about imported and finalized blocks.
 	spawn_handle.spawn(
@@ -593,18 +641,52 @@ pub fn spawn_tasks<TBl, TBackend, TExPool, TRpc, TCl>(
 		)
 	);
 
+	// Lifecycle requests of the system RPC are answered here, the other ones are forwarded to the
+	// network worker.
+	let system_rpc_tx = lifecycle_requests(
+		system_rpc_tx,
+		task_manager.restart_handle(),
+		config.network.node_key.clone(),
+		network.local_peer_id().clone(),
+		authoring_switch,
+		&spawn_handle,
+	);
+
 	// RPC
+	let ws_sessions = sc_rpc_server::WsSessions::default();
 	let gen_handler = |
 		deny_unsafe: sc_rpc::DenyUnsafe,
 		rpc_middleware: sc_rpc_server::RpcMiddleware
-	| gen_handler(
-		deny_unsafe, rpc_middleware, &config, task_manager.spawn_handle(),
-		client.clone(), transaction_pool.clone(), keystore.clone(),
-		on_demand.clone(), remote_blockchain.clone(), &*rpc_extensions_builder,
-		backend.offchain_storage(), system_rpc_tx.clone()
-	);
+	| {
+		let mut handler = gen_handler(
+			deny_unsafe, rpc_middleware, &config, task_manager.spawn_handle(),
+			client.clone(), transaction_pool.clone(), keystore.clone(),
+			on_demand.clone(), remote_blockchain.clone(), &*rpc_extensions_builder,
+			backend.offchain_storage(), system_rpc_tx.clone()
+		);
+		if let sc_rpc::DenyUnsafe::No = deny_unsafe {
+			sc_rpc_server::add_session_methods(&mut handler, ws_sessions.clone());
+		}
+		handler
+	};
 	let rpc_metrics = sc_rpc_server::RpcMetrics::new(config.prometheus_registry())?;
-	let rpc = start_rpc_servers(&config, gen_handler, rpc_metrics.clone())?;
+	let rpc_syncing = if config.rpc_wait_for_sync {
+		let syncing = Arc::new(AtomicBool::new(true));
+		spawn_handle.spawn(
+			"rpc-wait-for-sync",
+			wait_for_sync(network_status_sinks.status_stream(Duration::from_secs(1)), syncing.clone()),
+		);
+		Some(syncing)
+	} else {
+		None
+	};
+	let rpc = start_rpc_servers(
+		&config,
+		gen_handler,
+		rpc_metrics.clone(),
+		rpc_syncing,
+		ws_sessions.clone(),
+	)?;
 	// This is used internally, so don't restrict access to unsafe RPC
 	let rpc_handlers = RpcHandlers(Arc::new(gen_handler(
 		sc_rpc::DenyUnsafe::No,
@@ -642,6 +724,21 @@ pub fn spawn_tasks<TBl, TBackend, TExPool, TRpc, TCl>(
 	Ok(rpc_handlers)
 }
 
+/// Clear `syncing` once the node has caught up with the network, according to `network_status`.
+async fn wait_for_sync<TBl: BlockT>(
+	mut network_status: impl Stream<Item = NetworkStatus<TBl>> + Unpin,
+	syncing: Arc<AtomicBool>,
+) {
+	info!("⏳ Rejecting RPC calls until the node is synced");
+	while let Some(status) = network_status.next().await {
+		if status.sync_state == SyncState::Idle && status.best_seen_block.is_some() {
+			info!("✅ Node is synced, accepting RPC calls");
+			syncing.store(false, Ordering::Relaxed);
+			return;
+		}
+	}
+}
+
 async fn transaction_notifications<TBl, TExPool>(
 	transaction_pool: Arc<TExPool>,
 	network: Arc<NetworkService<TBl, <TBl as BlockT>::Hash>>
@@ -664,6 +761,101 @@ async fn transaction_notifications<TBl, TExPool>(
 		.await;
 }
 
+fn lifecycle_requests<TBl: BlockT>(
+	network_rpc_tx: TracingUnboundedSender<sc_rpc::system::Request<TBl>>,
+	restart_handle: RestartHandle,
+	node_key: NodeKeyConfig,
+	local_peer_id: PeerId,
+	authoring_switch: Option<sp_consensus::AuthoringSwitch>,
+	spawn_handle: &SpawnTaskHandle,
+) -> TracingUnboundedSender<sc_rpc::system::Request<TBl>> {
+	let (tx, mut rx) = tracing_unbounded("mpsc_system_rpc_lifecycle");
+
+	spawn_handle.spawn("system-rpc-lifecycle", async move {
+		while let Some(request) = rx.next().await {
+			match request {
+				sc_rpc::system::Request::PrepareRestart(sender) => {
+					info!("🔁 Restart requested, shutting down");
+					telemetry!(SUBSTRATE_INFO; "node.prepare_restart");
+					let _ = sender.send(());
+					restart_handle.request_restart();
+				},
+				sc_rpc::system::Request::RotateNodeKey(confirm, sender) => {
+					let _ = sender.send(rotate_node_key(&node_key, &local_peer_id, &confirm));
+				},
+				sc_rpc::system::Request::StopAuthoring(sender) => {
+					let _ = sender.send(switch_authoring(authoring_switch.as_ref(), false));
+				},
+				sc_rpc::system::Request::StartAuthoring(sender) => {
+					let _ = sender.send(switch_authoring(authoring_switch.as_ref(), true));
+				},
+				request => {
+					let _ = network_rpc_tx.unbounded_send(request);
+				},
+			}
+		}
+	});
+
+	tx
+}
+
+/// Pause or resume authoring through `switch`, returning whether it was switched.
+fn switch_authoring(
+	switch: Option<&sp_consensus::AuthoringSwitch>,
+	author: bool,
+) -> sc_rpc::system::error::Result<bool> {
+	let switch = switch.ok_or(sc_rpc::system::error::Error::AuthoringNotSwitchable)?;
+
+	let switched = if author { switch.resume() } else { switch.pause() };
+	match (switched, author) {
+		(false, _) => {},
+		(true, true) => {
+			info!("✍️  Authoring started");
+			telemetry!(SUBSTRATE_INFO; "node.authoring_started");
+		},
+		(true, false) => {
+			info!("⏸  Authoring stopped");
+			telemetry!(SUBSTRATE_INFO; "node.authoring_stopped");
+		},
+	}
+
+	Ok(switched)
+}
+
+/// Replace the node key by a new one, provided `confirm` is the current peer id of the node.
+///
+/// The network worker can't change its identity while running, so the node keeps its current
+/// peer id until it is next restarted, which is left to the operator.
+fn rotate_node_key(
+	node_key: &NodeKeyConfig,
+	local_peer_id: &PeerId,
+	confirm: &str,
+) -> sc_rpc::system::error::Result<String> {
+	use sc_rpc::system::error::Error;
+
+	if confirm != local_peer_id.to_base58() {
+		return Err(Error::NodeKeyNotRotated(
+			"the confirmation must be the current peer id of the node".into(),
+		));
+	}
+
+	let keypair = node_key.rotate().map_err(|e| Error::NodeKeyNotRotated(e.to_string()))?;
+	let new_peer_id = keypair.public().into_peer_id();
+	info!(
+		"🔑 Node key rotated, peer id {} becomes {} once the node is restarted",
+		local_peer_id,
+		new_peer_id,
+	);
+	telemetry!(
+		SUBSTRATE_INFO;
+		"node.rotate_node_key";
+		"old_peer_id" => local_peer_id.to_base58(),
+		"new_peer_id" => new_peer_id.to_base58()
+	);
+
+	Ok(new_peer_id.to_base58())
+}
+
 fn build_telemetry<TBl: BlockT>(
 	config: &mut Configuration,
 	endpoints: sc_telemetry::TelemetryEndpoints,
@@ -763,7 +955,7 @@ fn gen_handler<TBl, TBackend, TExPool, TRpc, TCl>(
 			subscriptions.clone(),
 			remote_blockchain.clone(),
 			on_demand.clone(),
-		);
+		).with_max_buffered_notifications(config.rpc_max_buffered_notifications);
 		let (state, child_state) = sc_rpc::state::new_light(
 			client.clone(),
 			subscriptions.clone(),
@@ -775,7 +967,8 @@ fn gen_handler<TBl, TBackend, TExPool, TRpc, TCl>(
 
 	} else {
 		// Full nodes
-		let chain = sc_rpc::chain::new_full(client.clone(), subscriptions.clone());
+		let chain = sc_rpc::chain::new_full(client.clone(), subscriptions.clone())
+			.with_max_buffered_notifications(config.rpc_max_buffered_notifications);
 		let (state, child_state) = sc_rpc::state::new_full(
 			client.clone(),
 			subscriptions.clone(),
@@ -969,3 +1162,32 @@ impl NetworkStarter {
 		let _ = self.0.send(());
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sc_network::config::Secret;
+
+	#[test]
+	fn rotate_node_key_replaces_the_key_file() {
+		let dir = tempfile::tempdir().unwrap();
+		let node_key = NodeKeyConfig::Ed25519(Secret::File(dir.path().join("secret_ed25519")));
+		let peer_id = node_key.clone().into_keypair().unwrap().public().into_peer_id();
+
+		assert!(rotate_node_key(&node_key, &peer_id, "not the peer id").is_err());
+		assert_eq!(node_key.clone().into_keypair().unwrap().public().into_peer_id(), peer_id);
+
+		let new_peer_id = rotate_node_key(&node_key, &peer_id, &peer_id.to_base58()).unwrap();
+		let loaded = node_key.into_keypair().unwrap().public().into_peer_id();
+		assert_ne!(new_peer_id, peer_id.to_base58());
+		assert_eq!(loaded.to_base58(), new_peer_id);
+	}
+
+	#[test]
+	fn rotate_node_key_needs_a_key_file() {
+		let node_key = NodeKeyConfig::Ed25519(Secret::New);
+		let peer_id = PeerId::random();
+
+		assert!(rotate_node_key(&node_key, &peer_id, &peer_id.to_base58()).is_err());
+	}
+}
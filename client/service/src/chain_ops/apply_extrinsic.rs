@@ -0,0 +1,85 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+
+use crate::error::Error;
+use sp_api::{ApiExt, Core, ProvideRuntimeApi};
+use sp_block_builder::BlockBuilder;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{ApplyExtrinsicResult, generic::BlockId};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, One};
+use sc_client_api::backend::Backend;
+use std::collections::HashMap;
+
+/// The outcome of applying an extrinsic on top of the state of a block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AppliedExtrinsic {
+	/// What the runtime returned when applying the extrinsic.
+	pub result: ApplyExtrinsicResult,
+	/// The storage keys whose value the extrinsic changed, with their new value, `None` if the
+	/// key was deleted.
+	pub changes: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+}
+
+/// Apply `extrinsic` alone in a new block built on top of `at`, and report what it changed.
+///
+/// The new block is initialized with an empty digest, and nothing is written to the database.
+/// The writes of the block initialization itself are left out of the reported changes.
+pub fn apply_extrinsic<B, BA, C>(
+	client: &C,
+	backend: &BA,
+	at: B::Hash,
+	extrinsic: B::Extrinsic,
+) -> Result<AppliedExtrinsic, Error>
+where
+	B: BlockT,
+	BA: Backend<B>,
+	C: HeaderBackend<B> + ProvideRuntimeApi<B>,
+	C::Api: Core<B, Error = sp_blockchain::Error>
+		+ BlockBuilder<B, Error = sp_blockchain::Error>
+		+ ApiExt<B, StateBackend = BA::State>,
+{
+	let at_id = BlockId::Hash(at);
+	let parent = client.header(at_id)?.ok_or_else(|| format!("Block {} not found", at))?;
+	let header = <B::Header as HeaderT>::new(
+		*parent.number() + One::one(),
+		Default::default(),
+		Default::default(),
+		at,
+		Default::default(),
+	);
+	let state = backend.state_at(at_id)?;
+
+	let api = client.runtime_api();
+	api.initialize_block(&at_id, &header)?;
+	let initialized = api.into_storage_changes(&state, None, at)
+		.map_err(sp_blockchain::Error::Storage)?
+		.main_storage_changes
+		.into_iter()
+		.collect::<HashMap<_, _>>();
+
+	let api = client.runtime_api();
+	api.initialize_block(&at_id, &header)?;
+	let result = api.apply_extrinsic(&at_id, extrinsic)?;
+	let changes = api.into_storage_changes(&state, None, at)
+		.map_err(sp_blockchain::Error::Storage)?
+		.main_storage_changes
+		.into_iter()
+		.filter(|(key, value)| initialized.get(key) != Some(value))
+		.collect();
+
+	Ok(AppliedExtrinsic { result, changes })
+}
@@ -43,7 +43,7 @@ where
 			1u64.encode_to(&mut buf);
 			block.encode_to(&mut buf);
 			let reader = std::io::Cursor::new(buf);
-			import_blocks(client, import_queue, reader, true, true)
+			import_blocks(client, import_queue, reader, true, true, false)
 		}
 		Ok(None) => Box::pin(future::err("Unknown block".into())),
 		Err(e) => Box::pin(future::err(format!("Error reading block: {:?}", e).into())),
@@ -0,0 +1,109 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+
+use crate::error::Error;
+use sp_blockchain::{Backend as _, HeaderBackend};
+use sp_runtime::{Justification, generic::BlockId};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, NumberFor, One, Saturating, Zero};
+use sc_client_api::backend::Backend;
+use std::collections::{BTreeMap, HashSet};
+
+/// What an authority signed twice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EquivocationKind {
+	/// Two blocks authored for the same slot.
+	Authorship,
+	/// Two finality votes for different blocks in the same round.
+	Finality,
+}
+
+/// Two or more conflicting messages signed by the same authority.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Equivocation<B: BlockT> {
+	/// What was signed twice.
+	pub kind: EquivocationKind,
+	/// Encoded public key of the offender.
+	pub offender: Vec<u8>,
+	/// Slot of the blocks, or round of the votes.
+	pub slot_or_round: u64,
+	/// Hashes of the conflicting blocks, or targets of the conflicting votes.
+	pub hashes: Vec<B::Hash>,
+}
+
+/// Look for equivocations in the last `window` blocks known by `backend`.
+///
+/// Every fork within the window is walked, and `author` gives the slot and the author of each
+/// header, if any. Finality votes are only available through the justifications stored for the
+/// finalized blocks, which `votes` splits into `(round, voter, target)` triples.
+///
+/// Returns the equivocations ordered by slot or round.
+pub fn check_equivocations<B, BA>(
+	backend: &BA,
+	window: NumberFor<B>,
+	author: impl Fn(&B::Header) -> Result<Option<(u64, Vec<u8>)>, Error>,
+	votes: impl Fn(&Justification) -> Result<Vec<(u64, Vec<u8>, B::Hash)>, Error>,
+) -> Result<Vec<Equivocation<B>>, Error>
+where
+	B: BlockT,
+	BA: Backend<B>,
+{
+	let blockchain = backend.blockchain();
+	let info = blockchain.info();
+	let lowest = info.best_number.saturating_sub(window);
+
+	let mut authored = BTreeMap::<(u64, Vec<u8>), Vec<B::Hash>>::new();
+	let mut visited = HashSet::new();
+	for leaf in blockchain.leaves()? {
+		let mut hash = leaf;
+		while visited.insert(hash) {
+			let header = match blockchain.header(BlockId::Hash(hash))? {
+				Some(header) => header,
+				None => break,
+			};
+			if *header.number() < lowest || header.number().is_zero() {
+				break;
+			}
+			if let Some(key) = author(&header)? {
+				authored.entry(key).or_default().push(hash);
+			}
+			hash = *header.parent_hash();
+		}
+	}
+
+	let mut voted = BTreeMap::<(u64, Vec<u8>), Vec<B::Hash>>::new();
+	let mut number = lowest;
+	while number <= info.finalized_number {
+		if let Some(justification) = blockchain.justification(BlockId::Number(number))? {
+			for (round, voter, target) in votes(&justification)? {
+				let targets = voted.entry((round, voter)).or_default();
+				if !targets.contains(&target) {
+					targets.push(target);
+				}
+			}
+		}
+		number += One::one();
+	}
+
+	let equivocations = |kind, signed: BTreeMap<(u64, Vec<u8>), Vec<B::Hash>>| signed.into_iter()
+		.filter(|(_, hashes)| hashes.len() > 1)
+		.map(move |((slot_or_round, offender), hashes)| Equivocation { kind, offender, slot_or_round, hashes })
+		.collect::<Vec<_>>();
+
+	let mut found = equivocations(EquivocationKind::Authorship, authored);
+	found.extend(equivocations(EquivocationKind::Finality, voted));
+	Ok(found)
+}
@@ -0,0 +1,86 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::error::Error;
+use sp_runtime::traits::Block as BlockT;
+use sp_runtime::generic::BlockId;
+use sp_core::storage::StorageKey;
+use sc_client_api::StorageProvider;
+
+use std::{cmp::Ordering, sync::Arc};
+
+/// A difference found between two states.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StateDiff {
+	/// The key only exists in the first state.
+	OnlyLeft(StorageKey),
+	/// The key only exists in the second state.
+	OnlyRight(StorageKey),
+	/// The key exists in both states but with different values.
+	Value(StorageKey),
+}
+
+/// Compare the top-level state of `left` and `right` at `block`.
+///
+/// Both key sets are walked in order side by side, so only one key of each state is kept in
+/// memory at any time. Every difference is reported to `on_diff` as soon as it is found. Child
+/// tries are only compared through their roots, which are stored in the top-level state.
+///
+/// Returns the total number of differences.
+pub fn diff_state<B, BA, C>(
+	left: Arc<C>,
+	right: Arc<C>,
+	block: BlockId<B>,
+	mut on_diff: impl FnMut(StateDiff) -> Result<(), Error>,
+) -> Result<u64, Error>
+where
+	C: StorageProvider<B, BA>,
+	B: BlockT,
+	BA: sc_client_api::backend::Backend<B>,
+{
+	let mut left_keys = left.storage_keys_iter(&block, None, None)?.peekable();
+	let mut right_keys = right.storage_keys_iter(&block, None, None)?.peekable();
+	let mut diffs = 0;
+
+	loop {
+		let diff = match (left_keys.peek(), right_keys.peek()) {
+			(None, None) => break,
+			(Some(_), None) => left_keys.next().map(StateDiff::OnlyLeft),
+			(None, Some(_)) => right_keys.next().map(StateDiff::OnlyRight),
+			(Some(l), Some(r)) => match l.cmp(r) {
+				Ordering::Less => left_keys.next().map(StateDiff::OnlyLeft),
+				Ordering::Greater => right_keys.next().map(StateDiff::OnlyRight),
+				Ordering::Equal => {
+					let key = left_keys.next().expect("peeked above; qed");
+					right_keys.next();
+
+					if left.storage(&block, &key)? != right.storage(&block, &key)? {
+						Some(StateDiff::Value(key))
+					} else {
+						None
+					}
+				},
+			},
+		};
+
+		if let Some(diff) = diff {
+			diffs += 1;
+			on_diff(diff)?;
+		}
+	}
+
+	Ok(diffs)
+}
@@ -85,12 +85,16 @@ where
 				}
 		},
 			// Reached end of the chain.
-			None => return Poll::Ready(Ok(())),
+			None => {
+				output.flush()?;
+				return Poll::Ready(Ok(()));
+			},
 		}
 		if (block % 10000u32.into()).is_zero() {
 			info!("#{}", block);
 		}
 		if block == last {
+			output.flush()?;
 			return Poll::Ready(Ok(()));
 		}
 		block += One::one();
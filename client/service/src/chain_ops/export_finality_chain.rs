@@ -0,0 +1,91 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+
+use crate::error::Error;
+use codec::Encode;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{Justification, generic::BlockId};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, NumberFor, One};
+use sc_client_api::BlockBackend;
+
+/// A finalized header a light client following the finality of the chain needs to know about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FinalityChainEntry<B: BlockT> {
+	/// The header.
+	pub header: B::Header,
+	/// Whether the header signals a change of the authority set.
+	pub signals_change: bool,
+	/// The justification stored for the block, if any.
+	pub justification: Option<Justification>,
+}
+
+impl<B: BlockT> FinalityChainEntry<B> {
+	/// Checksum of the entry, chained with the one of the entry before it: the blake2-256 hash of
+	/// `previous`, the encoded header and the justification.
+	///
+	/// Since each checksum depends on all the entries before it, the last one covers the whole
+	/// chain.
+	pub fn checksum(&self, previous: &[u8; 32]) -> [u8; 32] {
+		let mut data = previous.to_vec();
+		self.header.encode_to(&mut data);
+		if let Some(justification) = &self.justification {
+			data.extend_from_slice(justification);
+		}
+		sp_core::hashing::blake2_256(&data)
+	}
+}
+
+/// Walk the finalized headers of blocks `1..=to` and report to `on_entry` the ones for which
+/// `signals_change` returns `true` or that carry a justification.
+///
+/// Starting from the authority set of the genesis, this is enough for an external light client
+/// to verify every justification against the set it trusts, and move on to the next set as
+/// the changes get enacted.
+///
+/// Returns the number of reported entries.
+pub fn export_finality_chain<B, C>(
+	client: &C,
+	to: NumberFor<B>,
+	signals_change: impl Fn(&B::Header) -> bool,
+	mut on_entry: impl FnMut(FinalityChainEntry<B>) -> Result<(), Error>,
+) -> Result<u64, Error>
+where
+	B: BlockT,
+	C: BlockBackend<B> + HeaderBackend<B>,
+{
+	let finalized = client.info().finalized_number;
+	if to > finalized {
+		return Err(format!("Block #{} is not finalized, the last finalized block is #{}", to, finalized).into());
+	}
+
+	let mut number: NumberFor<B> = One::one();
+	let mut entries = 0;
+	while number <= to {
+		let id = BlockId::Number(number);
+		let header = client.header(id)?.ok_or_else(|| format!("Block #{} not found", number))?;
+		let justification = client.justification(&id)?;
+		let signals_change = signals_change(&header);
+
+		if signals_change || justification.is_some() {
+			on_entry(FinalityChainEntry { header, signals_change, justification })?;
+			entries += 1;
+		}
+		number += One::one();
+	}
+
+	Ok(entries)
+}
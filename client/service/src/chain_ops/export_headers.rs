@@ -0,0 +1,187 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Header-only chain export.
+//!
+//! The export is a plain sequence of entries, each entry being laid out as:
+//!
+//! - the SCALE `Compact<u32>` length of the payload,
+//! - the payload, i.e. the SCALE-encoded `(Header, Option<Justification>)` pair,
+//! - the 32 bytes blake2-256 checksum of the payload.
+//!
+//! There is no global header or trailer, which makes the format append-only: an interrupted
+//! export can be resumed by truncating the file after the last valid entry and continuing from
+//! the block that follows it (see [`last_exported_header`]).
+
+use crate::error::Error;
+use log::info;
+use futures::{future, prelude::*};
+use sp_runtime::traits::{
+	Block as BlockT, Header as HeaderT, NumberFor, One, Zero,
+};
+use sp_runtime::{generic::BlockId, Justification};
+use sp_core::hashing::blake2_256;
+use codec::{Compact, Decode, Encode};
+
+use std::{io::{Read, Write}, pin::Pin};
+use sc_client_api::{BlockBackend, UsageProvider};
+use sp_blockchain::HeaderBackend;
+use std::sync::Arc;
+use std::task::Poll;
+
+/// Length of the checksum appended to every exported header.
+const CHECKSUM_LEN: usize = 32;
+
+/// Maximum length of an entry, header and justification included. Any longer length can only
+/// come from a corrupted entry.
+const MAX_ENTRY_LEN: usize = 16 * 1024 * 1024;
+
+/// Performs the headers export.
+///
+/// Justifications are only written for finalized blocks and only if `justifications` is `true`.
+pub fn export_headers<B, C>(
+	client: Arc<C>,
+	mut output: impl Write + 'static,
+	from: NumberFor<B>,
+	to: Option<NumberFor<B>>,
+	justifications: bool,
+) -> Pin<Box<dyn Future<Output = Result<(), Error>>>>
+where
+	C: HeaderBackend<B> + BlockBackend<B> + UsageProvider<B> + 'static,
+	B: BlockT,
+{
+	let mut block = from;
+
+	let last = match to {
+		Some(v) if v.is_zero() => One::one(),
+		Some(v) => v,
+		None => client.usage_info().chain.best_number,
+	};
+
+	let mut logged_range = false;
+
+	// Same as for `export_blocks`, the export re-schedules itself after every header so that it
+	// stays interruptible.
+	let export = future::poll_fn(move |cx| {
+		let client = &client;
+
+		if last < block {
+			return Poll::Ready(Err("Invalid block range specified".into()));
+		}
+
+		if !logged_range {
+			info!("Exporting headers from #{} to #{}", block, last);
+			logged_range = true;
+		}
+
+		match client.header(BlockId::number(block))? {
+			Some(header) => {
+				let justification = if justifications &&
+					block <= client.usage_info().chain.finalized_number
+				{
+					client.justification(&BlockId::number(block))?
+				} else {
+					None
+				};
+
+				write_entry::<B>(&mut output, &header, justification)?;
+			},
+			// Reached end of the chain.
+			None => return Poll::Ready(Ok(())),
+		}
+		if (block % 10000u32.into()).is_zero() {
+			info!("#{}", block);
+		}
+		if block == last {
+			output.flush()?;
+			return Poll::Ready(Ok(()));
+		}
+		block += One::one();
+
+		// Re-schedule the task in order to continue the operation.
+		cx.waker().wake_by_ref();
+		Poll::Pending
+	});
+
+	Box::pin(export)
+}
+
+/// Scan a previous headers export and find the last entry with a valid checksum.
+///
+/// Returns the number of the last valid header together with the length in bytes of the valid
+/// prefix of the input, or `None` if the input doesn't contain any valid entry. Everything past
+/// that prefix is either truncated or corrupted and must be discarded before resuming.
+pub fn last_exported_header<B: BlockT>(
+	mut input: impl Read,
+) -> Result<Option<(NumberFor<B>, u64)>, Error> {
+	let mut last = None;
+	let mut valid_len = 0u64;
+
+	while let Some((header, len)) = read_entry::<B>(&mut input)? {
+		valid_len += len;
+		last = Some((*header.number(), valid_len));
+	}
+
+	Ok(last)
+}
+
+fn write_entry<B: BlockT>(
+	output: &mut impl Write,
+	header: &B::Header,
+	justification: Option<Justification>,
+) -> Result<(), Error> {
+	let payload = (header, justification).encode();
+	output.write_all(&Compact(payload.len() as u32).encode())?;
+	output.write_all(&payload)?;
+	output.write_all(&blake2_256(&payload))?;
+	Ok(())
+}
+
+/// Read one entry, returning the decoded header and the number of bytes consumed.
+///
+/// Returns `None` at the end of the input or at the first truncated or corrupted entry.
+fn read_entry<B: BlockT>(
+	input: &mut impl Read,
+) -> Result<Option<(B::Header, u64)>, Error> {
+	let mut reader = codec::IoReader(&mut *input);
+	let len = match <Compact<u32>>::decode(&mut reader) {
+		Ok(Compact(len)) if (len as usize) <= MAX_ENTRY_LEN => len as usize,
+		_ => return Ok(None),
+	};
+
+	// Allocated as it is read, so that a truncated entry doesn't allocate `len` bytes.
+	let mut payload = Vec::new();
+	let mut checksum = [0u8; CHECKSUM_LEN];
+	match (&mut *input).take(len as u64).read_to_end(&mut payload) {
+		Ok(read) if read == len => (),
+		_ => return Ok(None),
+	}
+	if input.read_exact(&mut checksum).is_err() {
+		return Ok(None);
+	}
+
+	if blake2_256(&payload) != checksum {
+		return Ok(None);
+	}
+
+	let (header, _) = match <(B::Header, Option<Justification>)>::decode(&mut &payload[..]) {
+		Ok(entry) => entry,
+		Err(_) => return Ok(None),
+	};
+
+	let consumed = Compact(len as u32).encode().len() + len + CHECKSUM_LEN;
+	Ok(Some((header, consumed as u64)))
+}
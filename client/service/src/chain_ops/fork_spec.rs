@@ -0,0 +1,120 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::error::Error;
+use sc_chain_spec::ChainSpec;
+use sc_client_api::{StorageProvider, UsageProvider};
+use sp_core::storage::{well_known_keys, ChildInfo, Storage, StorageKey};
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use std::{io::Write, sync::Arc};
+
+/// Write a raw chain spec based on `spec` to `output`, whose genesis is the state at `block`. If
+/// `block` is `None`, the best block will be used.
+///
+/// Unlike [`export_raw_state`](super::export_raw_state), the entries of the main trie are streamed
+/// from the database to `output` instead of being collected first, so that large states don't
+/// need to fit in memory. The child tries are read one at a time.
+///
+/// Returns the number of entries written.
+pub fn fork_spec<B, BA, C>(
+	client: Arc<C>,
+	spec: &dyn ChainSpec,
+	block: Option<BlockId<B>>,
+	mut output: impl Write,
+) -> Result<u64, Error>
+where
+	C: UsageProvider<B> + StorageProvider<B, BA>,
+	B: BlockT,
+	BA: sc_client_api::backend::Backend<B>,
+{
+	let block = block.unwrap_or_else(
+		|| BlockId::Hash(client.usage_info().chain.best_hash)
+	);
+
+	// Everything but the genesis is taken from `spec`.
+	let mut spec = spec.cloned_box();
+	spec.set_storage(Storage::default());
+	let mut fields = match serde_json::from_str(&spec.as_json(true)?) {
+		Ok(serde_json::Value::Object(fields)) => fields,
+		Ok(_) => return Err("Chain spec is not a JSON object".into()),
+		Err(e) => return Err(format!("Error parsing chain spec: {}", e).into()),
+	};
+	fields.remove("genesis");
+
+	let json_err = |e: serde_json::Error| Error::Other(format!("Error writing chain spec: {}", e));
+
+	output.write_all(b"{")?;
+	for (name, value) in &fields {
+		serde_json::to_writer(&mut output, name).map_err(json_err)?;
+		output.write_all(b":")?;
+		serde_json::to_writer(&mut output, value).map_err(json_err)?;
+		output.write_all(b",")?;
+	}
+	output.write_all(b"\"genesis\":{\"raw\":{\"top\":{")?;
+
+	let mut entries = 0;
+	let mut child_roots = Vec::new();
+	for key in client.storage_keys_iter(&block, None, None)? {
+		if key.0.starts_with(well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX) {
+			child_roots.push(key);
+			continue;
+		}
+
+		if let Some(value) = client.storage(&block, &key)? {
+			if entries > 0 {
+				output.write_all(b",")?;
+			}
+			serde_json::to_writer(&mut output, &key).map_err(json_err)?;
+			output.write_all(b":")?;
+			serde_json::to_writer(&mut output, &value).map_err(json_err)?;
+			entries += 1;
+		}
+	}
+	output.write_all(b"},\"childrenDefault\":{")?;
+
+	let empty_key = StorageKey(Vec::new());
+	for (index, root) in child_roots.into_iter().enumerate() {
+		let key = StorageKey(
+			root.0[well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX.len()..].to_vec(),
+		);
+		let child_info = ChildInfo::new_default(&key.0);
+
+		if index > 0 {
+			output.write_all(b",")?;
+		}
+		serde_json::to_writer(&mut output, &key).map_err(json_err)?;
+		output.write_all(b":{")?;
+
+		let mut first = true;
+		for child_key in client.child_storage_keys(&block, &child_info, &empty_key)? {
+			if let Some(value) = client.child_storage(&block, &child_info, &child_key)? {
+				if !first {
+					output.write_all(b",")?;
+				}
+				serde_json::to_writer(&mut output, &child_key).map_err(json_err)?;
+				output.write_all(b":")?;
+				serde_json::to_writer(&mut output, &value).map_err(json_err)?;
+				first = false;
+				entries += 1;
+			}
+		}
+		output.write_all(b"}")?;
+	}
+	output.write_all(b"}}}}")?;
+	output.flush()?;
+
+	Ok(entries)
+}
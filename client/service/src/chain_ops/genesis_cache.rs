@@ -0,0 +1,116 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Genesis state cache.
+//!
+//! Building the genesis state of a chain spec may require running the genesis build of every
+//! runtime module, which is slow for large specs. The cache stores the resulting storage so that
+//! it can be loaded back into the chain spec instead.
+
+use crate::error::Error;
+use crate::chain_ops::genesis_hash;
+use codec::{Decode, Encode};
+use sc_chain_spec::ChainSpec;
+use sp_core::{hashing::blake2_256, storage::{ChildInfo, Storage, StorageChild}};
+use sp_runtime::traits::Block as BlockT;
+
+use std::io::{Read, Write};
+
+/// Content of a genesis cache file.
+#[derive(Encode, Decode)]
+struct GenesisCache {
+	/// Hash of the JSON of the chain spec the cache was built from.
+	spec: [u8; 32],
+	/// SCALE-encoded hash of the genesis block.
+	genesis_hash: Vec<u8>,
+	/// The top-level genesis storage.
+	top: Vec<(Vec<u8>, Vec<u8>)>,
+	/// The default child tries of the genesis storage, by storage key.
+	children_default: Vec<(Vec<u8>, Vec<(Vec<u8>, Vec<u8>)>)>,
+}
+
+/// Hash identifying a chain spec.
+fn spec_hash(spec: &dyn ChainSpec) -> Result<[u8; 32], Error> {
+	Ok(blake2_256(spec.as_json(false).map_err(Error::Other)?.as_bytes()))
+}
+
+/// Build the genesis storage of `spec` and write it to `output`.
+///
+/// Returns the genesis hash.
+pub fn write_genesis_cache<B: BlockT>(
+	spec: &dyn ChainSpec,
+	mut output: impl Write,
+) -> Result<B::Hash, Error> {
+	let storage = spec.as_storage_builder().build_storage().map_err(Error::Other)?;
+	let genesis = genesis_hash::<B>(storage.clone())?;
+
+	let cache = GenesisCache {
+		spec: spec_hash(spec)?,
+		genesis_hash: genesis.encode(),
+		top: storage.top.into_iter().collect(),
+		children_default: storage.children_default.into_iter()
+			.map(|(key, child)| (key, child.data.into_iter().collect()))
+			.collect(),
+	};
+	output.write_all(&cache.encode())?;
+	output.flush()?;
+
+	Ok(genesis)
+}
+
+/// Read a genesis cache written by [`write_genesis_cache`] for `spec`.
+///
+/// Fails if the cache was built from another chain spec, or if the genesis hash of the cached
+/// storage doesn't match the one recorded when building it.
+pub fn read_genesis_cache<B: BlockT>(
+	spec: &dyn ChainSpec,
+	mut input: impl Read,
+) -> Result<Storage, Error> {
+	let mut encoded = Vec::new();
+	input.read_to_end(&mut encoded)?;
+	let cache = GenesisCache::decode(&mut &encoded[..])
+		.map_err(|e| Error::Other(format!("Invalid genesis cache: {}", e)))?;
+
+	if cache.spec != spec_hash(spec)? {
+		return Err(Error::Other(format!(
+			"The genesis cache wasn't built from the {} chain spec",
+			spec.id(),
+		)));
+	}
+
+	let storage = Storage {
+		top: cache.top.into_iter().collect(),
+		children_default: cache.children_default.into_iter()
+			.map(|(key, data)| {
+				let child_info = ChildInfo::new_default(&key);
+				(key, StorageChild { data: data.into_iter().collect(), child_info })
+			})
+			.collect(),
+	};
+
+	let expected = B::Hash::decode(&mut &cache.genesis_hash[..])
+		.map_err(|e| Error::Other(format!("Invalid genesis cache: {}", e)))?;
+	let genesis = genesis_hash::<B>(storage.clone())?;
+	if genesis != expected {
+		return Err(Error::Other(format!(
+			"Corrupted genesis cache: its storage has genesis {}, but {} was expected",
+			genesis,
+			expected,
+		)));
+	}
+
+	Ok(storage)
+}
@@ -31,7 +31,7 @@ use sp_consensus::{
 	import_queue::{IncomingBlock, Link, BlockImportError, BlockImportResult, ImportQueue},
 };
 
-use std::{io::{Read, Seek}, pin::Pin};
+use std::{io::{Read, Seek, SeekFrom}, pin::Pin};
 use std::time::{Duration, Instant};
 use futures_timer::Delay;
 use std::task::Poll;
@@ -60,7 +60,7 @@ pub fn build_spec(spec: &dyn ChainSpec, raw: bool) -> error::Result<String> {
 /// Helper enum that wraps either a binary decoder (from parity-scale-codec), or a JSON decoder
 /// (from serde_json). Implements the Iterator Trait, calling `next()` will decode the next
 /// SignedBlock and return it.
-enum BlockIter<R, B> where
+pub(super) enum BlockIter<R, B> where
 	R: std::io::Read + std::io::Seek,
 {
 	Binary {
@@ -83,7 +83,7 @@ impl<R, B> BlockIter<R, B> where
 	R: Read + Seek + 'static,
 	B: BlockT + MaybeSerializeDeserialize,
 {
-	fn new(input: R, binary: bool) -> Result<Self, String> {
+	pub(super) fn new(input: R, binary: bool) -> Result<Self, String> {
 		if binary {
 			let mut reader = CodecIoReader(input);
 			// If the file is encoded in binary format, it is expected to first specify the number
@@ -114,6 +114,14 @@ impl<R, B> BlockIter<R, B> where
 		}
 	}
 
+	/// Returns the current position in the input, in bytes, if possible.
+	fn position(&mut self) -> Option<u64> {
+		match self {
+			BlockIter::Binary { reader, .. } => reader.0.seek(SeekFrom::Current(0)).ok(),
+			BlockIter::Json { reader, .. } => Some(reader.byte_offset() as u64),
+		}
+	}
+
 	/// Returns the total number of blocks to be imported, if possible.
 	fn num_expected_blocks(&self) -> Option<u64> {
 		match self {
@@ -284,12 +292,19 @@ enum ImportState<R, B> where
 }
 
 /// Starts the process of importing blocks.
+///
+/// If `skip_corrupt` is `true`, blocks that fail to be decoded or imported are logged and
+/// skipped instead of aborting the whole import, and a summary of the skipped blocks is logged
+/// at the end. Note that blocks building on top of a skipped block will fail to be imported as
+/// well, and that a block that can't be decoded may leave the input in a state where the
+/// following blocks can't be decoded either.
 pub fn import_blocks<B, IQ, C>(
 	client: Arc<C>,
 	mut import_queue: IQ,
 	input: impl Read + Seek + Send + 'static,
 	force: bool,
 	binary: bool,
+	skip_corrupt: bool,
 ) -> Pin<Box<dyn Future<Output = Result<(), Error>> + Send>>
 where
 	C: UsageProvider<B> + Send + Sync + 'static,
@@ -298,14 +313,18 @@ where
 {
 	struct WaitLink {
 		imported_blocks: u64,
+		failed_blocks: u64,
 		has_error: bool,
+		skip_failed: bool,
 	}
 
 	impl WaitLink {
-		fn new() -> WaitLink {
+		fn new(skip_failed: bool) -> WaitLink {
 			WaitLink {
 				imported_blocks: 0,
+				failed_blocks: 0,
 				has_error: false,
+				skip_failed,
 			}
 		}
 	}
@@ -322,14 +341,17 @@ where
 			for result in results {
 				if let (Err(err), hash) = result {
 					warn!("There was an error importing block with hash {:?}: {:?}", hash, err);
-					self.has_error = true;
-					break;
+					if !self.skip_failed {
+						self.has_error = true;
+						break;
+					}
+					self.failed_blocks += 1;
 				}
 			}
 		}
 	}
 
-	let mut link = WaitLink::new();
+	let mut link = WaitLink::new(skip_corrupt);
 	let block_iter_res: Result<BlockIter<_, B>, String> = BlockIter::new(input, binary);
 
 	let block_iter = match block_iter_res {
@@ -343,6 +365,8 @@ where
 
 	let mut state = Some(ImportState::Reading{block_iter});
 	let mut speedometer = Speedometer::<B>::new();
+	// Index and position in the input of the blocks that couldn't be decoded.
+	let mut undecodable_blocks = Vec::new();
 
 	// Importing blocks is implemented as a future, because we want the operation to be
 	// interruptible.
@@ -370,7 +394,10 @@ where
 						let read_block_count = block_iter.read_block_count();
 						match block_result {
 							Ok(block) => {
-								if read_block_count - link.imported_blocks >= MAX_PENDING_BLOCKS {
+								let processed_blocks = link.imported_blocks +
+									link.failed_blocks +
+									undecodable_blocks.len() as u64;
+								if read_block_count - processed_blocks >= MAX_PENDING_BLOCKS {
 									// The queue is full, so do not add this block and simply wait
 									// until the queue has made some progress.
 									let delay = Delay::new(Duration::from_millis(DELAY_TIME));
@@ -383,6 +410,17 @@ where
 									state = Some(ImportState::Reading{block_iter});
 								}
 							}
+							Err(e) if skip_corrupt => {
+								let position = block_iter.position();
+								warn!(
+									"Skipping block #{} at byte {}: {}",
+									read_block_count,
+									position.map_or_else(|| "<unknown>".into(), |p| p.to_string()),
+									e,
+								);
+								undecodable_blocks.push((read_block_count, position));
+								state = Some(ImportState::Reading{block_iter});
+							}
 							Err(e) => {
 								return Poll::Ready(
 									Err(Error::Other(
@@ -395,7 +433,11 @@ where
 			},
 			ImportState::WaitingForImportQueueToCatchUp{block_iter, mut delay, block} => {
 				let read_block_count = block_iter.read_block_count();
-				if read_block_count - link.imported_blocks >= MAX_PENDING_BLOCKS {
+				// The blocks that failed or were skipped don't wait in the queue either.
+				let processed_blocks = link.imported_blocks +
+					link.failed_blocks +
+					undecodable_blocks.len() as u64;
+				if read_block_count - processed_blocks >= MAX_PENDING_BLOCKS {
 					// Queue is still full, so wait until there is room to insert our block.
 					match Pin::new(&mut delay).poll(cx) {
 						Poll::Pending => {
@@ -423,12 +465,23 @@ where
 			} => {
 				// All the blocks have been added to the queue, which doesn't mean they 
 				// have all been properly imported.
-				if importing_is_done(num_expected_blocks, read_block_count, link.imported_blocks) {
+				let processed_blocks = link.imported_blocks +
+					link.failed_blocks +
+					undecodable_blocks.len() as u64;
+				if importing_is_done(num_expected_blocks, read_block_count, processed_blocks) {
 					// Importing is done, we can log the result and return.
 					info!(
 						"🎉 Imported {} blocks. Best: #{}",
 						read_block_count, client.usage_info().chain.best_number
 					);
+					if !undecodable_blocks.is_empty() || link.failed_blocks > 0 {
+						warn!(
+							"Skipped {} block(s) that failed to be imported and {} that couldn't be decoded: {:?}",
+							link.failed_blocks,
+							undecodable_blocks.len(),
+							undecodable_blocks,
+						);
+					}
 					return Poll::Ready(Ok(()))
 				} else {
 					// Importing is not done, we still have to wait for the queue to finish.
@@ -16,14 +16,38 @@
 
 //! Chain utilities.
 
+mod apply_extrinsic;
 mod check_block;
+mod check_equivocations;
+mod diff_state;
 mod export_blocks;
+mod export_finality_chain;
+mod export_headers;
 mod export_raw_state;
+mod fork_spec;
+mod genesis_cache;
 mod import_blocks;
+mod read_replica;
 mod revert_chain;
+mod runtime_metadata;
+mod verify_against_export;
+mod verify_events;
+mod verify_genesis;
 
+pub use apply_extrinsic::*;
 pub use check_block::*;
+pub use check_equivocations::*;
+pub use diff_state::*;
 pub use export_blocks::*;
+pub use export_finality_chain::*;
+pub use export_headers::*;
 pub use export_raw_state::*;
+pub use fork_spec::*;
+pub use genesis_cache::*;
 pub use import_blocks::*;
+pub use read_replica::*;
 pub use revert_chain::*;
+pub use runtime_metadata::*;
+pub use verify_against_export::*;
+pub use verify_events::*;
+pub use verify_genesis::*;
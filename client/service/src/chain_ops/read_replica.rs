@@ -0,0 +1,90 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::config::DatabaseConfig;
+use crate::error::Error;
+use crate::chain_ops::genesis_hash;
+use sc_chain_spec::ChainSpec;
+use sp_runtime::traits::Block as BlockT;
+
+use std::path::Path;
+
+/// Build the configuration of the read replica database found under `base_path`, for the chain
+/// defined by `spec`.
+///
+/// The replica is expected to use the same layout and backend as `database`, the one of the
+/// primary node, i.e. to be found in `<base_path>/chains/<chain id>/`. It is opened as a RocksDB
+/// secondary instance, so that it is never written to, which ParityDb doesn't support. Fails if
+/// it doesn't exist, or if its genesis block isn't the one of `spec`, which the primary database
+/// is built from.
+pub fn read_replica_database<B: BlockT>(
+	database: &DatabaseConfig,
+	spec: &dyn ChainSpec,
+	base_path: &Path,
+) -> Result<DatabaseConfig, Error> {
+	let replica_path = |primary_path: &Path| {
+		let mut path = base_path.join("chains").join(spec.id());
+		if let Some(name) = primary_path.file_name() {
+			path.push(name);
+		}
+		path
+	};
+
+	let replica = match database {
+		DatabaseConfig::RocksDb { path, cache_size } => DatabaseConfig::RocksDbSecondary {
+			path: replica_path(path),
+			secondary_path: path.with_file_name("read-replica"),
+			cache_size: *cache_size,
+		},
+		DatabaseConfig::RocksDbSecondary { .. } => return Err(Error::Other(
+			"Already reading from a read replica".into(),
+		)),
+		DatabaseConfig::ParityDb { .. } => return Err(Error::Other(
+			"Cannot use a read replica along with ParityDb, which can't be opened read-only".into(),
+		)),
+		DatabaseConfig::Custom(_) => return Err(Error::Other(
+			"Cannot use a read replica along with a custom database implementation".into(),
+		)),
+	};
+
+	let path = replica.path().expect("Replica databases are on disk; qed");
+	if !path.exists() {
+		return Err(Error::Other(
+			format!("Read replica database {} does not exist", path.display()),
+		));
+	}
+
+	let stored = sc_client_db::read_genesis_hash::<B>(replica.clone())?
+		.ok_or_else(|| Error::Other(format!(
+			"Read replica database {} doesn't contain a genesis block",
+			path.display(),
+		)))?;
+
+	let storage = spec.as_storage_builder().build_storage().map_err(Error::Other)?;
+	let expected = genesis_hash::<B>(storage)?;
+
+	if expected != stored {
+		return Err(Error::Other(format!(
+			"Read replica genesis mismatch: the primary chain has genesis {}, but the replica \
+			database {} contains {}",
+			expected,
+			path.display(),
+			stored,
+		)));
+	}
+
+	Ok(replica)
+}
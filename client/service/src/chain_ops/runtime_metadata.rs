@@ -0,0 +1,65 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+
+use crate::error::Error;
+use codec::Decode;
+use sc_client_api::{CallExecutor, ExecutionStrategy, ExecutorProvider};
+use sp_core::traits::{CallInWasm, MissingHostFunctions};
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use sp_state_machine::BasicExternalities;
+
+const METADATA_METHOD: &str = "Metadata_metadata";
+
+/// Get the SCALE encoded metadata of the on-chain runtime at `at`.
+///
+/// The metadata is always taken from the wasm runtime in `:code`, never from the native one.
+pub fn runtime_metadata<B, C>(client: &C, at: &BlockId<B>) -> Result<Vec<u8>, Error>
+where
+	B: BlockT,
+	C: ExecutorProvider<B>,
+{
+	let encoded = client.executor().call(
+		at,
+		METADATA_METHOD,
+		&[],
+		ExecutionStrategy::AlwaysWasm,
+		None,
+	)?;
+	decode_opaque_metadata(&encoded)
+}
+
+/// Get the SCALE encoded metadata of the given wasm runtime blob.
+///
+/// The blob is executed against empty storage, and fails to load if it imports a host function
+/// that `executor` doesn't provide.
+pub fn wasm_metadata(executor: &impl CallInWasm, code: &[u8]) -> Result<Vec<u8>, Error> {
+	let mut ext = BasicExternalities::default();
+	let encoded = executor.call_in_wasm(
+		code,
+		None,
+		METADATA_METHOD,
+		&[],
+		&mut ext,
+		MissingHostFunctions::Disallow,
+	).map_err(|e| format!("Failed to execute the wasm runtime: {}", e))?;
+	decode_opaque_metadata(&encoded)
+}
+
+fn decode_opaque_metadata(encoded: &[u8]) -> Result<Vec<u8>, Error> {
+	Vec::<u8>::decode(&mut &encoded[..])
+		.map_err(|e| format!("Failed to decode the runtime metadata: {}", e).into())
+}
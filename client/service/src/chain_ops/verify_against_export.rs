@@ -0,0 +1,112 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::error::Error;
+use super::import_blocks::BlockIter;
+use codec::Encode;
+use sc_client_api::BlockBackend;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::generic::BlockId;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, MaybeSerializeDeserialize, NumberFor};
+use std::io::{Read, Seek};
+
+/// The first block of an export that doesn't match the database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportMismatch<B: BlockT> {
+	/// The block isn't in the database.
+	Missing {
+		/// Number of the block.
+		number: NumberFor<B>,
+		/// Hash of the block.
+		hash: B::Hash,
+	},
+	/// The block is in the database, but another block is canonical at its height.
+	NotCanonical {
+		/// Number of the block.
+		number: NumberFor<B>,
+		/// Hash of the block.
+		hash: B::Hash,
+		/// Hash of the canonical block at this height, if any.
+		canonical: Option<B::Hash>,
+	},
+	/// The extrinsics or the justification stored in the database differ from the exported ones.
+	Different {
+		/// Number of the block.
+		number: NumberFor<B>,
+		/// Hash of the block.
+		hash: B::Hash,
+		/// What differs.
+		reason: &'static str,
+	},
+}
+
+/// Summary of the comparison of an export with the database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportVerification<B: BlockT> {
+	/// Number of blocks of the export that match the database.
+	pub matching: u64,
+	/// The first block that didn't match, if any. The comparison stops there.
+	pub mismatch: Option<ExportMismatch<B>>,
+}
+
+/// Read the blocks of an export made by `export-blocks` and check that each of them is in the
+/// database, canonical, and with the same extrinsics and, when the export has one, the same
+/// justification.
+///
+/// Nothing is written to the database.
+pub fn verify_against_export<B, C>(
+	client: &C,
+	input: impl Read + Seek + 'static,
+	binary: bool,
+) -> Result<ExportVerification<B>, Error>
+where
+	B: BlockT + MaybeSerializeDeserialize,
+	C: BlockBackend<B> + HeaderBackend<B>,
+{
+	let mut matching = 0;
+
+	for exported in BlockIter::<_, B>::new(input, binary)? {
+		let exported = exported.map_err(|e| format!("Failed to read block #{}: {}", matching, e))?;
+		let number = *exported.block.header().number();
+		let hash = exported.block.header().hash();
+
+		let stored = match client.block(&BlockId::Hash(hash))? {
+			Some(stored) => stored,
+			None => return Ok(ExportVerification {
+				matching,
+				mismatch: Some(ExportMismatch::Missing { number, hash }),
+			}),
+		};
+
+		let canonical = client.hash(number)?;
+		let mismatch = if canonical != Some(hash) {
+			Some(ExportMismatch::NotCanonical { number, hash, canonical })
+		} else if stored.block.extrinsics().encode() != exported.block.extrinsics().encode() {
+			Some(ExportMismatch::Different { number, hash, reason: "extrinsics" })
+		} else if exported.justification.is_some() && stored.justification != exported.justification {
+			Some(ExportMismatch::Different { number, hash, reason: "justification" })
+		} else {
+			None
+		};
+
+		if mismatch.is_some() {
+			return Ok(ExportVerification { matching, mismatch });
+		}
+		matching += 1;
+	}
+
+	Ok(ExportVerification { matching, mismatch: None })
+}
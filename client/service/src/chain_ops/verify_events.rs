@@ -0,0 +1,100 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::error::Error;
+use sp_api::{ApiExt, Core, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::generic::BlockId;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, NumberFor, One};
+use sp_core::storage::StorageKey;
+use sc_client_api::{BlockBackend, StorageProvider, backend::Backend};
+
+/// The events of a block that differ after re-executing it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventsDivergence<B: BlockT> {
+	/// Number of the block.
+	pub number: NumberFor<B>,
+	/// Hash of the block.
+	pub hash: B::Hash,
+	/// Encoded events stored in the database.
+	pub stored: Option<Vec<u8>>,
+	/// Encoded events produced by the re-execution.
+	pub computed: Option<Vec<u8>>,
+}
+
+/// Re-execute the blocks `from..=to` on top of the state of their parent and compare the value
+/// they write at `events_key` with the one stored in the database.
+///
+/// Nothing is written to the database. The state of the parent of every block must still be
+/// available, so `from` must not be the genesis block and the node must not have pruned it.
+/// Every divergence is reported to `on_divergence` as soon as it is found.
+///
+/// Returns the total number of divergences.
+pub fn verify_events<B, BA, C>(
+	client: &C,
+	backend: &BA,
+	events_key: &StorageKey,
+	from: NumberFor<B>,
+	to: NumberFor<B>,
+	mut on_divergence: impl FnMut(EventsDivergence<B>) -> Result<(), Error>,
+) -> Result<u64, Error>
+where
+	B: BlockT,
+	BA: Backend<B>,
+	C: BlockBackend<B> + HeaderBackend<B> + StorageProvider<B, BA> + ProvideRuntimeApi<B>,
+	C::Api: Core<B, Error = sp_blockchain::Error> + ApiExt<B, StateBackend = BA::State>,
+{
+	if from > to {
+		return Err("Invalid block range specified".into());
+	}
+
+	let mut number = from;
+	let mut divergences = 0;
+
+	loop {
+		let block = client.block(&BlockId::Number(number))?
+			.ok_or_else(|| format!("Block #{} not found", number))?
+			.block;
+		let hash = block.header().hash();
+		let parent_hash = *block.header().parent_hash();
+		let parent = BlockId::Hash(parent_hash);
+
+		let api = client.runtime_api();
+		api.execute_block(&parent, block)?;
+		let state = backend.state_at(parent)?;
+		let changes = api.into_storage_changes(&state, None, parent_hash)
+			.map_err(sp_blockchain::Error::Storage)?;
+
+		let written = changes.main_storage_changes.iter().find(|(key, _)| *key == events_key.0);
+		let computed = match written {
+			Some((_, value)) => value.clone(),
+			None => client.storage(&parent, events_key)?.map(|data| data.0),
+		};
+		let stored = client.storage(&BlockId::Hash(hash), events_key)?.map(|data| data.0);
+
+		if computed != stored {
+			divergences += 1;
+			on_divergence(EventsDivergence { number, hash, stored, computed })?;
+		}
+
+		if number == to {
+			break;
+		}
+		number += One::one();
+	}
+
+	Ok(divergences)
+}
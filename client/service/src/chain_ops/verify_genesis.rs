@@ -0,0 +1,63 @@
+// Copyright 2020 Parity Technologies (UK) Ltd.
+// This file is part of Substrate.
+
+// Substrate is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Substrate is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Substrate.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::error::Error;
+use crate::client::genesis::construct_genesis_block;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, Zero};
+use sp_runtime::{generic::BlockId, BuildStorage};
+use sp_core::storage::Storage;
+use sp_blockchain::HeaderBackend;
+use sc_client_api::{in_mem, backend::{Backend, BlockImportOperation}};
+
+use std::sync::Arc;
+
+/// Compute the genesis hash defined by `spec` and compare it with the genesis block stored by
+/// `client`.
+///
+/// Returns the genesis hash if both match.
+pub fn verify_genesis<B, C>(
+	client: Arc<C>,
+	spec: &dyn BuildStorage,
+) -> Result<B::Hash, Error>
+where
+	C: HeaderBackend<B>,
+	B: BlockT,
+{
+	let stored = client.hash(Zero::zero())?
+		.ok_or_else(|| Error::Other("The database doesn't contain a genesis block".into()))?;
+
+	let storage = spec.build_storage().map_err(Error::Other)?;
+	let expected = genesis_hash::<B>(storage)?;
+
+	if expected != stored {
+		return Err(Error::Other(format!(
+			"Genesis mismatch: the chain spec defines genesis {}, but the database contains {}",
+			expected,
+			stored,
+		)));
+	}
+
+	Ok(expected)
+}
+
+/// Compute the hash of the genesis block whose state is `storage`.
+pub fn genesis_hash<B: BlockT>(storage: Storage) -> Result<B::Hash, Error> {
+	let backend = in_mem::Backend::<B>::new();
+	let mut op = backend.begin_operation()?;
+	backend.begin_state_operation(&mut op, BlockId::Hash(Default::default()))?;
+	let state_root = op.reset_storage(storage)?;
+	Ok(construct_genesis_block::<B>(state_root).header().hash())
+}
@@ -22,13 +22,16 @@ pub use sc_client_db::{Database, PruningMode, DatabaseSettingsSrc as DatabaseCon
 pub use sc_network::Multiaddr;
 pub use sc_network::config::{ExtTransport, MultiaddrWithPeerId, NetworkConfiguration, Role, NodeKeyConfig};
 pub use sc_executor::WasmExecutionMethod;
+pub use sp_consensus::import_queue::{FutureBlockAction, FutureBlockPolicy};
 use sc_client_api::execution_extensions::ExecutionStrategies;
 
-use std::{io, future::Future, path::{PathBuf, Path}, pin::Pin, net::SocketAddr, sync::Arc};
+use std::{io, future::Future, path::{PathBuf, Path}, pin::Pin, net::SocketAddr, sync::Arc, time::Duration};
 pub use sc_transaction_pool::txpool::Options as TransactionPoolOptions;
 use sc_chain_spec::ChainSpec;
+use crate::task_manager::TaskFilter;
 use sp_core::crypto::SecretString;
 pub use sc_telemetry::TelemetryEndpoints;
+pub use sc_informant::OutputFormat as InformantOutputFormat;
 use prometheus_endpoint::Registry;
 #[cfg(not(target_os = "unknown"))]
 use tempfile::TempDir;
@@ -117,6 +120,32 @@ pub struct Configuration {
 	pub base_path: Option<BasePath>,
 	/// Configuration of the output format that the informant uses.
 	pub informant_output_format: sc_informant::OutputFormat,
+	/// Which tasks of the service are spawned. All of them by default.
+	pub task_filter: TaskFilter,
+	/// Maximum number of justifications waiting in the import queue, if any.
+	pub max_pending_justifications: Option<usize>,
+	/// How the import queue handles the blocks whose slot is ahead of the local clock.
+	pub future_block_policy: FutureBlockPolicy,
+	/// Reject the calls to the RPC servers until the node has caught up with the network.
+	pub rpc_wait_for_sync: bool,
+	/// Additional RPC servers, each with its own policy.
+	pub rpc_endpoints: Vec<RpcEndpoint>,
+	/// Maximum number of notifications buffered for a head subscription before it is dropped,
+	/// if any.
+	pub rpc_max_buffered_notifications: Option<usize>,
+	/// Number of peers the node waits for before it starts authoring, if any.
+	pub min_peers_before_authoring: Option<MinPeersBeforeAuthoring>,
+	/// Maximum number of blocks imported per second until the node is synced, if any.
+	pub max_catch_up_import_rate: Option<u32>,
+}
+
+/// Delays the start of the authoring task until the node is connected to enough peers.
+#[derive(Debug, Clone, Copy)]
+pub struct MinPeersBeforeAuthoring {
+	/// Number of connected peers to wait for.
+	pub min_peers: usize,
+	/// Authoring starts anyway once this much time has elapsed.
+	pub timeout: Duration,
 }
 
 /// Type for tasks spawned by the executor.
@@ -206,6 +235,32 @@ pub enum RpcMethods {
 	Unsafe,
 }
 
+/// Transport of an additional RPC server.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum RpcTransport {
+	/// RPC over HTTP.
+	Http,
+	/// RPC over Websockets.
+	Ws,
+}
+
+/// An additional RPC server, listening next to the default ones with its own policy.
+#[derive(Debug, Clone)]
+pub struct RpcEndpoint {
+	/// Transport of the server.
+	pub transport: RpcTransport,
+	/// Binding address.
+	pub address: SocketAddr,
+	/// Whether unsafe RPC methods are exposed.
+	pub methods: RpcMethods,
+	/// Methods served by the server, all of them if `None`.
+	///
+	/// Entries are method names, or prefixes followed by `*`.
+	pub allow: Option<Vec<String>>,
+	/// Methods never served by the server, in the same format as `allow`.
+	pub deny: Vec<String>,
+}
+
 impl Default for RpcMethods {
 	fn default() -> RpcMethods {
 		RpcMethods::Auto
@@ -58,6 +58,15 @@ pub enum Error {
 	#[error("Application")]
 	Application(#[from] Box<dyn std::error::Error + Send + Sync + 'static>),
 
+	#[error("Restart requested")]
+	RestartRequested,
+
+	#[error("Essential task failed.")]
+	EssentialTaskFailed,
+
+	#[error("Disk usage reached the high-water mark")]
+	DiskUsageLimitReached,
+
 	#[error("Other: {0}")]
 	Other(String),
 }
@@ -43,7 +43,7 @@ use parking_lot::Mutex;
 
 use futures::{Future, FutureExt, Stream, StreamExt, stream, compat::*};
 use sc_network::{NetworkStatus, network_state::NetworkState, PeerId};
-use log::{warn, debug, error};
+use log::{info, warn, debug, error};
 use codec::{Encode, Decode};
 use sp_runtime::generic::BlockId;
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
@@ -52,14 +52,16 @@ use sp_utils::{status_sinks, mpsc::{tracing_unbounded, TracingUnboundedReceiver,
 
 pub use self::error::Error;
 pub use self::builder::{
-	new_full_client, new_client, new_full_parts, new_light_parts,
+	new_full_client, new_full_client_with_extensions, new_client, new_full_parts,
+	new_full_parts_with_extensions, new_light_parts,
 	spawn_tasks, build_network, build_offchain_workers,
 	BuildNetworkParams, KeystoreContainer, NetworkStarter, SpawnTasksParams, TFullClient, TLightClient,
 	TFullBackend, TLightBackend, TLightBackendWithHash, TLightClientWithBackend,
 	TFullCallExecutor, TLightCallExecutor, RpcExtensionBuilder, NoopRpcExtensionBuilder,
 };
 pub use config::{
-	BasePath, Configuration, DatabaseConfig, PruningMode, Role, RpcMethods, TaskExecutor, TaskType,
+	BasePath, Configuration, DatabaseConfig, MinPeersBeforeAuthoring, PruningMode, Role, RpcEndpoint,
+	RpcMethods, RpcTransport, TaskExecutor, TaskType,
 };
 pub use sc_chain_spec::{
 	ChainSpec, GenericChainSpec, Properties, RuntimeGenesis, Extension as ChainSpecExtension,
@@ -76,9 +78,14 @@ pub use sc_network::config::{
 	OnDemand, TransactionImport,
 	TransactionImportFuture,
 };
+pub use sc_informant::{InformantBlock, InformantBlockKind};
 pub use sc_tracing::TracingReceiver;
 pub use task_manager::SpawnTaskHandle;
+pub use task_manager::SpawnEssentialTaskHandle;
+pub use task_manager::RestartHandle;
+pub use task_manager::RunningTasks;
 pub use task_manager::TaskManager;
+pub use task_manager::TaskFilter;
 pub use sp_consensus::import_queue::ImportQueue;
 pub use self::client::{LocalCallExecutor, ClientConfig};
 use sc_client_api::{blockchain::HeaderBackend, BlockchainEvents};
@@ -336,6 +343,12 @@ async fn build_network_future<
 							highest_block: network.best_seen_block(),
 						});
 					}
+					sc_rpc::system::Request::PrepareRestart(_) |
+					sc_rpc::system::Request::RotateNodeKey(..) |
+					sc_rpc::system::Request::StopAuthoring(_) |
+					sc_rpc::system::Request::StartAuthoring(_) => {
+						// Answered in `spawn_tasks` before reaching the network worker.
+					}
 				}
 			}
 
@@ -359,6 +372,60 @@ async fn build_network_future<
 	}
 }
 
+/// Wait until `network` is connected to at least `barrier.min_peers` peers, or until
+/// `barrier.timeout` has elapsed, whichever comes first.
+///
+/// Meant to delay the start of the authoring task, so that a node doesn't author blocks in
+/// isolation after a fresh start or a network partition.
+pub async fn wait_for_authoring_peers<B: BlockT, H: sc_network::ExHashT>(
+	network: &sc_network::NetworkService<B, H>,
+	barrier: MinPeersBeforeAuthoring,
+) {
+	let start = wasm_timer::Instant::now();
+	info!("⏳ Waiting for {} peer(s) before authoring", barrier.min_peers);
+
+	loop {
+		let peers = network.num_connected();
+		if peers >= barrier.min_peers {
+			info!("Starting to author with {} peer(s)", peers);
+			return;
+		}
+		if start.elapsed() >= barrier.timeout {
+			warn!(
+				"Starting to author with {} peer(s) after waiting {:?} for {}",
+				peers,
+				barrier.timeout,
+				barrier.min_peers,
+			);
+			return;
+		}
+		futures_timer::Delay::new(Duration::from_secs(1)).await;
+	}
+}
+
+/// Import at most `max_blocks_per_sec` blocks per second through `throttle` until the node has
+/// caught up with the network, according to `network_status`.
+///
+/// The cap is lifted for good once the node is synced, and isn't applied again if it falls
+/// behind later on.
+pub async fn throttle_import_until_synced<B: BlockT>(
+	mut network_status: impl Stream<Item = NetworkStatus<B>> + Unpin,
+	throttle: sp_consensus::import_queue::ImportThrottle,
+	max_blocks_per_sec: u32,
+) {
+	info!("🐢 Importing at most {} block(s) per second until the node is synced", max_blocks_per_sec);
+	throttle.set_max_blocks_per_sec(Some(max_blocks_per_sec));
+
+	while let Some(status) = network_status.next().await {
+		if status.sync_state == sc_network::SyncState::Idle && status.best_seen_block.is_some() {
+			break;
+		}
+	}
+
+	throttle.set_max_blocks_per_sec(None);
+	info!("✅ Node is synced, lifting the block import cap");
+}
+
 #[cfg(not(target_os = "unknown"))]
 // Wrapper for HTTP and WS servers that makes sure they are properly shut down.
 mod waiting {
@@ -402,6 +469,8 @@ fn start_rpc_servers<
 	config: &Configuration,
 	mut gen_handler: H,
 	rpc_metrics: sc_rpc_server::RpcMetrics,
+	syncing: Option<Arc<std::sync::atomic::AtomicBool>>,
+	ws_sessions: sc_rpc_server::WsSessions,
 ) -> Result<Box<dyn std::any::Any + Send + Sync>, error::Error> {
 	fn maybe_start_server<T, F>(address: Option<SocketAddr>, mut start: F) -> Result<Option<T>, io::Error>
 		where F: FnMut(&SocketAddr) -> Result<T, io::Error>,
@@ -430,11 +499,74 @@ fn start_rpc_servers<
 		}
 	}
 
+	fn log_policy(
+		transport_label: &str,
+		address: &SocketAddr,
+		deny_unsafe: sc_rpc::DenyUnsafe,
+		allow: Option<&Vec<String>>,
+		deny: &[String],
+	) {
+		info!(
+			"RPC {} endpoint on {}: unsafe methods {}, allowed methods: {}, denied methods: {}",
+			transport_label,
+			address,
+			match deny_unsafe {
+				sc_rpc::DenyUnsafe::Yes => "denied",
+				sc_rpc::DenyUnsafe::No => "allowed",
+			},
+			allow.map_or_else(|| "all".into(), |allow| allow.join(",")),
+			if deny.is_empty() { "none".into() } else { deny.join(",") },
+		);
+	}
+
+	let middleware = |transport_label| {
+		let middleware = sc_rpc_server::RpcMiddleware::new(rpc_metrics.clone(), transport_label);
+		match &syncing {
+			Some(syncing) => middleware.reject_while_syncing(syncing.clone()),
+			None => middleware,
+		}
+	};
+
+	if let Some(address) = config.rpc_http {
+		log_policy("http", &address, deny_unsafe(&address, &config.rpc_methods), None, &[]);
+	}
+	if let Some(address) = config.rpc_ws {
+		log_policy("ws", &address, deny_unsafe(&address, &config.rpc_methods), None, &[]);
+	}
+
+	let mut endpoints = Vec::<Box<dyn std::any::Any + Send + Sync>>::new();
+	for endpoint in &config.rpc_endpoints {
+		let unsafe_policy = deny_unsafe(&endpoint.address, &endpoint.methods);
+		let filter = sc_rpc_server::MethodFilter::new(endpoint.allow.clone(), endpoint.deny.clone());
+		let transport_label = match endpoint.transport {
+			RpcTransport::Http => "http",
+			RpcTransport::Ws => "ws",
+		};
+		log_policy(transport_label, &endpoint.address, unsafe_policy, endpoint.allow.as_ref(), &endpoint.deny);
+
+		let handler = gen_handler(unsafe_policy, middleware(transport_label).with_method_filter(filter));
+		endpoints.push(match endpoint.transport {
+			RpcTransport::Http => Box::new(waiting::HttpServer(Some(sc_rpc_server::start_http(
+				&endpoint.address,
+				config.rpc_cors.as_ref(),
+				handler,
+			)?))) as Box<dyn std::any::Any + Send + Sync>,
+			RpcTransport::Ws => Box::new(waiting::WsServer(Some(sc_rpc_server::start_ws(
+				&endpoint.address,
+				config.rpc_ws_max_connections,
+				config.rpc_cors.as_ref(),
+				handler,
+				rpc_metrics.clone(),
+				ws_sessions.clone(),
+			)?))),
+		});
+	}
+
 	Ok(Box::new((
 		config.rpc_ipc.as_ref().map(|path| sc_rpc_server::start_ipc(
 			&*path, gen_handler(
 				sc_rpc::DenyUnsafe::No,
-				sc_rpc_server::RpcMiddleware::new(rpc_metrics.clone(), "ipc")
+				middleware("ipc"),
 			)
 		)),
 		maybe_start_server(
@@ -444,7 +576,7 @@ fn start_rpc_servers<
 				config.rpc_cors.as_ref(),
 				gen_handler(
 					deny_unsafe(&address, &config.rpc_methods),
-					sc_rpc_server::RpcMiddleware::new(rpc_metrics.clone(), "http")
+					middleware("http"),
 				),
 			),
 		)?.map(|s| waiting::HttpServer(Some(s))),
@@ -456,10 +588,13 @@ fn start_rpc_servers<
 				config.rpc_cors.as_ref(),
 				gen_handler(
 					deny_unsafe(&address, &config.rpc_methods),
-					sc_rpc_server::RpcMiddleware::new(rpc_metrics.clone(), "ws")
+					middleware("ws"),
 				),
+				rpc_metrics.clone(),
+				ws_sessions.clone(),
 			),
 		)?.map(|s| waiting::WsServer(Some(s))),
+		endpoints,
 	)))
 }
 
@@ -472,6 +607,8 @@ fn start_rpc_servers<
 	_: &Configuration,
 	_: H,
 	_: sc_rpc_server::RpcMetrics,
+	_: Option<Arc<std::sync::atomic::AtomicBool>>,
+	_: sc_rpc_server::WsSessions,
 ) -> Result<Box<dyn std::any::Any + Send + Sync>, error::Error> {
 	Ok(Box::new(()))
 }
@@ -25,7 +25,7 @@ use sc_telemetry::{telemetry, SUBSTRATE_INFO};
 use sp_api::ProvideRuntimeApi;
 use sp_runtime::traits::{NumberFor, Block, SaturatedConversion, UniqueSaturatedInto};
 use sp_transaction_pool::{PoolStatus, MaintainedTransactionPool};
-use sp_utils::metrics::register_globals;
+use sp_utils::metrics::{register_globals, PROCESS_START_TIME_SECONDS};
 use sp_utils::mpsc::TracingUnboundedReceiver;
 use sc_client_api::{ClientInfo, UsageProvider};
 use sc_network::config::Role;
@@ -67,12 +67,12 @@ impl PrometheusMetrics {
 
 		register_globals(registry)?;
 
-		let start_time_since_epoch = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)
-			.unwrap_or_default();
-		register(Gauge::<U64>::new(
-			"process_start_time_seconds",
-			"Number of seconds between the UNIX epoch and the moment the process started",
-		)?, registry)?.set(start_time_since_epoch.as_secs());
+		// Usually set already by the runner of the node, from the start of the process.
+		if PROCESS_START_TIME_SECONDS.get() == 0 {
+			let start_time_since_epoch = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)
+				.unwrap_or_default();
+			PROCESS_START_TIME_SECONDS.set(start_time_since_epoch.as_secs());
+		}
 
 		Ok(Self {
 			// generic internals
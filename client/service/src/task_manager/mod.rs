@@ -15,7 +15,7 @@
 
 use std::{panic, result::Result, pin::Pin};
 use exit_future::Signal;
-use log::{debug, error};
+use log::{debug, error, warn};
 use futures::{
 	Future, FutureExt, StreamExt,
 	future::{select, Either, BoxFuture, join_all, try_join_all, pending},
@@ -29,11 +29,91 @@ use prometheus_endpoint::{
 use sp_utils::mpsc::{TracingUnboundedSender, TracingUnboundedReceiver, tracing_unbounded};
 use tracing_futures::Instrument;
 use crate::{config::{TaskExecutor, TaskType, JoinFuture}, Error};
+use parking_lot::Mutex;
+use std::{collections::{BTreeMap, HashMap, HashSet}, sync::Arc};
 
 mod prometheus_future;
 #[cfg(test)]
 mod tests;
 
+/// Which tasks of the service are spawned, by name.
+///
+/// The names are the ones given to [`SpawnTaskHandle::spawn`], which are also reported in the
+/// `tasks_spawned_total` metric, e.g. `babe-proposer` or `offchain-on-block`. Excluding tasks is
+/// meant for debugging only: the node may not work properly without them.
+#[derive(Clone, Debug)]
+pub enum TaskFilter {
+	/// Spawn every task, the default.
+	All,
+	/// Only spawn the tasks with the given names.
+	Only(HashSet<String>),
+	/// Spawn all the tasks except the ones with the given names.
+	Except(HashSet<String>),
+}
+
+impl Default for TaskFilter {
+	fn default() -> Self {
+		TaskFilter::All
+	}
+}
+
+impl TaskFilter {
+	/// Returns `true` if the task with the given name must be spawned.
+	pub fn is_enabled(&self, name: &str) -> bool {
+		match self {
+			TaskFilter::All => true,
+			TaskFilter::Only(names) => names.contains(name),
+			TaskFilter::Except(names) => !names.contains(name),
+		}
+	}
+}
+
+/// Number of running tasks of a [`TaskManager`], by name.
+type RunningCounts = Arc<Mutex<HashMap<&'static str, usize>>>;
+
+/// The tasks of a [`TaskManager`] and of its children that are still running, see
+/// [`TaskManager::running_tasks`].
+#[derive(Clone, Default)]
+pub struct RunningTasks(Vec<RunningCounts>);
+
+impl RunningTasks {
+	/// The names of the running tasks along with how many of each are running, sorted by name.
+	pub fn get(&self) -> Vec<(&'static str, usize)> {
+		let mut tasks = BTreeMap::new();
+		for counts in &self.0 {
+			for (name, count) in counts.lock().iter() {
+				*tasks.entry(*name).or_insert(0) += count;
+			}
+		}
+		tasks.into_iter().collect()
+	}
+}
+
+/// Counts a task as running until dropped, along with the task.
+struct RunningGuard {
+	running: RunningCounts,
+	name: &'static str,
+}
+
+impl RunningGuard {
+	fn new(running: RunningCounts, name: &'static str) -> Self {
+		*running.lock().entry(name).or_insert(0) += 1;
+		RunningGuard { running, name }
+	}
+}
+
+impl Drop for RunningGuard {
+	fn drop(&mut self) {
+		let mut running = self.running.lock();
+		if let Some(count) = running.get_mut(self.name) {
+			*count -= 1;
+			if *count == 0 {
+				running.remove(self.name);
+			}
+		}
+	}
+}
+
 /// An handle for spawning tasks in the service.
 #[derive(Clone)]
 pub struct SpawnTaskHandle {
@@ -41,6 +121,8 @@ pub struct SpawnTaskHandle {
 	executor: TaskExecutor,
 	metrics: Option<Metrics>,
 	task_notifier: TracingUnboundedSender<JoinFuture>,
+	task_filter: Arc<TaskFilter>,
+	running: RunningCounts,
 }
 
 impl SpawnTaskHandle {
@@ -73,8 +155,14 @@ impl SpawnTaskHandle {
 			return;
 		}
 
+		if !self.task_filter.is_enabled(name) {
+			debug!("Task `{}` is disabled, not spawning it", name);
+			return;
+		}
+
 		let on_exit = self.on_exit.clone();
 		let metrics = self.metrics.clone();
+		let running = RunningGuard::new(self.running.clone(), name);
 
 		// Note that we increase the started counter here and not within the future. This way,
 		// we could properly visualize on Prometheus situations where the spawning doesn't work.
@@ -85,6 +173,7 @@ impl SpawnTaskHandle {
 		}
 
 		let future = async move {
+			let _running = running;
 			if let Some(metrics) = metrics {
 				// Add some wrappers around `task`.
 				let task = {
@@ -197,6 +286,22 @@ impl SpawnEssentialTaskHandle {
 	}
 }
 
+/// A handle for asking the service to shut itself down in preparation of a restart.
+///
+/// The service future returned by [`TaskManager::future`] then completes with
+/// [`Error::RestartRequested`].
+#[derive(Clone)]
+pub struct RestartHandle {
+	restart_tx: TracingUnboundedSender<()>,
+}
+
+impl RestartHandle {
+	/// Request the service to shut down for a restart.
+	pub fn request_restart(&self) {
+		let _ = self.restart_tx.unbounded_send(());
+	}
+}
+
 /// Helper struct to manage background/async tasks in Service.
 pub struct TaskManager {
 	/// A future that resolves when the service has exited, this is useful to
@@ -213,6 +318,10 @@ pub struct TaskManager {
 	essential_failed_tx: TracingUnboundedSender<()>,
 	/// A receiver for spawned essential-tasks concluding.
 	essential_failed_rx: TracingUnboundedReceiver<()>,
+	/// Send a signal when a restart of the service is requested.
+	restart_tx: TracingUnboundedSender<()>,
+	/// A receiver for restart requests.
+	restart_rx: TracingUnboundedReceiver<()>,
 	/// Things to keep alive until the task manager is dropped.
 	keep_alive: Box<dyn std::any::Any + Send + Sync>,
 	/// A sender to a stream of background tasks. This is used for the completion future.
@@ -223,12 +332,18 @@ pub struct TaskManager {
 	/// terminates and gracefully shutdown. Also ends the parent `future()` if a child's essential
 	/// task fails.
 	children: Vec<TaskManager>,
+	/// Which tasks are spawned.
+	task_filter: Arc<TaskFilter>,
+	/// Number of running tasks, by name.
+	running: RunningCounts,
+	/// Describes the best block of the chain of the node.
+	best_block: Option<Box<dyn Fn() -> String + Send + Sync>>,
 }
 
 impl TaskManager {
  	/// If a Prometheus registry is passed, it will be used to report statistics about the
  	/// service tasks.
-	pub(super) fn new(
+	pub fn new(
 		executor: TaskExecutor,
 		prometheus_registry: Option<&Registry>
 	) -> Result<Self, PrometheusError> {
@@ -237,6 +352,9 @@ impl TaskManager {
 		// A side-channel for essential tasks to communicate shutdown.
 		let (essential_failed_tx, essential_failed_rx) = tracing_unbounded("mpsc_essential_tasks");
 
+		// A side-channel for restart requests.
+		let (restart_tx, restart_rx) = tracing_unbounded("mpsc_restart_requests");
+
 		let metrics = prometheus_registry.map(Metrics::register).transpose()?;
 
 		let (task_notifier, background_tasks) = tracing_unbounded("mpsc_background_tasks");
@@ -255,13 +373,35 @@ impl TaskManager {
 			metrics,
 			essential_failed_tx,
 			essential_failed_rx,
+			restart_tx,
+			restart_rx,
 			keep_alive: Box::new(()),
 			task_notifier,
 			completion_future,
 			children: Vec::new(),
+			task_filter: Arc::new(TaskFilter::All),
+			running: Default::default(),
+			best_block: None,
 		})
 	}
 
+	/// Only spawn the tasks allowed by `task_filter`.
+	pub(super) fn with_task_filter(mut self, task_filter: TaskFilter) -> Self {
+		match &task_filter {
+			TaskFilter::All => {},
+			TaskFilter::Only(names) => warn!(
+				"⚠️  Only spawning the tasks: {}. This is meant for debugging only.",
+				names.iter().cloned().collect::<Vec<_>>().join(", "),
+			),
+			TaskFilter::Except(names) => warn!(
+				"⚠️  Not spawning the tasks: {}. This is meant for debugging only.",
+				names.iter().cloned().collect::<Vec<_>>().join(", "),
+			),
+		}
+		self.task_filter = Arc::new(task_filter);
+		self
+	}
+
 	/// Get a handle for spawning tasks.
 	pub fn spawn_handle(&self) -> SpawnTaskHandle {
 		SpawnTaskHandle {
@@ -269,7 +409,19 @@ impl TaskManager {
 			executor: self.executor.clone(),
 			metrics: self.metrics.clone(),
 			task_notifier: self.task_notifier.clone(),
+			task_filter: self.task_filter.clone(),
+			running: self.running.clone(),
+		}
+	}
+
+	/// Get a view of the tasks of this task manager and of its current children that are still
+	/// running, e.g. to report the ones not winding down at shutdown.
+	pub fn running_tasks(&self) -> RunningTasks {
+		let mut running = RunningTasks(vec![self.running.clone()]);
+		for child in &self.children {
+			running.0.extend(child.running_tasks().0);
 		}
+		running
 	}
 
 	/// Get a handle for spawning essential tasks.
@@ -277,6 +429,11 @@ impl TaskManager {
 		SpawnEssentialTaskHandle::new(self.essential_failed_tx.clone(), self.spawn_handle())
 	}
 
+	/// Get a handle for requesting a restart of the service.
+	pub fn restart_handle(&self) -> RestartHandle {
+		RestartHandle { restart_tx: self.restart_tx.clone() }
+	}
+
 	/// Send the signal for termination, prevent new tasks to be created, await for all the existing
 	/// tasks to be finished and drop the object. You can consider this as an async drop.
 	///
@@ -300,7 +457,8 @@ impl TaskManager {
 	}
 
 	/// Return a future that will end with success if the signal to terminate was sent
-	/// (`self.terminate()`) or with an error if an essential task fails.
+	/// (`self.terminate()`) or with an error if an essential task fails or if a restart was
+	/// requested through a [`RestartHandle`].
 	///
 	/// # Warning
 	///
@@ -316,11 +474,13 @@ impl TaskManager {
 					// it must not stop
 					.chain(std::iter::once(pending().boxed()))
 			).fuse();
+			let mut t4 = self.restart_rx.next().fuse();
 
 			futures::select! {
-				_ = t1 => Err(Error::Other("Essential task failed.".into())),
+				_ = t1 => Err(Error::EssentialTaskFailed),
 				_ = t2 => Ok(()),
 				res = t3 => Err(res.map(|_| ()).expect_err("this future never ends; qed")),
+				_ = t4 => Err(Error::RestartRequested),
 			}
 		})
 	}
@@ -345,6 +505,18 @@ impl TaskManager {
 		self.keep_alive = Box::new((to_keep_alive, old));
 	}
 
+	/// Describe the best block of the chain of the node with `best_block`, e.g. `#42 (0x1a2b…)`.
+	///
+	/// Set by [`spawn_tasks`](crate::spawn_tasks), so that the runner of the node can report it.
+	pub fn set_best_block(&mut self, best_block: impl Fn() -> String + Send + Sync + 'static) {
+		self.best_block = Some(Box::new(best_block));
+	}
+
+	/// The best block of the chain of the node, if described by [`TaskManager::set_best_block`].
+	pub fn best_block(&self) -> Option<String> {
+		self.best_block.as_ref().map(|best_block| best_block())
+	}
+
 	/// Register another TaskManager to terminate and gracefully shutdown when the parent
 	/// terminates and gracefully shutdown. Also ends the parent `future()` if a child's essential
 	/// task fails. (But don't end the parent if a child's normal task fails.)
@@ -17,7 +17,8 @@
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
 use crate::config::TaskExecutor;
-use crate::task_manager::TaskManager;
+use crate::error::Error;
+use crate::task_manager::{TaskFilter, TaskManager};
 use futures::{future::FutureExt, pin_mut, select};
 use parking_lot::Mutex;
 use std::any::Any;
@@ -100,6 +101,47 @@ fn ensure_tasks_are_awaited_on_shutdown() {
 	assert_eq!(drop_tester, 0);
 }
 
+#[test]
+fn running_tasks_are_tracked() {
+	let mut runtime = tokio::runtime::Runtime::new().unwrap();
+	let handle = runtime.handle().clone();
+	let task_executor: TaskExecutor = (move |future, _| handle.spawn(future).map(|_| ())).into();
+
+	let mut task_manager = TaskManager::new(task_executor.clone(), None).unwrap();
+	let child = TaskManager::new(task_executor, None).unwrap();
+	let drop_tester = DropTester::new();
+	task_manager.spawn_handle().spawn("task1", run_background_task(drop_tester.new_ref()));
+	task_manager.spawn_handle().spawn("task1", run_background_task(drop_tester.new_ref()));
+	child.spawn_handle().spawn("task2", run_background_task(drop_tester.new_ref()));
+	child.spawn_handle().spawn("task3", async {});
+	task_manager.add_child(child);
+	// allow the tasks to even start
+	runtime.block_on(async { tokio::time::delay_for(Duration::from_secs(1)).await });
+
+	let running_tasks = task_manager.running_tasks();
+	assert_eq!(running_tasks.get(), vec![("task1", 2), ("task2", 1)]);
+	runtime.block_on(task_manager.clean_shutdown());
+	assert!(running_tasks.get().is_empty());
+}
+
+#[test]
+fn ensure_disabled_tasks_are_not_spawned() {
+	let mut runtime = tokio::runtime::Runtime::new().unwrap();
+	let handle = runtime.handle().clone();
+	let task_executor: TaskExecutor = (move |future, _| handle.spawn(future).map(|_| ())).into();
+
+	let task_filter = TaskFilter::Except(vec!["task2".to_string()].into_iter().collect());
+	let task_manager = TaskManager::new(task_executor, None).unwrap().with_task_filter(task_filter);
+	let spawn_handle = task_manager.spawn_handle();
+	let drop_tester = DropTester::new();
+	spawn_handle.spawn("task1", run_background_task(drop_tester.new_ref()));
+	spawn_handle.spawn("task2", run_background_task(drop_tester.new_ref()));
+	// the disabled task has been dropped without being spawned
+	assert_eq!(drop_tester, 1);
+	runtime.block_on(task_manager.clean_shutdown());
+	assert_eq!(drop_tester, 0);
+}
+
 #[test]
 fn ensure_keep_alive_during_shutdown() {
 	let mut runtime = tokio::runtime::Runtime::new().unwrap();
@@ -203,7 +245,8 @@ fn ensure_task_manager_future_ends_with_error_when_essential_task_fails() {
 	runtime.block_on(async { tokio::time::delay_for(Duration::from_secs(1)).await });
 	assert_eq!(drop_tester, 2);
 	spawn_essential_handle.spawn("task3", async { panic!("task failed") });
-	runtime.block_on(task_manager.future()).expect_err("future()'s Result must be Err");
+	let err = runtime.block_on(task_manager.future()).expect_err("future()'s Result must be Err");
+	assert!(matches!(err, Error::EssentialTaskFailed), "{:?}", err);
 	assert_eq!(drop_tester, 2);
 	runtime.block_on(task_manager.clean_shutdown());
 	assert_eq!(drop_tester, 0);
@@ -274,6 +274,14 @@ fn node_config<G: RuntimeGenesis + 'static, E: ChainSpecExtension + Clone + 'sta
 		announce_block: true,
 		base_path: Some(BasePath::new(root)),
 		informant_output_format: Default::default(),
+		task_filter: Default::default(),
+		max_pending_justifications: None,
+		future_block_policy: Default::default(),
+		rpc_wait_for_sync: false,
+		rpc_endpoints: Default::default(),
+		rpc_max_buffered_notifications: None,
+		min_peers_before_authoring: None,
+		max_catch_up_import_rate: None,
 		disable_log_reloading: false,
 	}
 }
@@ -16,8 +16,12 @@
 // You should have received a copy of the GNU General Public License
 // along with this program. If not, see <https://www.gnu.org/licenses/>.
 
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::fmt::{self, Write};
+use std::time::{Duration, Instant};
 use ansi_term::Colour;
+use parking_lot::{Mutex, RwLock};
 use tracing::{span::Attributes, Event, Id, Level, Subscriber};
 use tracing_log::NormalizeEvent;
 use tracing_subscriber::{
@@ -34,6 +38,99 @@ use regex::Regex;
 /// Span name used for the logging prefix. See macro `sc_cli::prefix_logs_with!`
 pub const PREFIX_LOG_SPAN: &str = "substrate-log-prefix";
 
+/// A function rewriting every formatted log line before it is written.
+pub type LogRedactor = Box<dyn for<'a> Fn(&'a str) -> Cow<'a, str> + Send + Sync>;
+
+lazy_static::lazy_static! {
+	static ref LOG_REDACTOR: RwLock<Option<LogRedactor>> = RwLock::new(None);
+}
+
+/// Install a function that rewrites every log line formatted by [`EventFormat`], replacing any
+/// previously installed one.
+///
+/// The redactor sees the complete line (timestamp, level, target and message, including the
+/// color escape codes if colors are enabled) right before it is written. It doesn't see anything
+/// that isn't formatted by [`EventFormat`], e.g. telemetry or profiling traces.
+///
+/// The redactor runs synchronously on the logging thread for every line, so any cost it has is
+/// paid by each log call. Prefer returning `Cow::Borrowed` when nothing needs to be redacted.
+pub fn set_log_redactor(redactor: LogRedactor) {
+	*LOG_REDACTOR.write() = Some(redactor);
+}
+
+/// Settings for collapsing repeated identical log records.
+#[derive(Debug, Clone, Copy)]
+pub struct LogDedup {
+	/// How long a record is remembered after it was first seen.
+	pub window: Duration,
+	/// How many identical records are written within a window before the next ones are
+	/// suppressed.
+	pub threshold: u32,
+}
+
+/// A record seen by [`LogDeduplicator`] within the current window.
+struct SeenRecord {
+	first_seen: Instant,
+	count: u32,
+	suppressed: u64,
+}
+
+/// Collapses the identical log records, i.e. with the same level, target and fields, seen within
+/// a window.
+///
+/// Once more than `threshold` identical records have been seen in a window, the next ones are
+/// dropped. When the window of a record ends, a single line reports how many were dropped.
+pub struct LogDeduplicator {
+	settings: LogDedup,
+	seen: Mutex<(Instant, HashMap<String, SeenRecord>)>,
+}
+
+impl LogDeduplicator {
+	/// Create a new instance.
+	pub fn new(settings: LogDedup) -> Self {
+		Self {
+			settings,
+			seen: Mutex::new((Instant::now(), HashMap::new())),
+		}
+	}
+
+	/// Register a record, returning whether it must be written, together with the summaries of
+	/// the records suppressed in the windows that ended.
+	fn check(&self, record: &str) -> (bool, Vec<(String, u64)>) {
+		let now = Instant::now();
+		let window = self.settings.window;
+		let mut seen = self.seen.lock();
+		let (last_sweep, records) = &mut *seen;
+
+		let mut summaries = Vec::new();
+		if now.duration_since(*last_sweep) >= window {
+			records.retain(|record, seen| {
+				if now.duration_since(seen.first_seen) < window {
+					return true;
+				}
+				if seen.suppressed > 0 {
+					summaries.push((record.clone(), seen.suppressed));
+				}
+				false
+			});
+			*last_sweep = now;
+		}
+
+		let seen = records.entry(record.to_owned()).or_insert_with(|| SeenRecord {
+			first_seen: now,
+			count: 0,
+			suppressed: 0,
+		});
+		seen.count += 1;
+		if seen.count > self.settings.threshold {
+			seen.suppressed += 1;
+			(false, summaries)
+		} else {
+			(true, summaries)
+		}
+	}
+}
+
 /// A writer that may write to `inner_writer` with colors.
 ///
 /// This is used by [`EventFormat`] to kill colors when `enable_color` is `false`.
@@ -70,11 +167,17 @@ impl<'a> MaybeColorWriter<'a> {
 			static ref RE: Regex = Regex::new("\x1b\\[[^m]+m").expect("Error initializing color regex");
 		}
 
+		let redactor = LOG_REDACTOR.read();
+		let buffer = match redactor.as_ref() {
+			Some(redactor) => redactor(&self.buffer),
+			None => Cow::Borrowed(self.buffer.as_str()),
+		};
+
 		if !self.enable_color {
-			let replaced = RE.replace_all(&self.buffer, "");
+			let replaced = RE.replace_all(&buffer, "");
 			self.inner_writer.write_str(&replaced)
 		} else {
-			self.inner_writer.write_str(&self.buffer)
+			self.inner_writer.write_str(&buffer)
 		}
 	}
 }
@@ -85,6 +188,7 @@ pub struct EventFormat<T = SystemTime> {
 	pub display_level: bool,
 	pub display_thread_name: bool,
 	pub enable_color: bool,
+	pub dedup: Option<LogDeduplicator>,
 }
 
 // NOTE: the following code took inspiration from tracing-subscriber
@@ -105,6 +209,22 @@ where
 		let writer = &mut MaybeColorWriter::new(self.enable_color, writer);
 		let normalized_meta = event.normalized_metadata();
 		let meta = normalized_meta.as_ref().unwrap_or_else(|| event.metadata());
+
+		let mut fields = String::new();
+		ctx.format_fields(&mut fields, event)?;
+
+		if let Some(dedup) = &self.dedup {
+			let record = format!("{} {}:{}", meta.level(), meta.target(), fields);
+			let (write, summaries) = dedup.check(&record);
+			for (record, suppressed) in summaries {
+				time::write(&self.timer, writer, self.enable_color)?;
+				writeln!(writer, "Suppressed {} repetition(s) of: {}", suppressed, record)?;
+			}
+			if !write {
+				return writer.write();
+			}
+		}
+
 		time::write(&self.timer, writer, self.enable_color)?;
 
 		if self.display_level {
@@ -140,7 +260,7 @@ where
 		if self.display_target {
 			write!(writer, "{}:", meta.target())?;
 		}
-		ctx.format_fields(writer, event)?;
+		writer.write_str(&fields)?;
 		writeln!(writer)?;
 
 		writer.write()
@@ -315,3 +435,36 @@ mod time {
 		Ok(())
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn dedup_suppresses_repetitions_within_window() {
+		let dedup = LogDeduplicator::new(LogDedup {
+			window: Duration::from_secs(3600),
+			threshold: 2,
+		});
+
+		assert_eq!(dedup.check("WARN sync: storm"), (true, vec![]));
+		assert_eq!(dedup.check("WARN sync: storm"), (true, vec![]));
+		assert_eq!(dedup.check("WARN sync: storm"), (false, vec![]));
+		assert_eq!(dedup.check("WARN sync: other"), (true, vec![]));
+	}
+
+	#[test]
+	fn dedup_reports_suppressed_records_when_window_ends() {
+		let dedup = LogDeduplicator::new(LogDedup {
+			window: Duration::from_millis(10),
+			threshold: 1,
+		});
+
+		assert_eq!(dedup.check("WARN sync: storm"), (true, vec![]));
+		assert_eq!(dedup.check("WARN sync: storm"), (false, vec![]));
+		assert_eq!(dedup.check("WARN sync: storm"), (false, vec![]));
+
+		std::thread::sleep(Duration::from_millis(20));
+		assert_eq!(dedup.check("WARN sync: storm"), (true, vec![("WARN sync: storm".into(), 2)]));
+	}
+}
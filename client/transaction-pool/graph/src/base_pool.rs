@@ -383,6 +383,11 @@ impl<Hash: hash::Hash + Member + Serialize, Ex: std::fmt::Debug> BasePool<Hash,
 		self.future.all()
 	}
 
+	/// Returns an iterator over shared references to the future transactions in the pool.
+	pub fn futures_shared(&self) -> impl Iterator<Item=Arc<Transaction<Hash, Ex>>> + '_ {
+		self.future.all_shared()
+	}
+
 	/// Returns pool transactions given list of hashes.
 	///
 	/// Includes both ready and future pool. For every hash in the `hashes`
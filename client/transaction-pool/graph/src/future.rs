@@ -231,6 +231,11 @@ impl<Hash: hash::Hash + Eq + Clone, Ex> FutureTransactions<Hash, Ex> {
 		self.waiting.values().map(|waiting| &*waiting.transaction)
 	}
 
+	/// Returns shared references to all future transactions.
+	pub fn all_shared(&self) -> impl Iterator<Item=Arc<Transaction<Hash, Ex>>> + '_ {
+		self.waiting.values().map(|waiting| waiting.transaction.clone())
+	}
+
 	/// Removes and returns all future transactions.
 	pub fn clear(&mut self) -> Vec<Arc<Transaction<Hash, Ex>>> {
 		self.wanted_tags.clear();
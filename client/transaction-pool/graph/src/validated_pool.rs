@@ -591,6 +591,11 @@ impl<B: ChainApi> ValidatedPool<B> {
 		self.pool.read().ready()
 	}
 
+	/// Returns the future transactions of the pool.
+	pub fn futures(&self) -> Vec<TransactionFor<B>> {
+		self.pool.read().futures_shared().collect()
+	}
+
 	/// Returns pool status.
 	pub fn status(&self) -> PoolStatus {
 		self.pool.read().status()
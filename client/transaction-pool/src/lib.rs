@@ -328,6 +328,10 @@ impl<PoolApi, Block> TransactionPool for BasicPool<PoolApi, Block>
 	fn ready(&self) -> ReadyIteratorFor<PoolApi> {
 		Box::new(self.pool.validated_pool().ready())
 	}
+
+	fn futures(&self) -> Vec<Arc<Self::InPoolTransaction>> {
+		self.pool.validated_pool().futures()
+	}
 }
 
 impl<Block, Client, Fetcher> LightPool<Block, Client, Fetcher>
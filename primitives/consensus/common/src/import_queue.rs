@@ -38,7 +38,7 @@ use crate::{
 	},
 	metrics::Metrics,
 };
-pub use basic_queue::BasicQueue;
+pub use basic_queue::{BasicQueue, ImportThrottle};
 
 /// A commonly-used Import Queue type.
 ///
@@ -95,6 +95,46 @@ pub trait Verifier<B: BlockT>: Send + Sync {
 	) -> Result<(BlockImportParams<B, ()>, Option<Vec<(CacheKeyId, Vec<u8>)>>), String>;
 }
 
+/// How a verifier handles the blocks whose slot is ahead of the local clock.
+///
+/// Rejecting a block from the future keeps the clock of the node authoritative: such a block is
+/// only imported once it is received again after its slot has started. Tolerating or queueing it
+/// makes the node follow authors whose clock runs ahead, which helps on chains with loose time
+/// synchronization, but lets a misbehaving author build on top of slots that haven't started yet
+/// for the nodes with an accurate clock. A large tolerance also weakens the slot-based ordering
+/// of forks, so keep it as small as the time synchronization of the chain allows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FutureBlockPolicy {
+	/// Number of slots a block may be ahead of the local clock and still be verified right away.
+	pub tolerance: u64,
+	/// What to do with the blocks that are further ahead.
+	pub action: FutureBlockAction,
+}
+
+impl Default for FutureBlockPolicy {
+	fn default() -> Self {
+		FutureBlockPolicy {
+			tolerance: 1,
+			action: FutureBlockAction::Reject,
+		}
+	}
+}
+
+/// What to do with a block that is further ahead than the tolerance of a [`FutureBlockPolicy`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FutureBlockAction {
+	/// Fail the verification of the block.
+	Reject,
+	/// Hold the block until it is within the tolerance, then verify it.
+	///
+	/// The import queue verifies blocks one at a time, so the blocks after it wait as well.
+	/// Blocks that would need to wait for more than `max_wait` slots are rejected.
+	Queue {
+		/// Maximum number of slots to wait for.
+		max_wait: u64,
+	},
+}
+
 /// Blocks import queue API.
 ///
 /// The `import_*` methods can be called in order to send elements for the import queue to verify.
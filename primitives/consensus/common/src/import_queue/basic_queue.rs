@@ -15,7 +15,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::{mem, pin::Pin, time::Duration, marker::PhantomData};
+use std::{
+	mem, pin::Pin, time::Duration, marker::PhantomData, collections::VecDeque,
+	sync::{Arc, atomic::{AtomicU64, AtomicUsize, Ordering}},
+};
 use futures::{prelude::*, task::Context, task::Poll};
 use futures_timer::Delay;
 use sp_runtime::{Justification, traits::{Block as BlockT, Header as HeaderT, NumberFor}};
@@ -42,6 +45,15 @@ pub struct BasicQueue<B: BlockT, Transaction> {
 	block_import_sender: TracingUnboundedSender<worker_messages::ImportBlocks<B>>,
 	/// Results coming from the worker task.
 	result_port: BufferedLinkReceiver<B>,
+	/// Number of justifications sent to the background task and not imported yet.
+	pending_justifications: Arc<AtomicUsize>,
+	/// Maximum number of justifications that can be pending at any time, if any.
+	max_pending_justifications: Option<usize>,
+	/// Justifications received while the maximum was reached, sent once there is room for them.
+	deferred_justifications: VecDeque<worker_messages::ImportJustification<B>>,
+	/// Caps the import rate of the background task.
+	throttle: ImportThrottle,
+	metrics: Option<Metrics>,
 	_phantom: PhantomData<Transaction>,
 }
 
@@ -75,12 +87,17 @@ impl<B: BlockT, Transaction: Send + 'static> BasicQueue<B, Transaction> {
 				.ok()
 		});
 
+		let pending_justifications = Arc::new(AtomicUsize::new(0));
+		let throttle = ImportThrottle::default();
+
 		let (future, justification_sender, block_import_sender) = BlockImportWorker::new(
 			result_sender,
 			verifier,
 			block_import,
 			justification_import,
-			metrics,
+			pending_justifications.clone(),
+			throttle.clone(),
+			metrics.clone(),
 		);
 
 		spawner.spawn_blocking("basic-block-import-worker", future.boxed());
@@ -89,9 +106,51 @@ impl<B: BlockT, Transaction: Send + 'static> BasicQueue<B, Transaction> {
 			justification_sender,
 			block_import_sender,
 			result_port,
+			pending_justifications,
+			max_pending_justifications: None,
+			deferred_justifications: VecDeque::new(),
+			throttle,
+			metrics,
 			_phantom: PhantomData,
 		}
 	}
+
+	/// Get a handle capping the rate at which the background task imports blocks.
+	///
+	/// By default blocks are imported as fast as possible.
+	pub fn import_throttle(&self) -> ImportThrottle {
+		self.throttle.clone()
+	}
+
+	/// Bound the number of justifications waiting to be imported by the background task.
+	///
+	/// Justifications received while the limit is reached are kept aside and counted in the
+	/// `import_queue_justifications_deferred_total` metric. They are handed to the background
+	/// task, in the order they were received, as the pending ones get imported, so none is ever
+	/// lost. `None`, the default, means no limit.
+	pub fn set_max_pending_justifications(&mut self, max: Option<usize>) {
+		self.max_pending_justifications = max;
+	}
+}
+
+/// Handle capping the rate at which a [`BasicQueue`] imports blocks, e.g. so that a node
+/// catching up doesn't starve the other services running on the same machine.
+#[derive(Debug, Clone, Default)]
+pub struct ImportThrottle(Arc<AtomicU64>);
+
+impl ImportThrottle {
+	/// Import at most `max` blocks per second, or as fast as possible if `None`.
+	///
+	/// Takes effect from the next batch of blocks the queue starts importing.
+	pub fn set_max_blocks_per_sec(&self, max: Option<u32>) {
+		let interval = max.filter(|max| *max > 0).map_or(0, |max| 1_000_000 / max as u64);
+		self.0.store(interval, Ordering::Relaxed);
+	}
+
+	/// The minimum interval between the start of two block imports.
+	fn min_block_interval(&self) -> Duration {
+		Duration::from_micros(self.0.load(Ordering::Relaxed))
+	}
 }
 
 impl<B: BlockT, Transaction: Send> ImportQueue<B> for BasicQueue<B, Transaction> {
@@ -119,25 +178,63 @@ impl<B: BlockT, Transaction: Send> ImportQueue<B> for BasicQueue<B, Transaction>
 		number: NumberFor<B>,
 		justification: Justification,
 	) {
-		let res = self.justification_sender.unbounded_send(
-			worker_messages::ImportJustification(who, hash, number, justification),
-		);
-
-		if res.is_err() {
-			log::error!(
+		let justification = worker_messages::ImportJustification(who, hash, number, justification);
+		// The justifications received earlier and deferred are sent first.
+		if !self.deferred_justifications.is_empty() || self.is_full() {
+			trace!(
 				target: "sync",
-				"import_justification: Background import task is no longer alive"
+				"Deferring justification for block #{} ({:?}): too many justifications are pending",
+				number,
+				hash,
 			);
+			if let Some(metrics) = self.metrics.as_ref() {
+				metrics.justifications_deferred.inc();
+			}
+			self.deferred_justifications.push_back(justification);
+			return;
 		}
+
+		self.send_justification(justification);
 	}
 
 	fn poll_actions(&mut self, cx: &mut Context, link: &mut dyn Link<B>) {
+		// The background task reports every justification it imported, so this is polled again
+		// once there is room for the deferred ones.
+		while !self.is_full() {
+			match self.deferred_justifications.pop_front() {
+				Some(justification) => self.send_justification(justification),
+				None => break,
+			}
+		}
+
 		if self.result_port.poll_actions(cx, link).is_err() {
 			log::error!(target: "sync", "poll_actions: Background import task is no longer alive");
 		}
 	}
 }
 
+impl<B: BlockT, Transaction> BasicQueue<B, Transaction> {
+	/// Whether the maximum number of pending justifications is reached.
+	fn is_full(&self) -> bool {
+		let pending = self.pending_justifications.load(Ordering::SeqCst);
+		self.max_pending_justifications.map_or(false, |max| pending >= max)
+	}
+
+	/// Send `justification` to the background task to be imported.
+	fn send_justification(&mut self, justification: worker_messages::ImportJustification<B>) {
+		self.pending_justifications.fetch_add(1, Ordering::SeqCst);
+		let res = self.justification_sender.unbounded_send(justification);
+
+		if res.is_err() {
+			self.pending_justifications.fetch_sub(1, Ordering::SeqCst);
+			log::error!(
+				target: "sync",
+				"import_justification: Background import task is no longer alive"
+			);
+		}
+	}
+}
+
 /// Messages destinated to the background worker.
 mod worker_messages {
 	use super::*;
@@ -149,7 +246,8 @@ mod worker_messages {
 struct BlockImportWorker<B: BlockT, Transaction> {
 	result_sender: BufferedLinkSender<B>,
 	justification_import: Option<BoxJustificationImport<B>>,
-	delay_between_blocks: Duration,
+	pending_justifications: Arc<AtomicUsize>,
+	throttle: ImportThrottle,
 	metrics: Option<Metrics>,
 	_phantom: PhantomData<Transaction>,
 }
@@ -160,6 +258,8 @@ impl<B: BlockT, Transaction: Send> BlockImportWorker<B, Transaction> {
 		verifier: V,
 		block_import: BoxBlockImport<B, Transaction>,
 		justification_import: Option<BoxJustificationImport<B>>,
+		pending_justifications: Arc<AtomicUsize>,
+		throttle: ImportThrottle,
 		metrics: Option<Metrics>,
 	) -> (
 		impl Future<Output = ()> + Send,
@@ -177,7 +277,8 @@ impl<B: BlockT, Transaction: Send> BlockImportWorker<B, Transaction> {
 		let mut worker = BlockImportWorker {
 			result_sender,
 			justification_import,
-			delay_between_blocks: Duration::new(0, 0),
+			pending_justifications,
+			throttle,
 			metrics,
 			_phantom: PhantomData,
 		};
@@ -269,7 +370,9 @@ impl<B: BlockT, Transaction: Send> BlockImportWorker<B, Transaction> {
 		let mut result_sender = self.result_sender.clone();
 		let metrics = self.metrics.clone();
 
-		import_many_blocks(block_import, origin, blocks, verifier, self.delay_between_blocks, metrics)
+		let min_block_interval = self.throttle.min_block_interval();
+
+		import_many_blocks(block_import, origin, blocks, verifier, min_block_interval, metrics)
 			.then(move |(imported, count, results, block_import, verifier)| {
 				result_sender.blocks_processed(imported, count, results);
 				future::ready((block_import, verifier))
@@ -303,6 +406,8 @@ impl<B: BlockT, Transaction: Send> BlockImportWorker<B, Transaction> {
 			metrics.justification_import_time.observe(started.elapsed().as_secs_f64());
 		}
 
+		self.pending_justifications.fetch_sub(1, Ordering::SeqCst);
+
 		self.result_sender.justification_imported(who, &hash, number, success);
 	}
 }
@@ -313,13 +418,14 @@ impl<B: BlockT, Transaction: Send> BlockImportWorker<B, Transaction> {
 /// back in the output once the import is finished.
 ///
 /// The returned `Future` yields at every imported block, which makes the execution more
-/// fine-grained and making it possible to interrupt the process.
+/// fine-grained and making it possible to interrupt the process. Consecutive imports are started
+/// at least `min_block_interval` apart.
 fn import_many_blocks<B: BlockT, V: Verifier<B>, Transaction>(
 	import_handle: BoxBlockImport<B, Transaction>,
 	blocks_origin: BlockOrigin,
 	blocks: Vec<IncomingBlock<B>>,
 	verifier: V,
-	delay_between_blocks: Duration,
+	min_block_interval: Duration,
 	metrics: Option<Metrics>,
 ) -> impl Future<
 	Output = (
@@ -387,6 +493,7 @@ fn import_many_blocks<B: BlockT, V: Verifier<B>, Transaction>(
 
 		let block_number = block.header.as_ref().map(|h| h.number().clone());
 		let block_hash = block.hash;
+		let started = wasm_timer::Instant::now();
 		let import_result = if has_error {
 			Err(BlockImportError::Cancelled)
 		} else {
@@ -415,8 +522,10 @@ fn import_many_blocks<B: BlockT, V: Verifier<B>, Transaction>(
 
 		// Notifies the current task again so that we re-execute this closure again for the next
 		// block.
-		if delay_between_blocks != Duration::new(0, 0) {
-			waiting = Some(Delay::new(delay_between_blocks));
+		if let Some(remaining) = min_block_interval.checked_sub(started.elapsed()) {
+			if remaining != Duration::new(0, 0) {
+				waiting = Some(Delay::new(remaining));
+			}
 		}
 		cx.waker().wake_by_ref();
 		Poll::Pending
@@ -430,7 +539,7 @@ mod tests {
 		import_queue::{CacheKeyId, Verifier},
 		BlockCheckParams, BlockImport, BlockImportParams, ImportResult, JustificationImport,
 	};
-	use futures::{executor::block_on, Future};
+	use futures::{executor::block_on, future::BoxFuture, Future};
 	use sp_test_primitives::{Block, BlockNumber, Extrinsic, Hash, Header};
 	use std::collections::HashMap;
 
@@ -513,12 +622,78 @@ mod tests {
 		}
 	}
 
+	/// Keeps the spawned futures without ever running them.
+	#[derive(Clone, Default)]
+	struct IdleSpawner(Arc<parking_lot::Mutex<Vec<BoxFuture<'static, ()>>>>);
+
+	impl sp_core::traits::SpawnNamed for IdleSpawner {
+		fn spawn_blocking(&self, _: &'static str, future: BoxFuture<'static, ()>) {
+			self.0.lock().push(future);
+		}
+
+		fn spawn(&self, _: &'static str, future: BoxFuture<'static, ()>) {
+			self.0.lock().push(future);
+		}
+	}
+
+	#[test]
+	fn justifications_over_the_limit_are_deferred() {
+		let spawner = IdleSpawner::default();
+		let mut queue = BasicQueue::new((), Box::new(()), Some(Box::new(())), &spawner, None);
+		queue.set_max_pending_justifications(Some(1));
+
+		let hashes = [Hash::random(), Hash::random(), Hash::random()];
+		for hash in &hashes {
+			queue.import_justification(libp2p::PeerId::random(), *hash, 1, Vec::new());
+		}
+		assert_eq!(queue.pending_justifications.load(Ordering::SeqCst), 1);
+		assert_eq!(queue.deferred_justifications.len(), 2);
+
+		// Nothing is sent while the limit is still reached.
+		let mut link = TestLink::default();
+		block_on(futures::future::poll_fn(|cx| {
+			queue.poll_actions(cx, &mut link);
+			Poll::Ready(())
+		}));
+		assert_eq!(queue.deferred_justifications.len(), 2);
+
+		// The background task imported the first justification.
+		queue.pending_justifications.fetch_sub(1, Ordering::SeqCst);
+		block_on(futures::future::poll_fn(|cx| {
+			queue.poll_actions(cx, &mut link);
+			Poll::Ready(())
+		}));
+		assert_eq!(queue.pending_justifications.load(Ordering::SeqCst), 1);
+		assert_eq!(queue.deferred_justifications.len(), 1);
+		assert_eq!(queue.deferred_justifications[0].1, hashes[2]);
+	}
+
+	#[test]
+	fn import_throttle_spaces_out_block_imports() {
+		let throttle = ImportThrottle::default();
+		assert_eq!(throttle.min_block_interval(), Duration::new(0, 0));
+
+		throttle.set_max_blocks_per_sec(Some(4));
+		assert_eq!(throttle.min_block_interval(), Duration::from_millis(250));
+
+		throttle.set_max_blocks_per_sec(None);
+		assert_eq!(throttle.min_block_interval(), Duration::new(0, 0));
+	}
+
 	#[test]
 	fn prioritizes_finality_work_over_block_import() {
 		let (result_sender, mut result_port) = buffered_link::buffered_link();
 
 		let (mut worker, mut finality_sender, mut block_import_sender) =
-			BlockImportWorker::new(result_sender, (), Box::new(()), Some(Box::new(())), None);
+			BlockImportWorker::new(
+				result_sender,
+				(),
+				Box::new(()),
+				Some(Box::new(())),
+				Arc::new(AtomicUsize::new(0)),
+				ImportThrottle::default(),
+				None,
+			);
 
 		let mut import_block = |n| {
 			let header = Header {
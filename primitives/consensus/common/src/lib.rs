@@ -28,7 +28,7 @@
 
 #[macro_use] extern crate log;
 
-use std::sync::Arc;
+use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
 use std::time::Duration;
 
 use sp_runtime::{
@@ -263,6 +263,57 @@ impl<Block: BlockT> CanAuthorWith<Block> for NeverCanAuthor {
 	}
 }
 
+/// Error returned by [`PausableAuthoring`] while authoring is paused.
+pub const AUTHORING_PAUSED: &str = "Authoring is paused.";
+
+/// Switch to pause and resume the authoring of a running node, e.g. before the maintenance of a
+/// validator.
+///
+/// Clones share the same state.
+#[derive(Clone, Default)]
+pub struct AuthoringSwitch(Arc<AtomicBool>);
+
+impl AuthoringSwitch {
+	/// Pause authoring. Returns `false` if it was already paused.
+	pub fn pause(&self) -> bool {
+		!self.0.swap(true, Ordering::SeqCst)
+	}
+
+	/// Resume authoring. Returns `false` if it wasn't paused.
+	pub fn resume(&self) -> bool {
+		self.0.swap(false, Ordering::SeqCst)
+	}
+
+	/// Whether authoring is paused.
+	pub fn is_paused(&self) -> bool {
+		self.0.load(Ordering::SeqCst)
+	}
+}
+
+/// Can author unless an [`AuthoringSwitch`] is paused, in which case [`AUTHORING_PAUSED`] is
+/// returned. Otherwise defers to `inner`.
+#[derive(Clone)]
+pub struct PausableAuthoring<T> {
+	inner: T,
+	switch: AuthoringSwitch,
+}
+
+impl<T> PausableAuthoring<T> {
+	/// Creates a new instance of `Self`.
+	pub fn new(inner: T, switch: AuthoringSwitch) -> Self {
+		Self { inner, switch }
+	}
+}
+
+impl<T: CanAuthorWith<Block>, Block: BlockT> CanAuthorWith<Block> for PausableAuthoring<T> {
+	fn can_author_with(&self, at: &BlockId<Block>) -> Result<(), String> {
+		if self.switch.is_paused() {
+			return Err(AUTHORING_PAUSED.to_string());
+		}
+		self.inner.can_author_with(at)
+	}
+}
+
 /// A type from which a slot duration can be obtained.
 pub trait SlotData {
 	/// Gets the slot duration.
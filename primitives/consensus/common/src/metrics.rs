@@ -17,7 +17,8 @@
 //! Metering tools for consensus
 
 use prometheus_endpoint::{
-	register, U64, Registry, PrometheusError, Opts, CounterVec, Histogram, HistogramVec, HistogramOpts
+	register, U64, Registry, PrometheusError, Opts, Counter, CounterVec, Histogram, HistogramVec,
+	HistogramOpts,
 };
 
 use sp_runtime::traits::{Block as BlockT, NumberFor};
@@ -31,6 +32,7 @@ pub(crate) struct Metrics {
 	pub block_verification_time: HistogramVec,
 	pub block_verification_and_import_time: Histogram,
 	pub justification_import_time: Histogram,
+	pub justifications_deferred: Counter<U64>,
 }
 
 impl Metrics {
@@ -71,6 +73,13 @@ impl Metrics {
 				)?,
 				registry,
 			)?,
+			justifications_deferred: register(
+				Counter::new(
+					"import_queue_justifications_deferred_total",
+					"Justifications deferred because too many were pending import",
+				)?,
+				registry,
+			)?,
 		})
 	}
 
@@ -1290,14 +1290,38 @@ mod allocator_impl {
 	}
 }
 
+/// The hook reporting the panics of the runtime, see [`set_panic_hook`].
+#[cfg(all(not(feature = "disable_panic_handler"), not(feature = "std")))]
+static mut PANIC_HOOK: Option<fn(&core::panic::PanicInfo)> = None;
+
+/// Report the panics of the runtime with `hook` instead of logging them, e.g. to call a host
+/// function of its own.
+///
+/// The default panic handler still aborts the execution once `hook` returns. Runtimes replacing
+/// the handler altogether use the `disable_panic_handler` feature instead.
+#[cfg(all(not(feature = "disable_panic_handler"), not(feature = "std")))]
+pub fn set_panic_hook(hook: fn(&core::panic::PanicInfo)) {
+	// The runtime is single threaded.
+	unsafe {
+		PANIC_HOOK = Some(hook);
+	}
+}
+
 /// A default panic handler for WASM environment.
+///
+/// The panic is logged, or reported by the hook of [`set_panic_hook`] if any.
 #[cfg(all(not(feature = "disable_panic_handler"), not(feature = "std")))]
 #[panic_handler]
 #[no_mangle]
 pub fn panic(info: &core::panic::PanicInfo) -> ! {
 	unsafe {
-		let message = sp_std::alloc::format!("{}", info);
-		logging::log(LogLevel::Error, "runtime", message.as_bytes());
+		match PANIC_HOOK {
+			Some(hook) => hook(info),
+			None => {
+				let message = sp_std::alloc::format!("{}", info);
+				logging::log(LogLevel::Error, "runtime", message.as_bytes());
+			},
+		}
 		core::arch::wasm32::unreachable();
 	}
 }
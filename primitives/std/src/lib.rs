@@ -90,15 +90,39 @@ impl Writer {
 
 /// Prelude of common useful imports.
 ///
-/// This should include only things which are in the normal std prelude.
+/// This should include only things which are in the normal std prelude, along with the
+/// collections commonly used by runtime code, so that they come from the same path with and
+/// without `std`.
+///
+/// # Example
+///
+/// ```
+/// use sp_std::prelude::*;
+///
+/// fn describe(value: u32) -> String {
+///     if value == 0 {
+///         "nothing".to_string()
+///     } else {
+///         format!("{} item(s)", value)
+///     }
+/// }
+///
+/// assert_eq!(describe(2), "2 item(s)");
+/// ```
 pub mod prelude {
 	pub use crate::vec::Vec;
 	pub use crate::boxed::Box;
+	pub use crate::string::{String, ToString};
+	pub use crate::borrow::Cow;
+	pub use crate::collections::btree_map::BTreeMap;
+	pub use crate::collections::btree_set::BTreeSet;
 	pub use crate::cmp::{Eq, PartialEq, Reverse};
 	pub use crate::clone::Clone;
 
-	// Re-export `vec!` macro here, but not in `std` mode, since
-	// std's prelude already brings `vec!` into the scope.
+	// Re-export `vec!` and `format!` macros here, but not in `std` mode, since
+	// std's prelude already brings them into the scope.
 	#[cfg(not(feature = "std"))]
 	pub use crate::vec;
+	#[cfg(not(feature = "std"))]
+	pub use crate::alloc::format;
 }
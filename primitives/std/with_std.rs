@@ -37,6 +37,7 @@ pub use std::sync;
 pub use std::result;
 pub use std::slice;
 pub use std::str;
+pub use std::string;
 pub use std::vec;
 
 pub mod collections {
@@ -78,6 +78,39 @@ impl TimestampInherentData for InherentData {
 	}
 }
 
+/// Milliseconds added to the clock of the node, see [`set_clock_offset`].
+#[cfg(feature = "std")]
+static CLOCK_OFFSET_MS: std::sync::atomic::AtomicI64 = std::sync::atomic::AtomicI64::new(0);
+
+/// Shift the clock used by the timestamp inherent and the slots by `offset_ms` milliseconds, to
+/// simulate a node whose clock is ahead of, or behind, the other ones.
+///
+/// Meant for testing time-dependent consensus behavior only.
+#[cfg(feature = "std")]
+pub fn set_clock_offset(offset_ms: i64) {
+	CLOCK_OFFSET_MS.store(offset_ms, std::sync::atomic::Ordering::Relaxed);
+}
+
+/// The offset set by [`set_clock_offset`], in milliseconds.
+#[cfg(feature = "std")]
+pub fn clock_offset() -> i64 {
+	CLOCK_OFFSET_MS.load(std::sync::atomic::Ordering::Relaxed)
+}
+
+/// Shift `since_epoch` by the offset set by [`set_clock_offset`], saturating at the unix epoch.
+#[cfg(feature = "std")]
+pub fn with_clock_offset(since_epoch: std::time::Duration) -> std::time::Duration {
+	use std::time::Duration;
+
+	let offset = clock_offset();
+	if offset >= 0 {
+		since_epoch + Duration::from_millis(offset as u64)
+	} else {
+		since_epoch.checked_sub(Duration::from_millis(offset.wrapping_neg() as u64))
+			.unwrap_or_default()
+	}
+}
+
 /// Provide duration since unix epoch in millisecond for timestamp inherent.
 #[cfg(feature = "std")]
 pub struct InherentDataProvider;
@@ -99,7 +132,7 @@ impl ProvideInherentData for InherentDataProvider {
 			.map_err(|_| {
 				"Current time is before unix epoch".into()
 			}).and_then(|d| {
-				let duration: InherentType = d.as_millis() as u64;
+				let duration: InherentType = with_clock_offset(d).as_millis() as u64;
 				inherent_data.put_data(INHERENT_IDENTIFIER, &duration)
 			})
 	}
@@ -20,12 +20,10 @@
 use lazy_static::lazy_static;
 use prometheus::{
 	Registry, Error as PrometheusError,
-	core::{ AtomicU64, GenericGauge, GenericCounter },
+	core::{ AtomicU64, GenericGauge, GenericCounter, GenericCounterVec },
+	Opts,
 };
 
-#[cfg(feature = "metered")]
-use prometheus::{core::GenericCounterVec, Opts};
-
 
 lazy_static! {
 	pub static ref TOKIO_THREADS_TOTAL: GenericCounter<AtomicU64> = GenericCounter::new(
@@ -35,6 +33,28 @@ lazy_static! {
 	pub static ref TOKIO_THREADS_ALIVE: GenericGauge<AtomicU64> = GenericGauge::new(
 		"tokio_threads_alive", "Number of threads alive right now"
 	).expect("Creating of statics doesn't fail. qed");
+
+	pub static ref TOKIO_TASKS_ACTIVE: GenericGauge<AtomicU64> = GenericGauge::new(
+		"tokio_tasks_active", "Number of tasks spawned and not finished yet"
+	).expect("Creating of statics doesn't fail. qed");
+
+	pub static ref TOKIO_BLOCKING_TASKS_QUEUED: GenericGauge<AtomicU64> = GenericGauge::new(
+		"tokio_blocking_tasks_queued", "Number of blocking tasks waiting for a thread"
+	).expect("Creating of statics doesn't fail. qed");
+
+	pub static ref PROCESS_START_TIME_SECONDS: GenericGauge<AtomicU64> = GenericGauge::new(
+		"process_start_time_seconds", "Start time of the process since unix epoch in seconds"
+	).expect("Creating of statics doesn't fail. qed");
+
+	pub static ref RPC_SUBSCRIPTIONS_DROPPED_TOTAL: GenericCounter<AtomicU64> = GenericCounter::new(
+		"rpc_subscriptions_dropped_total",
+		"Total number of RPC subscriptions dropped for buffering too many notifications"
+	).expect("Creating of statics doesn't fail. qed");
+
+	pub static ref PEER_DISCONNECTS_TOTAL: GenericCounterVec<AtomicU64> = GenericCounterVec::new(
+		Opts::new("peer_disconnects_total", "Total number of connections to peers closed, by reason"),
+		&["reason"]
+	).expect("Creating of statics doesn't fail. qed");
 }
 
 #[cfg(feature = "metered")]
@@ -51,6 +71,11 @@ lazy_static! {
 pub fn register_globals(registry: &Registry) -> Result<(), PrometheusError> {
 	registry.register(Box::new(TOKIO_THREADS_ALIVE.clone()))?;
 	registry.register(Box::new(TOKIO_THREADS_TOTAL.clone()))?;
+	registry.register(Box::new(TOKIO_TASKS_ACTIVE.clone()))?;
+	registry.register(Box::new(TOKIO_BLOCKING_TASKS_QUEUED.clone()))?;
+	registry.register(Box::new(PROCESS_START_TIME_SECONDS.clone()))?;
+	registry.register(Box::new(PEER_DISCONNECTS_TOTAL.clone()))?;
+	registry.register(Box::new(RPC_SUBSCRIPTIONS_DROPPED_TOTAL.clone()))?;
 
 	#[cfg(feature = "metered")]
 	registry.register(Box::new(UNBOUNDED_CHANNELS_COUNTER.clone()))?;
@@ -104,7 +104,18 @@ where
 		base_path: None,
 		informant_output_format: sc_informant::OutputFormat {
 			enable_color: false,
+			enable_emoji: true,
+			block_formatter: None,
+			import_summary_interval: None,
 		},
+		task_filter: Default::default(),
+		max_pending_justifications: None,
+		future_block_policy: Default::default(),
+		rpc_wait_for_sync: false,
+		rpc_endpoints: Default::default(),
+		rpc_max_buffered_notifications: None,
+		min_peers_before_authoring: None,
+		max_catch_up_import_rate: None,
 		disable_log_reloading: false,
 	};
 
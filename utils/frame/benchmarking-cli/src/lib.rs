@@ -22,7 +22,7 @@ use sc_cli::{ExecutionStrategy, WasmExecutionMethod};
 use std::fmt::Debug;
 
 /// The `benchmark` command used to benchmark FRAME Pallets.
-#[derive(Debug, structopt::StructOpt)]
+#[derive(Debug, Clone, structopt::StructOpt)]
 pub struct BenchmarkCmd {
 	/// Select a FRAME Pallet to benchmark, or `*` for all (in which case `extrinsic` must be `*`).
 	#[structopt(short, long)]